@@ -3,12 +3,14 @@
 //! 演示两种对话框类型:
 //! - 普通文本对话框 (不可取消)
 //! - 可取消对话框 (带回调和用户数据)
+//! - 确认 / 输入 / 选择对话框 (按 key 轮询结果)
+//! - 进度对话框 (后台线程通过 handle 更新)
 //!
 //! 运行方式:
 //! cargo run -p egui_kit --example dialog_demo
 
 use eframe::egui;
-use egui_kit::{Dialog, setup_theme, ThemeName};
+use egui_kit::{Dialog, DialogResult, setup_theme, ThemeName};
 use std::sync::{
     atomic::{AtomicU32, Ordering},
     Arc,
@@ -194,6 +196,84 @@ impl eframe::App for DemoApp {
                 }
             });
 
+            ui.add_space(10.0);
+
+            // ==================== 确认 / 输入 / 选择 ====================
+            ui.label("4. Confirm / Prompt / Choice (Enter = primary, Escape = cancel)");
+            ui.horizontal(|ui| {
+                if ui.button("Confirm").clicked() {
+                    Dialog::show_confirm(ctx, "confirm", "Save changes?", "Your changes will be written to disk.");
+                }
+
+                if ui.button("Delete…").clicked() {
+                    Dialog::show_confirm_danger(
+                        ctx,
+                        "delete",
+                        "Delete project?",
+                        "This cannot be undone.",
+                        "Delete",
+                    );
+                }
+
+                if ui.button("Rename…").clicked() {
+                    Dialog::show_prompt_with_validation(
+                        ctx,
+                        "rename",
+                        "Rename",
+                        "New name:",
+                        "untitled",
+                        |name| {
+                            if name.trim().is_empty() {
+                                Err("Name must not be empty".to_owned())
+                            } else {
+                                Ok(())
+                            }
+                        },
+                    );
+                }
+
+                if ui.button("Choose…").clicked() {
+                    Dialog::show_choice(
+                        ctx,
+                        "export",
+                        "Export",
+                        "Choose a format:",
+                        ["PNG", "SVG", "PDF"],
+                    );
+                }
+            });
+
+            ui.add_space(10.0);
+
+            // ==================== 进度 ====================
+            ui.label("5. Progress Dialog (updated from a background thread)");
+            if ui.button("Import").clicked() {
+                let handle = Dialog::show_progress(ctx, "import", "Importing files");
+                std::thread::spawn(move || {
+                    let files = 40;
+                    for i in 0..files {
+                        if handle.is_cancelled() {
+                            return;
+                        }
+                        handle.update(i as f32 / files as f32, format!("Reading file_{i:03}.csv"));
+                        std::thread::sleep(std::time::Duration::from_millis(150));
+                    }
+                    handle.finish();
+                });
+            }
+
+            for key in ["confirm", "delete", "rename", "export", "import"] {
+                if let Some(result) = Dialog::take_result(ctx, key) {
+                    let text = match result {
+                        DialogResult::Confirmed => "confirmed".to_owned(),
+                        DialogResult::Cancelled => "cancelled".to_owned(),
+                        DialogResult::Text(text) => format!("text {text:?}"),
+                        DialogResult::Choice(index) => format!("choice #{index}"),
+                    };
+                    self.log(format!("{key}: {text}"));
+                }
+            }
+
             ui.add_space(20.0);
 
             // ==================== 控制按钮 ====================
@@ -10,9 +10,10 @@ use egui_kit::components::list_item::ListItemContentButtonsExt as _;
 use egui_kit::components::notifications::NotificationUi;
 use egui_kit::extensions::egui_ext::OnResponseExt as _;
 use egui_kit::{
-    CommandPalette, CommandPaletteAction, CommandPaletteUrl, ContextExt as _, DesignTokens,
-    UICommand, UICommandSender, UiExt as _, components::Help, components::IconText,
-    components::basic::icon, list_item,
+    CommandPalette, CommandPaletteAction, CommandPaletteUrl, CommandRegistry, ContextExt as _,
+    DesignTokens, Menu, MenuBar, PaletteItem, PaletteProvider, PaletteResults, UICommand,
+    UICommandSender, UiExt as _, components::Help, components::IconText, components::basic::icon,
+    list_item,
 };
 
 /// Sender that queues up the execution of a command.
@@ -45,7 +46,9 @@ fn command_channel() -> (CommandSender, CommandReceiver) {
 }
 
 fn main() -> eframe::Result {
-    // Simple logging to console (no logger initialization needed)
+    // With the `logger` feature, log records are also shown as notifications.
+    #[cfg(feature = "logger")]
+    egui_kit::utils::logger::init_logger().ok();
 
     let fullsize_content = egui_kit::fullsize_content(os::OperatingSystem::default());
     let native_options = eframe::NativeOptions {
@@ -75,7 +78,6 @@ fn main() -> eframe::Result {
 pub struct ExampleApp {
     notifications: NotificationUi,
 
-    // Removed: text_log_rx (ChannelLogger not available)
     tree: egui_tiles::Tree<Tab>,
 
     /// regular modal
@@ -84,6 +86,13 @@ pub struct ExampleApp {
     /// modal with full span mode
     full_span_modal_handler: egui_kit::components::modal::ModalHandler,
 
+    /// modal opened from within the regular modal
+    nested_modal_handler: egui_kit::components::modal::ModalHandler,
+
+    /// multi-step modal
+    wizard: egui_kit::Wizard<ProjectSetup>,
+    project_setup: ProjectSetup,
+
     show_left_panel: bool,
     show_right_panel: bool,
     show_bottom_panel: bool,
@@ -95,6 +104,7 @@ pub struct ExampleApp {
     filter_state: FilterState,
 
     cmd_palette: CommandPalette,
+    command_registry: CommandRegistry<UICommand>,
 
     /// Commands to run at the end of the frame.
     pub command_sender: CommandSender,
@@ -104,19 +114,20 @@ pub struct ExampleApp {
 
 impl ExampleApp {
     fn new(ctx: egui::Context) -> Self {
-        // Removed: ChannelLogger initialization (not available in standard log crate)
-        // Log messages will be printed to console via env_logger
-
         let tree = egui_tiles::Tree::new_tabs("my_tree", vec![1, 2, 3]);
 
         let (command_sender, command_receiver) = command_channel();
 
+        let command_registry = UICommand::registry(ctx.os());
+
         Self {
-            notifications: NotificationUi::new(ctx),
-            // Removed: text_log_rx
+            notifications: notification_ui(ctx),
             tree,
             modal_handler: Default::default(),
             full_span_modal_handler: Default::default(),
+            nested_modal_handler: Default::default(),
+            wizard: project_setup_wizard(),
+            project_setup: ProjectSetup::default(),
 
             show_left_panel: true,
             show_right_panel: true,
@@ -129,15 +140,30 @@ impl ExampleApp {
             filter_state: FilterState::default(),
 
             cmd_palette: CommandPalette::default(),
+            command_registry,
             command_sender,
             command_receiver,
             latest_cmd: Default::default(),
         }
     }
+}
 
-    // Removed: show_text_logs_as_notifications() method
-    // (ChannelLogger not available in standard log crate)
-    // Log messages from the "Log info/warn/error" buttons will be printed to console
+/// Notifications for warnings from everywhere and info from this example.
+///
+/// The history is kept across restarts.
+fn notification_ui(ctx: egui::Context) -> NotificationUi {
+    #[allow(unused_mut)]
+    let mut notifications = NotificationUi::new(ctx)
+        .with_log_filter(
+            egui_kit::components::notifications::LogFilter::default()
+                .target(module_path!(), log::LevelFilter::Info),
+        )
+        .with_persistence(Default::default());
+
+    #[cfg(feature = "logger")]
+    notifications.receive_kit_logs();
+
+    notifications
 }
 
 impl eframe::App for ExampleApp {
@@ -152,10 +178,15 @@ impl eframe::App for ExampleApp {
     fn update(&mut self, egui_ctx: &egui::Context, _frame: &mut eframe::Frame) {
         let tokens = egui_ctx.tokens();
 
-        // Removed: show_text_logs_as_notifications() call
-
         self.top_bar(_frame, egui_ctx);
 
+        for action in egui_kit::Banners::top_panel(egui_ctx) {
+            if action.action == "retry" {
+                egui_kit::Banners::remove(egui_ctx, &action.banner);
+                log::info!("Reconnected");
+            }
+        }
+
         egui::TopBottomPanel::bottom("bottom_panel")
             .frame(egui_ctx.tokens().bottom_panel_frame())
             .show_animated(egui_ctx, self.show_bottom_panel, |ui| {
@@ -223,9 +254,49 @@ impl eframe::App for ExampleApp {
             self.modal_handler.ui(
                 ui.ctx(),
                 || egui_kit::components::modal::ModalWrapper::new("Modal window"),
-                |ui| ui.label("This is a modal window."),
+                |ui| {
+                    ui.label("This is a modal window.");
+                    if ui.button("Open nested modal").clicked() {
+                        self.nested_modal_handler.open();
+                    }
+                },
             );
 
+            self.nested_modal_handler.ui(
+                ui.ctx(),
+                || egui_kit::components::modal::ModalWrapper::new("Nested modal"),
+                |ui| ui.label("Escape closes only this modal."),
+            );
+
+            // ---
+
+            if ui.button("Go offline").clicked() {
+                egui_kit::Banners::show(
+                    ui.ctx(),
+                    egui_kit::Banner::warning("offline", "You are offline")
+                        .details("Changes are saved locally until the connection is back.")
+                        .with_action("retry", "Retry"),
+                );
+                egui_kit::Banners::show(
+                    ui.ctx(),
+                    egui_kit::Banner::info("welcome", "Welcome to the egui_kit example")
+                        .remember_dismissal(true),
+                );
+            }
+
+            // ---
+
+            if ui.button("Open wizard").clicked() {
+                self.project_setup = ProjectSetup::default();
+                self.wizard.open();
+            }
+
+            if let Some(egui_kit::WizardOutcome::Finished) =
+                self.wizard.ui(ui.ctx(), &mut self.project_setup)
+            {
+                self.latest_cmd = format!("Created project {:?}", self.project_setup.name);
+            }
+
             // ---
 
             if ui.button("Open full span modal").clicked() {
@@ -373,12 +444,31 @@ impl eframe::App for ExampleApp {
                 tabs_ui(ui, &mut self.tree);
             });
 
-        if let Some(cmd) = self.cmd_palette.show(egui_ctx, &parse_url) {
+        let mut tab_provider = TabProvider {
+            tabs: self
+                .tree
+                .tiles
+                .tiles()
+                .filter_map(|tile| match tile {
+                    egui_tiles::Tile::Pane(tab) => Some(*tab),
+                    egui_tiles::Tile::Container(_) => None,
+                })
+                .collect(),
+        };
+        if let Some(cmd) = self.cmd_palette.show_with_providers(
+            egui_ctx,
+            &self.command_registry,
+            &mut [&mut tab_provider],
+            &parse_url,
+        ) {
             match cmd {
-                CommandPaletteAction::UiCommand(cmd) => self.command_sender.send_ui(cmd),
+                CommandPaletteAction::Command(cmd) => self.command_sender.send_ui(cmd),
                 CommandPaletteAction::OpenUrl(url) => {
                     egui_ctx.open_url(egui::OpenUrl::new_tab(url.url));
                 }
+                CommandPaletteAction::Item(tab) => {
+                    self.latest_cmd = format!("Tab {tab}");
+                }
             }
         }
         if let Some(cmd) = egui_kit::UICommand::listen_for_kb_shortcut(egui_ctx) {
@@ -390,6 +480,9 @@ impl eframe::App for ExampleApp {
 
             match cmd {
                 UICommand::ToggleCommandPalette => self.cmd_palette.toggle(),
+                UICommand::ToggleBlueprintPanel => self.show_left_panel ^= true,
+                UICommand::ToggleSelectionPanel => self.show_right_panel ^= true,
+                UICommand::ToggleTimePanel => self.show_bottom_panel ^= true,
                 UICommand::ZoomIn => {
                     let mut zoom_factor = egui_ctx.zoom_factor();
                     zoom_factor += 0.1;
@@ -448,13 +541,42 @@ impl ExampleApp {
                     ui.set_height(top_bar_style.height);
                     ui.add_space(top_bar_style.indent);
 
-                    ui.menu_button("File", |ui| file_menu(ui, &self.command_sender));
+                    self.menu_bar()
+                        .menus_ui(ui, &self.command_registry, &self.command_sender);
 
                     self.top_bar_ui(ui);
                 });
             });
     }
 
+    fn menu_bar(&self) -> MenuBar<UICommand> {
+        MenuBar::new()
+            .menu(
+                Menu::new("&File")
+                    .commands([
+                        UICommand::SaveRecording,
+                        UICommand::SaveRecordingSelection,
+                        UICommand::Open,
+                    ])
+                    .separator()
+                    .command(UICommand::Quit),
+            )
+            .menu(
+                Menu::new("&View")
+                    .checkbox(UICommand::ToggleBlueprintPanel, self.show_left_panel)
+                    .checkbox(UICommand::ToggleSelectionPanel, self.show_right_panel)
+                    .checkbox(UICommand::ToggleTimePanel, self.show_bottom_panel)
+                    .separator()
+                    .submenu(Menu::new("&Zoom").commands([
+                        UICommand::ZoomIn,
+                        UICommand::ZoomOut,
+                        UICommand::ZoomReset,
+                    ]))
+                    .separator()
+                    .command(UICommand::ToggleCommandPalette),
+            )
+    }
+
     fn top_bar_ui(&mut self, ui: &mut egui::Ui) {
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
             // From right-to-left:
@@ -488,19 +610,77 @@ impl ExampleApp {
     }
 }
 
-fn file_menu(ui: &mut egui::Ui, command_sender: &CommandSender) {
-    UICommand::SaveRecording.menu_button_ui(ui, command_sender);
-    UICommand::SaveRecordingSelection.menu_button_ui(ui, command_sender);
-    UICommand::Open.menu_button_ui(ui, command_sender);
-    UICommand::Quit.menu_button_ui(ui, command_sender);
-}
-
 fn tabs_ui(ui: &mut egui::Ui, tree: &mut egui_tiles::Tree<Tab>) {
     tree.ui(&mut MyTileTreeBehavior {}, ui);
 }
 
 pub type Tab = i32;
 
+/// Lists the open tabs in the command palette (`@` prefix).
+#[derive(Default)]
+struct ProjectSetup {
+    name: String,
+    dark_mode: bool,
+    telemetry: bool,
+}
+
+fn project_setup_wizard() -> egui_kit::Wizard<ProjectSetup> {
+    use egui_kit::WizardStep;
+
+    egui_kit::Wizard::new("New project")
+        .step(
+            WizardStep::new("Name", |ui, setup: &mut ProjectSetup| {
+                ui.text_edit_singleline(&mut setup.name);
+            })
+            .description("How should the project be called?")
+            .validate(|setup| {
+                if setup.name.trim().is_empty() {
+                    Err("Enter a name".to_owned())
+                } else {
+                    Ok(())
+                }
+            }),
+        )
+        .step(
+            WizardStep::new("Appearance", |ui, setup: &mut ProjectSetup| {
+                ui.checkbox(&mut setup.dark_mode, "Dark mode");
+                ui.checkbox(&mut setup.telemetry, "Send anonymous usage data");
+            })
+            .skippable(true),
+        )
+        .step(WizardStep::new(
+            "Summary",
+            |ui, setup: &mut ProjectSetup| {
+                ui.label(format!("Name: {}", setup.name));
+                ui.label(format!("Dark mode: {}", setup.dark_mode));
+                ui.label(format!("Telemetry: {}", setup.telemetry));
+            },
+        ))
+}
+
+struct TabProvider {
+    tabs: Vec<Tab>,
+}
+
+impl PaletteProvider<Tab> for TabProvider {
+    fn title(&self) -> &str {
+        "Tabs"
+    }
+
+    fn prefix(&self) -> Option<&str> {
+        Some("@")
+    }
+
+    fn query(&mut self, _egui_ctx: &egui::Context, _query: &str) -> PaletteResults<Tab> {
+        PaletteResults::Ready(
+            self.tabs
+                .iter()
+                .map(|tab| PaletteItem::new(*tab, format!("Tab {tab}")).tooltip("Focus this tab"))
+                .collect(),
+        )
+    }
+}
+
 struct MyTileTreeBehavior {}
 
 impl egui_tiles::Behavior<Tab> for MyTileTreeBehavior {
@@ -11,7 +11,8 @@
 
 use eframe::egui;
 use egui_kit::{setup_theme, ThemeName, ALL_THEMES, DARK_THEMES, LIGHT_THEMES};
-use egui_kit::toast::GlobalToast;
+use egui_kit::components::notifications::{Notification, NotificationLevel};
+use egui_kit::toast::{GlobalToast, ToastAnimation, ToastDirection, ToastOptions};
 use egui_kit::foundation::style_by_name;
 
 #[cfg(feature = "font")]
@@ -84,6 +85,7 @@ fn main() -> Result<(), eframe::Error> {
                 toast_input: "Toast message example".to_string(),
                 async_loading: false,
                 async_progress: 0,
+                toast_options: ToastOptions::default(),
             }))
         }),
     )
@@ -128,6 +130,7 @@ struct ComprehensiveShowcaseApp {
     toast_input: String,
     async_loading: bool,
     async_progress: u32,
+    toast_options: ToastOptions,
 }
 
 impl ComprehensiveShowcaseApp {
@@ -902,6 +905,89 @@ impl ComprehensiveShowcaseApp {
 
         ui.add_space(15.0);
 
+        // Placement
+        ui.group(|ui| {
+            ui.heading("Placement & Animation");
+            ui.separator();
+
+            let options = &mut self.toast_options;
+            let before = options.clone();
+            egui::ComboBox::from_label("Anchor")
+                .selected_text(format!("{:?}", options.anchor))
+                .show_ui(ui, |ui| {
+                    for anchor in [
+                        egui::Align2::LEFT_TOP,
+                        egui::Align2::CENTER_TOP,
+                        egui::Align2::RIGHT_TOP,
+                        egui::Align2::LEFT_CENTER,
+                        egui::Align2::RIGHT_CENTER,
+                        egui::Align2::LEFT_BOTTOM,
+                        egui::Align2::CENTER_BOTTOM,
+                        egui::Align2::RIGHT_BOTTOM,
+                    ] {
+                        ui.selectable_value(&mut options.anchor, anchor, format!("{anchor:?}"));
+                    }
+                });
+            ui.horizontal(|ui| {
+                ui.label("Stacking:");
+                ui.selectable_value(&mut options.direction, ToastDirection::Auto, "Auto");
+                ui.selectable_value(&mut options.direction, ToastDirection::Down, "Down");
+                ui.selectable_value(&mut options.direction, ToastDirection::Up, "Up");
+            });
+            ui.horizontal(|ui| {
+                ui.label("Animation:");
+                ui.selectable_value(&mut options.animation, ToastAnimation::None, "None");
+                ui.selectable_value(&mut options.animation, ToastAnimation::Fade, "Fade");
+                ui.selectable_value(&mut options.animation, ToastAnimation::Slide, "Slide");
+            });
+            ui.add(egui::Slider::new(&mut options.max_width, 200.0..=600.0).text("Max width"));
+
+            if *options != before {
+                GlobalToast::set_options(options.clone());
+            }
+        });
+
+        ui.add_space(15.0);
+
+        // Actions and progress
+        ui.group(|ui| {
+            ui.heading("Actions & Progress");
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                if ui.button("🗑 Delete with Undo").clicked() {
+                    GlobalToast::notify(
+                        Notification::new(NotificationLevel::Info, "File moved to trash")
+                            .with_action("undo", "Undo")
+                            .with_action("open", "Open folder"),
+                    );
+                }
+
+                if ui.button("⬆ Upload").clicked() {
+                    let upload = GlobalToast::progress("Uploading…");
+                    std::thread::spawn(move || {
+                        for percent in (0..=100).step_by(5) {
+                            upload.update(percent as f32 / 100.0, format!("Uploading {percent}%"));
+                            std::thread::sleep(std::time::Duration::from_millis(150));
+                        }
+                        upload.success("Upload complete");
+                    });
+                }
+
+                if ui.button("📚 Burst of 8").clicked() {
+                    for i in 1..=8 {
+                        GlobalToast::info(format!("Message {i}"));
+                    }
+                }
+            });
+
+            for action in GlobalToast::take_actions() {
+                GlobalToast::success(format!("Action: {}", action.action));
+            }
+        });
+
+        ui.add_space(15.0);
+
         // Usage guide
         ui.group(|ui| {
             ui.heading("📖 Usage Guide");
@@ -190,6 +190,9 @@ impl eframe::App for DemoApp {
                     TreeAction::ContextMenuRequested { id, .. } => {
                         self.log(format!("Context menu: {}", id));
                     }
+                    TreeAction::LoadChildren(id) => {
+                        self.log(format!("Load children: {}", id));
+                    }
                 }
             }
         });
@@ -0,0 +1,143 @@
+//!
+//! TreeTable 示例: 树 + 可调整宽度/可排序的列.
+//!
+//! 运行方式:
+//! RUSTFLAGS="-A warnings" cargo check -q --message-format short -p egui_kit --example tree_table
+
+use std::cmp::Ordering;
+
+use eframe::egui;
+use egui_extras::Column;
+use egui_kit::{icons, setup_theme, ThemeName, TreeAction, TreeTable, TreeTableColumn, TreeTableModel};
+
+fn main() -> Result<(), eframe::Error> {
+    env_logger::init();
+
+    let native_options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_inner_size([820.0, 520.0])
+            .with_title("TreeTable Demo"),
+        ..Default::default()
+    };
+
+    eframe::run_native(
+        "TreeTable Demo",
+        native_options,
+        Box::new(|cc| {
+            setup_theme(&cc.egui_ctx, ThemeName::ModernDark);
+            Ok(Box::new(DemoApp::default()))
+        }),
+    )
+}
+
+struct Entry {
+    name: &'static str,
+    parent: Option<usize>,
+    is_dir: bool,
+    size: u64,
+    modified: &'static str,
+}
+
+struct Files {
+    entries: Vec<Entry>,
+}
+
+impl Default for Files {
+    fn default() -> Self {
+        let entry = |name, parent, is_dir, size, modified| Entry {
+            name,
+            parent,
+            is_dir,
+            size,
+            modified,
+        };
+        Self {
+            entries: vec![
+                entry("src", None, true, 0, "2025-11-02"),
+                entry("main.rs", Some(0), false, 2_410, "2025-11-20"),
+                entry("lib.rs", Some(0), false, 9_812, "2025-11-18"),
+                entry("components", Some(0), true, 0, "2025-11-21"),
+                entry("tree.rs", Some(3), false, 15_002, "2025-11-21"),
+                entry("dialog.rs", Some(3), false, 7_330, "2025-10-05"),
+                entry("assets", None, true, 0, "2025-09-14"),
+                entry("logo.png", Some(6), false, 48_120, "2025-09-14"),
+                entry("Cargo.toml", None, false, 1_204, "2025-11-19"),
+            ],
+        }
+    }
+}
+
+impl TreeTableModel<usize> for Files {
+    fn children(&self, parent: Option<&usize>) -> Vec<usize> {
+        (0..self.entries.len())
+            .filter(|&index| self.entries[index].parent.as_ref() == parent)
+            .collect()
+    }
+
+    fn is_dir(&self, id: &usize) -> bool {
+        self.entries[*id].is_dir
+    }
+
+    fn label(&self, id: &usize) -> egui::WidgetText {
+        self.entries[*id].name.into()
+    }
+
+    fn icon(&self, id: &usize) -> Option<&'static str> {
+        Some(if self.entries[*id].is_dir { icons::FOLDER } else { icons::FILE_DOC })
+    }
+
+    fn cell_ui(&self, ui: &mut egui::Ui, id: &usize, column: usize) {
+        let entry = &self.entries[*id];
+        match column {
+            1 if !entry.is_dir => {
+                ui.label(format!("{:.1} KB", entry.size as f64 / 1024.0));
+            }
+            2 => {
+                ui.weak(entry.modified);
+            }
+            _ => {}
+        }
+    }
+
+    fn compare(&self, a: &usize, b: &usize, column: usize) -> Ordering {
+        let (a, b) = (&self.entries[*a], &self.entries[*b]);
+        match column {
+            0 => a.name.cmp(b.name),
+            1 => a.size.cmp(&b.size),
+            2 => a.modified.cmp(b.modified),
+            _ => Ordering::Equal,
+        }
+    }
+}
+
+#[derive(Default)]
+struct DemoApp {
+    files: Files,
+    last_action: String,
+}
+
+impl eframe::App for DemoApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::TopBottomPanel::bottom("status").show(ctx, |ui| {
+            ui.label(&self.last_action);
+        });
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let actions = TreeTable::new("files")
+                .allow_multi_select(true)
+                .column(TreeTableColumn::new("Name", Column::remainder().at_least(160.0)).sortable(true))
+                .column(TreeTableColumn::new("Size", Column::initial(90.0).resizable(true)).sortable(true))
+                .column(TreeTableColumn::new("Modified", Column::initial(110.0).resizable(true)).sortable(true))
+                .show(ui, &self.files);
+
+            for action in actions {
+                if let TreeAction::SelectionChanged(ids) = &action {
+                    let names: Vec<_> = ids.iter().map(|id| self.files.entries[*id].name).collect();
+                    self.last_action = format!("Selection: {names:?}");
+                } else {
+                    self.last_action = format!("{action:?}");
+                }
+            }
+        });
+    }
+}
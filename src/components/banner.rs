@@ -0,0 +1,357 @@
+//! 横幅 (Banner) - 应用级的常驻提示条.
+//!
+//! 与 toast 不同, 横幅一直显示, 直到代码移除或用户关闭 (例如 "You are offline — Retry").
+//! 横幅使用 [`Alert`] 的样式, 可以带操作按钮, 按严重程度排序 (错误在最上面),
+//! 可以记住用户的关闭 (跨重启, 保存在持久化的 egui memory 中).
+//!
+//! 横幅保存在 egui memory 中, 可以从任意线程通过 [`egui::Context`] 添加:
+//!
+//! ```ignore
+//! Banners::show(
+//!     ctx,
+//!     Banner::warning("offline", "You are offline").with_action("retry", "Retry"),
+//! );
+//!
+//! // 每帧, 在面板或窗口的顶部:
+//! for action in Banners::ui(ui) {
+//!     if action.action == "retry" {
+//!         reconnect();
+//!     }
+//! }
+//!
+//! // 恢复连接后:
+//! Banners::remove(ctx, "offline");
+//! ```
+//!
+//! [`Banners::top_panel`] 在窗口顶部的 [`egui::TopBottomPanel`] 中显示横幅.
+
+use egui::{Context, Id};
+use egui_phosphor::regular::X;
+
+use crate::UiExt as _;
+use crate::components::alert::Alert;
+
+/// How severe a [`Banner`] is. More severe banners are shown above less severe ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BannerSeverity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl BannerSeverity {
+    fn alert(self) -> Alert {
+        match self {
+            Self::Info => Alert::info(),
+            Self::Success => Alert::success(),
+            Self::Warning => Alert::warning(),
+            Self::Error => Alert::error(),
+        }
+    }
+}
+
+/// A button on a [`Banner`], see [`Banner::with_action`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BannerButton {
+    /// Reported in [`BannerAction::action`].
+    pub action: String,
+    pub label: String,
+}
+
+/// A banner button was clicked, see [`Banners::ui`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BannerAction {
+    /// [`Banner::id`] of the banner.
+    pub banner: String,
+    pub action: String,
+}
+
+/// A persistent bar at the top of a panel or window, see the [module docs](self).
+#[derive(Clone, Debug)]
+pub struct Banner {
+    id: String,
+    severity: BannerSeverity,
+    text: String,
+    details: Option<String>,
+    buttons: Vec<BannerButton>,
+    dismissible: bool,
+    remember_dismissal: bool,
+}
+
+impl Banner {
+    /// `id` identifies the banner: showing a banner with the same id replaces it.
+    pub fn new(id: impl Into<String>, severity: BannerSeverity, text: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            severity,
+            text: text.into(),
+            details: None,
+            buttons: Vec::new(),
+            dismissible: true,
+            remember_dismissal: false,
+        }
+    }
+
+    pub fn info(id: impl Into<String>, text: impl Into<String>) -> Self {
+        Self::new(id, BannerSeverity::Info, text)
+    }
+
+    pub fn success(id: impl Into<String>, text: impl Into<String>) -> Self {
+        Self::new(id, BannerSeverity::Success, text)
+    }
+
+    pub fn warning(id: impl Into<String>, text: impl Into<String>) -> Self {
+        Self::new(id, BannerSeverity::Warning, text)
+    }
+
+    pub fn error(id: impl Into<String>, text: impl Into<String>) -> Self {
+        Self::new(id, BannerSeverity::Error, text)
+    }
+
+    /// Shown when hovering the text.
+    #[inline]
+    pub fn details(mut self, details: impl Into<String>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+
+    /// Add a button. Clicks are returned by [`Banners::ui`]; the banner stays until removed.
+    #[inline]
+    pub fn with_action(mut self, action: impl Into<String>, label: impl Into<String>) -> Self {
+        self.buttons.push(BannerButton {
+            action: action.into(),
+            label: label.into(),
+        });
+        self
+    }
+
+    /// Show a close button (default `true`).
+    #[inline]
+    pub fn dismissible(mut self, dismissible: bool) -> Self {
+        self.dismissible = dismissible;
+        self
+    }
+
+    /// Once the user closes the banner, never show a banner with this id again,
+    /// even after a restart. See [`Banners::forget_dismissal`].
+    #[inline]
+    pub fn remember_dismissal(mut self, remember_dismissal: bool) -> Self {
+        self.remember_dismissal = remember_dismissal;
+        self
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn severity(&self) -> BannerSeverity {
+        self.severity
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
+struct DismissedBannerMarker;
+
+fn dismissed_id(banner_id: &str) -> Id {
+    Id::new(("banner_dismissed", banner_id))
+}
+
+/// The banners of an [`egui::Context`], see the [module docs](self).
+pub struct Banners;
+
+impl Banners {
+    fn with_banners<R>(ctx: &Context, f: impl FnOnce(&mut Vec<Banner>) -> R) -> R {
+        let id = Id::new("banners");
+        ctx.data_mut(|d| f(d.get_temp_mut_or_default::<Vec<Banner>>(id)))
+    }
+
+    /// Show a banner, replacing the banner with the same id.
+    ///
+    /// Does nothing if the user dismissed a banner with this id that had
+    /// [`Banner::remember_dismissal`].
+    pub fn show(ctx: &Context, banner: Banner) {
+        if Self::is_dismissed(ctx, &banner.id) {
+            return;
+        }
+        Self::with_banners(ctx, |banners| {
+            if let Some(existing) = banners.iter_mut().find(|b| b.id == banner.id) {
+                *existing = banner;
+            } else {
+                banners.push(banner);
+            }
+            // 稳定排序: 同一级别内按添加顺序
+            banners.sort_by_key(|b| std::cmp::Reverse(b.severity));
+        });
+        ctx.request_repaint();
+    }
+
+    /// Remove a banner, e.g. once the problem it describes is resolved.
+    pub fn remove(ctx: &Context, id: &str) {
+        Self::with_banners(ctx, |banners| banners.retain(|b| b.id != id));
+        ctx.request_repaint();
+    }
+
+    /// Remove a banner as if the user closed it.
+    pub fn dismiss(ctx: &Context, id: &str) {
+        let remember = Self::with_banners(ctx, |banners| {
+            let index = banners.iter().position(|b| b.id == id)?;
+            Some(banners.remove(index).remember_dismissal)
+        });
+        if remember == Some(true) {
+            ctx.data_mut(|d| d.insert_persisted(dismissed_id(id), DismissedBannerMarker));
+        }
+    }
+
+    /// Whether the user dismissed a banner with this id that had [`Banner::remember_dismissal`].
+    pub fn is_dismissed(ctx: &Context, id: &str) -> bool {
+        ctx.data_mut(|d| d.get_persisted::<DismissedBannerMarker>(dismissed_id(id)))
+            .is_some()
+    }
+
+    /// Allow a banner that was dismissed with [`Banner::remember_dismissal`] to show again.
+    pub fn forget_dismissal(ctx: &Context, id: &str) {
+        ctx.data_mut(|d| d.remove::<DismissedBannerMarker>(dismissed_id(id)));
+    }
+
+    pub fn is_shown(ctx: &Context, id: &str) -> bool {
+        Self::with_banners(ctx, |banners| banners.iter().any(|b| b.id == id))
+    }
+
+    /// The shown banners, most severe first.
+    pub fn banners(ctx: &Context) -> Vec<Banner> {
+        Self::with_banners(ctx, |banners| banners.clone())
+    }
+
+    pub fn is_empty(ctx: &Context) -> bool {
+        Self::with_banners(ctx, |banners| banners.is_empty())
+    }
+
+    /// Show all banners, most severe first, using the full width of `ui`.
+    ///
+    /// Returns the buttons clicked this frame.
+    pub fn ui(ui: &mut egui::Ui) -> Vec<BannerAction> {
+        let banners = Self::banners(ui.ctx());
+        let mut actions = Vec::new();
+        let mut dismissed = Vec::new();
+
+        for banner in &banners {
+            ui.push_id(&banner.id, |ui| {
+                Self::banner_ui(ui, banner, &mut actions, &mut dismissed);
+            });
+        }
+
+        for id in dismissed {
+            Self::dismiss(ui.ctx(), &id);
+        }
+        actions
+    }
+
+    /// Show the banners in a panel at the top of the window (nothing if there are none).
+    ///
+    /// Call before the other panels, like [`egui::TopBottomPanel::top`].
+    pub fn top_panel(ctx: &Context) -> Vec<BannerAction> {
+        if Self::is_empty(ctx) {
+            return Vec::new();
+        }
+        egui::TopBottomPanel::top("banners")
+            .frame(
+                egui::Frame::new()
+                    .inner_margin(4.0)
+                    .fill(ctx.style().visuals.panel_fill),
+            )
+            .show_separator_line(false)
+            .show(ctx, |ui| {
+                ui.spacing_mut().item_spacing.y = 4.0;
+                Self::ui(ui)
+            })
+            .inner
+    }
+
+    fn banner_ui(
+        ui: &mut egui::Ui,
+        banner: &Banner,
+        actions: &mut Vec<BannerAction>,
+        dismissed: &mut Vec<String>,
+    ) {
+        banner.severity.alert().show(ui, |ui| {
+            egui::Sides::new().shrink_left().show(
+                ui,
+                |ui| {
+                    ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Wrap);
+                    let response = ui.strong(&banner.text);
+                    if let Some(details) = &banner.details {
+                        response.on_hover_text(details);
+                    }
+                },
+                |ui| {
+                    // right-to-left: 关闭按钮在最右边
+                    if banner.dismissible && ui.phosphor_icon_button(X, "Dismiss").clicked() {
+                        dismissed.push(banner.id.clone());
+                    }
+                    for button in banner.buttons.iter().rev() {
+                        if ui.small_button(&button.label).clicked() {
+                            actions.push(BannerAction {
+                                banner: banner.id.clone(),
+                                action: button.action.clone(),
+                            });
+                        }
+                    }
+                },
+            );
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Banner, BannerSeverity, Banners};
+
+    #[test]
+    fn test_banners() {
+        let ctx = egui::Context::default();
+
+        Banners::show(&ctx, Banner::info("tip", "Try the command palette"));
+        Banners::show(&ctx, Banner::warning("offline", "You are offline"));
+        Banners::show(
+            &ctx,
+            Banner::error("disk", "Disk full").remember_dismissal(true),
+        );
+        Banners::show(&ctx, Banner::warning("update", "Update available"));
+
+        let order = |ctx: &egui::Context| -> Vec<String> {
+            Banners::banners(ctx)
+                .iter()
+                .map(|b| b.id().to_owned())
+                .collect()
+        };
+        assert_eq!(order(&ctx), ["disk", "offline", "update", "tip"]);
+
+        // 替换同一 id 的横幅
+        Banners::show(&ctx, Banner::error("offline", "Connection lost"));
+        assert_eq!(order(&ctx), ["disk", "offline", "update", "tip"]);
+        assert_eq!(Banners::banners(&ctx)[1].severity(), BannerSeverity::Error);
+
+        Banners::remove(&ctx, "tip");
+        assert!(!Banners::is_shown(&ctx, "tip"));
+        Banners::show(&ctx, Banner::info("tip", "Try the command palette"));
+        assert!(Banners::is_shown(&ctx, "tip"));
+
+        // 记住关闭的横幅不再显示
+        Banners::dismiss(&ctx, "disk");
+        assert!(Banners::is_dismissed(&ctx, "disk"));
+        Banners::show(
+            &ctx,
+            Banner::error("disk", "Disk full").remember_dismissal(true),
+        );
+        assert!(!Banners::is_shown(&ctx, "disk"));
+
+        Banners::forget_dismissal(&ctx, "disk");
+        Banners::show(&ctx, Banner::error("disk", "Disk full"));
+        assert!(Banners::is_shown(&ctx, "disk"));
+    }
+}
@@ -6,6 +6,9 @@ pub enum Variant {
     Primary,
     Secondary,
     Ghost,
+
+    /// For destructive actions, e.g. "Delete".
+    Danger,
 }
 
 pub enum Size {
@@ -63,6 +66,16 @@ impl Variant {
             Self::Ghost => {
                 // The default button
             }
+            Self::Danger => {
+                all_visuals(style, |vis| {
+                    vis.bg_fill = tokens.error_fg_color;
+                    vis.weak_bg_fill = tokens.error_fg_color;
+                    vis.fg_stroke.color = egui::Color32::WHITE;
+                });
+                let hovered = tokens.error_fg_color.gamma_multiply(0.85);
+                style.visuals.widgets.hovered.bg_fill = hovered;
+                style.visuals.widgets.hovered.weak_bg_fill = hovered;
+            }
         }
     }
 }
@@ -101,6 +114,11 @@ impl<'a> ReButton<'a> {
         self
     }
 
+    pub fn danger(mut self) -> Self {
+        self.variant = Variant::Danger;
+        self
+    }
+
     pub fn small(mut self) -> Self {
         self.size = Size::Small;
         self
@@ -25,6 +25,23 @@ impl IconText {
         Self::from_modifiers_and(os, modifiers, key_text)
     }
 
+    /// A multi-step shortcut such as `Ctrl+K Ctrl+S`, with a gap between the steps.
+    pub fn from_key_sequence(
+        os: OperatingSystem,
+        steps: &[egui::KeyboardShortcut],
+    ) -> Atoms<'static> {
+        let mut atoms = Atoms::new(());
+        for (i, step) in steps.iter().enumerate() {
+            if 0 < i {
+                atoms.push_right(" ");
+            }
+            for atom in Self::from_keyboard_shortcut(os, *step) {
+                atoms.push_right(atom);
+            }
+        }
+        atoms
+    }
+
     pub fn from_modifiers_and(
         os: OperatingSystem,
         modifiers: Modifiers,
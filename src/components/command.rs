@@ -2,14 +2,22 @@ use egui::os::OperatingSystem;
 use egui::{Id, Key, KeyboardShortcut, Modifiers};
 use smallvec::{SmallVec, smallvec};
 
+use crate::components::command_arguments::CommandParameter;
+use crate::components::command_registry::{Command, CommandId, CommandRegistry, CommandSender};
+use crate::components::key_sequence::KeySequence;
 use crate::extensions::context_ext::ContextExt as _;
-use crate::extensions::egui_ext::context_ext::ContextExt as _;
 
 /// Interface for sending [`UICommand`] messages.
 pub trait UICommandSender {
     fn send_ui(&self, command: UICommand);
 }
 
+impl<T: UICommandSender> CommandSender<UICommand> for T {
+    fn send(&self, command: UICommand) {
+        self.send_ui(command);
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct SetPlaybackSpeed(pub egui::emath::OrderedFloat<f32>);
 
@@ -131,7 +139,55 @@ pub enum UICommand {
     AddRedapServer,
 }
 
+impl CommandId for UICommand {}
+
 impl UICommand {
+    /// All [`UICommand`]s as a [`CommandRegistry`] preset, in command palette order.
+    pub fn registry(os: OperatingSystem) -> CommandRegistry<Self> {
+        use strum::IntoEnumIterator as _;
+
+        let mut registry = CommandRegistry::new();
+        for command in Self::iter() {
+            let (text, tooltip) = command.text_and_tooltip();
+            let mut entry = Command::new(command, text)
+                .tooltip(tooltip)
+                .shortcuts(command.kb_shortcuts(os))
+                .category(command.category())
+                .link(command.is_link());
+            if let Some(icon) = command.icon() {
+                entry = entry.icon(icon);
+            }
+            if matches!(command, Self::PlaybackSpeed(_)) {
+                entry = entry.arguments(
+                    [CommandParameter::number("Speed", 0.01..=100.0)
+                        .hint("Playback speed, e.g. 0.5 or 2")
+                        .default_value("1")],
+                    |values| {
+                        let speed = values.first()?.as_f64()? as f32;
+                        Some(Self::PlaybackSpeed(SetPlaybackSpeed(egui::emath::OrderedFloat(speed))))
+                    },
+                );
+            }
+            registry.register(entry);
+        }
+
+        for (speed, sequence) in Self::playback_speed_sequences() {
+            let command = Self::PlaybackSpeed(SetPlaybackSpeed(egui::emath::OrderedFloat(speed)));
+            if let Some(entry) = registry.get_mut(&command) {
+                // 1x 是命令面板中带参数的条目.
+                entry.sequences.push(sequence);
+            } else {
+                let mut entry = Command::new(command, format!("Playback speed {speed}x"))
+                    .tooltip(format!("Set the playback speed to {speed}x"))
+                    .category(command.category())
+                    .visible_when(|_| false);
+                entry.sequences.push(sequence);
+                registry.register(entry);
+            }
+        }
+        registry
+    }
+
     pub fn text(self) -> &'static str {
         self.text_and_tooltip().0
     }
@@ -569,8 +625,88 @@ impl UICommand {
         matches!(self, Self::OpenWebHelp | Self::OpenRerunDiscord)
     }
 
-    fn handle_playback_chord(ctx: &egui::Context) -> Option<Self> {
-        const CHORD_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(500);
+    /// Category used to group the command in menus and the command palette.
+    pub fn category(self) -> &'static str {
+        match self {
+            Self::Open
+            | Self::OpenUrl
+            | Self::Import
+            | Self::SaveRecording
+            | Self::SaveRecordingSelection
+            | Self::SaveBlueprint
+            | Self::CloseCurrentRecording
+            | Self::CloseAllEntries
+            | Self::NextRecording
+            | Self::PreviousRecording => "File",
+
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Quit => "File",
+
+            Self::NavigateBack
+            | Self::NavigateForward
+            | Self::Undo
+            | Self::Redo
+            | Self::Share
+            | Self::CopyDirectLink
+            | Self::CopyTimeSelectionLink
+            | Self::CopyEntityHierarchy => "Edit",
+
+            Self::OpenWebHelp | Self::OpenRerunDiscord => "Help",
+
+            Self::ResetViewer
+            | Self::ClearActiveBlueprint
+            | Self::ClearActiveBlueprintAndEnableHeuristics
+            | Self::TogglePanelStateOverrides
+            | Self::ToggleMemoryPanel
+            | Self::ToggleTopPanel
+            | Self::ToggleBlueprintPanel
+            | Self::ExpandBlueprintPanel
+            | Self::ToggleSelectionPanel
+            | Self::ExpandSelectionPanel
+            | Self::ToggleTimePanel
+            | Self::ToggleChunkStoreBrowser
+            | Self::Settings
+            | Self::ToggleFullscreen
+            | Self::ToggleCommandPalette => "View",
+
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::ZoomIn | Self::ZoomOut | Self::ZoomReset => "View",
+
+            Self::PlaybackTogglePlayPause
+            | Self::PlaybackFollow
+            | Self::PlaybackStepBack
+            | Self::PlaybackStepForward
+            | Self::PlaybackBack
+            | Self::PlaybackForward
+            | Self::PlaybackBackFast
+            | Self::PlaybackForwardFast
+            | Self::PlaybackBeginning
+            | Self::PlaybackEnd
+            | Self::PlaybackRestart
+            | Self::PlaybackSpeed(_) => "Playback",
+
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::OpenProfiler
+            | Self::ScreenshotWholeApp
+            | Self::PrintChunkStore
+            | Self::PrintBlueprintStore
+            | Self::PrintPrimaryCache => "Developer",
+
+            #[cfg(debug_assertions)]
+            Self::ToggleBlueprintInspectionPanel | Self::ToggleEguiDebugPanel | Self::ResetEguiMemory => {
+                "Developer"
+            }
+
+            #[cfg(target_arch = "wasm32")]
+            Self::RestartWithWebGl | Self::RestartWithWebGpu => "Developer",
+
+            Self::AddRedapServer => "File",
+        }
+    }
+
+    /// Number-key chords for [`Self::PlaybackSpeed`]: one or two digits set the speed (`5 0` = 50x),
+    /// and each leading `0` divides it by 10 (`0 5` = 0.5x, `0 0 5` = 0.05x).
+    fn playback_speed_sequences() -> impl Iterator<Item = (f32, KeySequence)> {
         const NUMBER_KEYS: [Key; 10] = [
             Key::Num0,
             Key::Num1,
@@ -584,136 +720,34 @@ impl UICommand {
             Key::Num9,
         ];
 
-        fn key_to_digit(key: Key) -> Option<char> {
-            let i = NUMBER_KEYS.iter().position(|&k| k == key)?;
-            char::from_digit(i as u32, 10)
-        }
-
-        #[derive(Default, Clone)]
-        struct PlaybackChordState {
-            last_key_time: Option<web_time::Instant>,
-            accumulated: String,
-        }
-
-        if ctx.text_edit_focused() {
-            return None;
-        }
-
-        let mut chord_state = ctx.data_mut(|data| {
-            data.get_temp_mut_or_default::<PlaybackChordState>(Id::NULL)
-                .clone()
-        });
-
-        let now = web_time::Instant::now();
-
-        let pressed_number = ctx.input(|i| {
-            let mut pressed_number = NUMBER_KEYS.iter().find(|&&k| i.key_pressed(k)).copied();
-            let has_other = i.keys_down.iter().any(|k| !NUMBER_KEYS.contains(k));
-
-            if has_other || i.modifiers.any() {
-                chord_state = PlaybackChordState::default();
-                pressed_number = None;
-            }
-
-            pressed_number
-        });
-
-        // Check if timeout expired - clear old state
-        if let Some(last_time) = chord_state.last_key_time
-            && now.duration_since(last_time) >= CHORD_TIMEOUT
-        {
-            chord_state = PlaybackChordState::default();
-        }
-
-        let mut command = None;
-
-        // Handle number key press
-        if let Some(key) = pressed_number {
-            if let Some(digit) = key_to_digit(key) {
-                chord_state.accumulated.push(digit);
-            }
-
-            chord_state.last_key_time = Some(now);
-
-            // Leading zeros should divide the speed by 10 for each zero.
-            // So e.g. 05 = 0.5x speed, 005 = 0.05x speed, etc.
-            let leading_zeros = chord_state
-                .accumulated
-                .chars()
-                .take_while(|&c| c == '0')
-                .count();
-
-            let factor = 10usize.pow(leading_zeros as u32);
-
-            if let Ok(speed) = chord_state.accumulated.parse::<f32>()
-                && speed > 0.0
-            {
-                command = Some(Self::PlaybackSpeed(SetPlaybackSpeed(
-                    egui::emath::OrderedFloat(speed / factor as f32),
-                )));
-            }
-        }
-
-        ctx.data_mut(|data| data.insert_temp(Id::NULL, chord_state.clone()));
-
-        command
+        (0..3).flat_map(|leading_zeros| {
+            (1..100_usize).map(move |number| {
+                let digits = if number < 10 {
+                    vec![number]
+                } else {
+                    vec![number / 10, number % 10]
+                };
+                let steps = std::iter::repeat_n(Key::Num0, leading_zeros)
+                    .chain(digits.into_iter().map(|digit| NUMBER_KEYS[digit]))
+                    .map(|key| KeyboardShortcut::new(Modifiers::NONE, key));
+                let speed = number as f32 / 10usize.pow(leading_zeros as u32) as f32;
+                (speed, KeySequence::new(steps))
+            })
+        })
     }
 
+    /// Dispatch the shortcuts of [`Self::registry`], including the multi-key playback speed chords.
     #[must_use = "Returns the Command that was triggered by some keyboard shortcut"]
     pub fn listen_for_kb_shortcut(egui_ctx: &egui::Context) -> Option<Self> {
-        use strum::IntoEnumIterator as _;
-
-        let anything_has_focus = egui_ctx.memory(|mem| mem.focused().is_some());
-
-        let mut commands: Vec<(KeyboardShortcut, Self)> = Self::iter()
-            .flat_map(|cmd| {
-                cmd.kb_shortcuts(egui_ctx.os())
-                    .into_iter()
-                    .map(move |kb_shortcut| (kb_shortcut, cmd))
-            })
-            .collect();
-
-        // If the user pressed `Cmd-Shift-S` then egui will match that
-        // with both `Cmd-Shift-S` and `Cmd-S`.
-        // The reason is that `Shift` (and `Alt`) are sometimes required to produce certain keys,
-        // such as `+` (`Shift =` on an american keyboard).
-        // The result of this is that we must check for `Cmd-Shift-S` before `Cmd-S`, etc.
-        // So we order the commands here so that the commands with `Shift` and `Alt` in them
-        // are checked first.
-        commands.sort_by_key(|(kb_shortcut, _cmd)| {
-            let num_shift_alts =
-                kb_shortcut.modifiers.shift as i32 + kb_shortcut.modifiers.alt as i32;
-            -num_shift_alts // most first
-        });
-
-        let command = egui_ctx.input_mut(|input| {
-            for (kb_shortcut, command) in commands {
-                if anything_has_focus {
-                    // If a text edit has focus, is should usually get exclusive access to that input.
-                    // For instance: use alt-arrows to move the cursor a whole word (at least on mac).
-                    // The exception are shortcuts with ctrl/cmd in them:
-                    let is_command = kb_shortcut.modifiers.command
-                        || kb_shortcut.modifiers.mac_cmd
-                        || kb_shortcut.modifiers.ctrl;
-                    if !is_command {
-                        continue; // ignore
-                    }
-                }
-
-                if input.consume_shortcut(&kb_shortcut) {
-                    // Clear the shortcut key from input to prevent it from propagating to other UI component.
-                    input.keys_down.remove(&kb_shortcut.logical_key);
-                    return Some(command);
-                }
-            }
-            None
+        let os = egui_ctx.os();
+        let registry = egui_ctx.memory_mut(|mem| {
+            mem.data
+                .get_temp_mut_or_insert_with(Id::new(("ui_command_registry", os)), || {
+                    std::sync::Arc::new(Self::registry(os))
+                })
+                .clone()
         });
-
-        if command.is_none() {
-            Self::handle_playback_chord(egui_ctx)
-        } else {
-            command
-        }
+        registry.listen_for_kb_shortcut(egui_ctx)
     }
 
     /// Show this command as a menu-button.
@@ -781,23 +815,6 @@ impl UICommand {
 
 #[test]
 fn check_for_clashing_command_shortcuts() {
-    fn clashes(a: KeyboardShortcut, b: KeyboardShortcut) -> bool {
-        if a.logical_key != b.logical_key {
-            return false;
-        }
-
-        if a.modifiers.alt != b.modifiers.alt {
-            return false;
-        }
-
-        if a.modifiers.shift != b.modifiers.shift {
-            return false;
-        }
-
-        // On Non-Mac, command is interpreted as ctrl!
-        (a.modifiers.command || a.modifiers.ctrl) == (b.modifiers.command || b.modifiers.ctrl)
-    }
-
     use strum::IntoEnumIterator as _;
 
     for os in [
@@ -813,7 +830,7 @@ fn check_for_clashing_command_shortcuts() {
                     }
                     for b_shortcut in b_cmd.kb_shortcuts(os) {
                         assert!(
-                            !clashes(a_shortcut, b_shortcut),
+                            !crate::components::keymap::shortcuts_clash(os, a_shortcut, b_shortcut),
                             "Command '{a_cmd:?}' and '{b_cmd:?}' have overlapping keyboard shortcuts: {:?} vs {:?}",
                             a_shortcut.format(&egui::ModifierNames::NAMES, true),
                             b_shortcut.format(&egui::ModifierNames::NAMES, true),
@@ -824,3 +841,29 @@ fn check_for_clashing_command_shortcuts() {
         }
     }
 }
+
+#[test]
+fn playback_speed_chords() {
+    let ctx = egui::Context::default();
+    let mut triggered = Vec::new();
+    for key in [Key::Num5, Key::Num0, Key::Num0, Key::Num5, Key::A, Key::Num3, Key::A] {
+        let input = egui::RawInput {
+            events: vec![egui::Event::Key {
+                key,
+                physical_key: None,
+                pressed: true,
+                repeat: false,
+                modifiers: Modifiers::NONE,
+            }],
+            ..Default::default()
+        };
+        let _ = ctx.run(input, |ctx| triggered.push(UICommand::listen_for_kb_shortcut(ctx)));
+    }
+
+    let speed = |speed| Some(UICommand::PlaybackSpeed(SetPlaybackSpeed(egui::emath::OrderedFloat(speed))));
+    // 还可能有更长的序列时等待, 按下其他键时触发已完成的序列.
+    assert_eq!(
+        triggered,
+        vec![None, speed(50.0), None, None, speed(0.5), None, speed(3.0)]
+    );
+}
@@ -0,0 +1,265 @@
+//! 命令参数 (Command arguments).
+//!
+//! 有些命令需要一个值才能执行, 例如 [`crate::UICommand::PlaybackSpeed`].
+//! 命令可以声明参数; 在 [`crate::CommandPalette`] 中选择这样的命令后, 面板进入参数步骤,
+//! 依次询问每个参数 (自由文本, 选择列表, 数字), 最后由 `build` 生成带参数的命令:
+//!
+//! ```ignore
+//! registry.register(
+//!     Command::new(AppCommand::GoToFrame(0), "Go to frame").arguments(
+//!         [CommandParameter::number("Frame", 0.0..=1e6).integer()],
+//!         |values| Some(AppCommand::GoToFrame(values[0].as_f64()? as u64)),
+//!     ),
+//! );
+//! ```
+//!
+//! 在参数步骤中 `Escape` / `Back` 返回上一步, 在第一个参数时返回命令列表.
+
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// Validates free-text input. `Err` holds the message shown to the user.
+pub type TextValidator = Arc<dyn Fn(&str) -> Result<(), String> + Send + Sync>;
+
+/// Builds the parameterized command from the collected values, one per parameter.
+pub type ArgumentsBuilder<C> = Arc<dyn Fn(&[ArgumentValue]) -> Option<C> + Send + Sync>;
+
+/// What kind of input a [`CommandParameter`] asks for.
+#[derive(Clone)]
+pub enum ParameterKind {
+    /// Free text, optionally validated.
+    Text { validate: Option<TextValidator> },
+
+    /// One of a fixed list of choices, fuzzy-filtered by the query.
+    Pick(Vec<String>),
+
+    /// A number within `range`.
+    Number {
+        range: RangeInclusive<f64>,
+        integer: bool,
+    },
+}
+
+/// A single parameter of a command.
+#[derive(Clone)]
+pub struct CommandParameter {
+    pub name: String,
+    pub kind: ParameterKind,
+
+    /// Shown in the empty input field.
+    pub hint: String,
+
+    /// Pre-filled input when the step is entered.
+    pub default_value: Option<String>,
+}
+
+impl CommandParameter {
+    fn new(name: impl Into<String>, kind: ParameterKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            hint: String::new(),
+            default_value: None,
+        }
+    }
+
+    pub fn text(name: impl Into<String>) -> Self {
+        Self::new(name, ParameterKind::Text { validate: None })
+    }
+
+    pub fn pick<S: Into<String>>(
+        name: impl Into<String>,
+        choices: impl IntoIterator<Item = S>,
+    ) -> Self {
+        Self::new(
+            name,
+            ParameterKind::Pick(choices.into_iter().map(Into::into).collect()),
+        )
+    }
+
+    pub fn number(name: impl Into<String>, range: RangeInclusive<f64>) -> Self {
+        Self::new(
+            name,
+            ParameterKind::Number {
+                range,
+                integer: false,
+            },
+        )
+    }
+
+    /// Only accept whole numbers. Only affects [`ParameterKind::Number`].
+    #[inline]
+    pub fn integer(mut self) -> Self {
+        if let ParameterKind::Number { integer, .. } = &mut self.kind {
+            *integer = true;
+        }
+        self
+    }
+
+    /// Validate free-text input. Only affects [`ParameterKind::Text`].
+    #[inline]
+    pub fn validate(
+        mut self,
+        validate: impl Fn(&str) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        if let ParameterKind::Text { validate: v } = &mut self.kind {
+            *v = Some(Arc::new(validate));
+        }
+        self
+    }
+
+    #[inline]
+    pub fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = hint.into();
+        self
+    }
+
+    #[inline]
+    pub fn default_value(mut self, default_value: impl Into<String>) -> Self {
+        self.default_value = Some(default_value.into());
+        self
+    }
+
+    /// Parse the user's input.
+    ///
+    /// For [`ParameterKind::Pick`] the input must equal one of the choices (ignoring case).
+    pub fn parse(&self, input: &str) -> Result<ArgumentValue, String> {
+        let input = input.trim();
+        match &self.kind {
+            ParameterKind::Text { validate } => {
+                if let Some(validate) = validate {
+                    validate(input)?;
+                }
+                Ok(ArgumentValue::Text(input.to_owned()))
+            }
+
+            ParameterKind::Pick(choices) => choices
+                .iter()
+                .position(|choice| choice.eq_ignore_ascii_case(input))
+                .map(|index| ArgumentValue::Pick {
+                    index,
+                    text: choices[index].clone(),
+                })
+                .ok_or_else(|| format!("Pick one of the {} choices", choices.len())),
+
+            ParameterKind::Number { range, integer } => {
+                let number: f64 = input
+                    .parse()
+                    .map_err(|_err| format!("{input:?} is not a number"))?;
+                if *integer && number.fract() != 0.0 {
+                    return Err("Expected a whole number".to_owned());
+                }
+                if !range.contains(&number) {
+                    return Err(format!(
+                        "Expected a number between {} and {}",
+                        range.start(),
+                        range.end()
+                    ));
+                }
+                Ok(ArgumentValue::Number(number))
+            }
+        }
+    }
+}
+
+/// The value entered for a [`CommandParameter`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArgumentValue {
+    Text(String),
+    Pick { index: usize, text: String },
+    Number(f64),
+}
+
+impl ArgumentValue {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Text(text) | Self::Pick { text, .. } => Some(text),
+            Self::Number(_) => None,
+        }
+    }
+
+    /// Index of the chosen [`ParameterKind::Pick`] choice.
+    pub fn as_index(&self) -> Option<usize> {
+        match self {
+            Self::Pick { index, .. } => Some(*index),
+            Self::Text(_) | Self::Number(_) => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Number(number) => Some(*number),
+            Self::Text(_) | Self::Pick { .. } => None,
+        }
+    }
+
+    /// The value as the user would type it.
+    pub fn to_input_string(&self) -> String {
+        match self {
+            Self::Text(text) | Self::Pick { text, .. } => text.clone(),
+            Self::Number(number) => number.to_string(),
+        }
+    }
+}
+
+/// The parameters of a command and how to build the parameterized command.
+#[derive(Clone)]
+pub struct CommandArguments<C> {
+    pub parameters: Vec<CommandParameter>,
+    build: ArgumentsBuilder<C>,
+}
+
+impl<C> CommandArguments<C> {
+    pub fn new(
+        parameters: impl IntoIterator<Item = CommandParameter>,
+        build: impl Fn(&[ArgumentValue]) -> Option<C> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            parameters: parameters.into_iter().collect(),
+            build: Arc::new(build),
+        }
+    }
+
+    /// `None` if the values don't form a valid command.
+    pub fn build(&self, values: &[ArgumentValue]) -> Option<C> {
+        (values.len() == self.parameters.len())
+            .then(|| (self.build)(values))
+            .flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArgumentValue, CommandArguments, CommandParameter};
+
+    #[test]
+    fn test_parse_arguments() {
+        let speed = CommandParameter::number("Speed", 0.01..=100.0);
+        assert_eq!(speed.parse(" 2.5 "), Ok(ArgumentValue::Number(2.5)));
+        assert!(speed.parse("fast").is_err());
+        assert!(speed.parse("1000").is_err());
+        assert!(
+            CommandParameter::number("Frame", 0.0..=10.0)
+                .integer()
+                .parse("1.5")
+                .is_err()
+        );
+
+        let mode = CommandParameter::pick("Mode", ["Light", "Dark"]);
+        assert_eq!(mode.parse("dark").unwrap().as_index(), Some(1));
+        assert!(mode.parse("Blue").is_err());
+
+        let name = CommandParameter::text("Name").validate(|text| {
+            if text.is_empty() {
+                Err("Name must not be empty".to_owned())
+            } else {
+                Ok(())
+            }
+        });
+        assert!(name.parse("  ").is_err());
+
+        let arguments = CommandArguments::new([speed], |values| values[0].as_f64());
+        assert_eq!(arguments.build(&[ArgumentValue::Number(2.0)]), Some(2.0));
+        assert_eq!(arguments.build(&[]), None);
+    }
+}
@@ -0,0 +1,392 @@
+//! 命令上下文 (Context keys / when-clauses).
+//!
+//! UI 区域每帧推入上下文键 (如 `"tree"`, `"canvas"`), 命令注册时用 when 表达式
+//! 声明在哪些上下文下生效:
+//!
+//! ```ignore
+//! registry
+//!     .register(Command::new(AppCommand::DeleteNode, "Delete node").shortcut(delete).when("tree"))
+//!     .register(Command::new(AppCommand::DeleteShape, "Delete shape").shortcut(delete).when("canvas && !dragging"));
+//!
+//! // 区域获得焦点 (最近一次点击在区域内, 或焦点控件在区域内) 时推入键:
+//! CommandContext::scope(ui, "tree", |ui| tree_ui(ui));
+//!
+//! // 也可以无条件推入:
+//! if self.dragging {
+//!     CommandContext::push(ui.ctx(), "dragging");
+//! }
+//! ```
+//!
+//! 同一快捷键匹配多个命令时, 选择最具体的 when 表达式 (见 [`WhenClause::specificity`]).
+//! 本帧还没有推入任何键时 (例如在 UI 之前分发快捷键), 使用上一帧的键;
+//! 一旦本帧推入了键, 上一帧的键就失效, 因此焦点切换后不会同时匹配两个区域.
+
+use std::collections::BTreeSet;
+
+use crate::extensions::egui_ext::context_ext::ContextExt as _;
+
+/// 上下文键在 egui memory 中的存储.
+#[derive(Clone, Default)]
+struct ContextFrames {
+    pass_nr: u64,
+    current: BTreeSet<String>,
+    previous: BTreeSet<String>,
+
+    /// 最近一次指针按下的位置, 用于判断区域焦点.
+    last_press: Option<egui::Pos2>,
+}
+
+impl ContextFrames {
+    fn id() -> egui::Id {
+        egui::Id::new("command_context")
+    }
+
+    /// 进入新的一帧时, 把本帧的键移到 `previous`.
+    fn advance(&mut self, pass_nr: u64) {
+        if self.pass_nr != pass_nr {
+            self.previous = if self.pass_nr + 1 == pass_nr {
+                std::mem::take(&mut self.current)
+            } else {
+                BTreeSet::new()
+            };
+            self.current.clear();
+            self.pass_nr = pass_nr;
+        }
+    }
+}
+
+/// The context keys active this frame.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommandContext {
+    keys: BTreeSet<String>,
+}
+
+impl CommandContext {
+    /// Built-in key: a text edit has keyboard focus.
+    pub const TEXT_INPUT_FOCUS: &'static str = "textInputFocus";
+
+    /// A context with exactly the given keys (mostly for tests).
+    pub fn from_keys<S: Into<String>>(keys: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            keys: keys.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Make `key` active for this frame (and the start of the next, see [`Self::active`]).
+    pub fn push(ctx: &egui::Context, key: impl Into<String>) {
+        let pass_nr = ctx.cumulative_pass_nr();
+        ctx.data_mut(|d| {
+            let frames = d.get_temp_mut_or_default::<ContextFrames>(ContextFrames::id());
+            frames.advance(pass_nr);
+            frames.current.insert(key.into());
+        });
+    }
+
+    /// The keys pushed this frame, plus the built-in keys.
+    ///
+    /// Until the first key of this frame is pushed, the keys of the previous frame are used instead.
+    pub fn active(ctx: &egui::Context) -> Self {
+        let pass_nr = ctx.cumulative_pass_nr();
+        let mut keys = ctx.data_mut(|d| {
+            let frames = d.get_temp_mut_or_default::<ContextFrames>(ContextFrames::id());
+            frames.advance(pass_nr);
+            if frames.current.is_empty() {
+                frames.previous.clone()
+            } else {
+                frames.current.clone()
+            }
+        });
+
+        if ctx.text_edit_focused() {
+            keys.insert(Self::TEXT_INPUT_FOCUS.to_owned());
+        }
+
+        Self { keys }
+    }
+
+    /// Show a UI region that pushes `key` while it has focus.
+    ///
+    /// The region has focus if the focused widget lies inside it, or, when no widget is focused,
+    /// if the last pointer press was inside it. Nested regions all push their keys.
+    pub fn scope<R>(
+        ui: &mut egui::Ui,
+        key: impl Into<String>,
+        add_contents: impl FnOnce(&mut egui::Ui) -> R,
+    ) -> egui::InnerResponse<R> {
+        let inner = ui.scope(add_contents);
+        let rect = inner.response.rect;
+        let ctx = ui.ctx();
+
+        let press = ctx.input(|i| {
+            i.pointer
+                .any_pressed()
+                .then(|| i.pointer.press_origin())
+                .flatten()
+        });
+        let last_press = ctx.data_mut(|d| {
+            let frames = d.get_temp_mut_or_default::<ContextFrames>(ContextFrames::id());
+            if press.is_some() {
+                frames.last_press = press;
+            }
+            frames.last_press
+        });
+
+        let focused_rect = ctx
+            .memory(|m| m.focused())
+            .and_then(|id| ctx.read_response(id))
+            .map(|response| response.rect);
+
+        let has_focus = match focused_rect {
+            Some(focused_rect) => rect.contains_rect(focused_rect),
+            None => last_press.is_some_and(|pos| rect.contains(pos)),
+        };
+        if has_focus {
+            Self::push(ctx, key);
+        }
+
+        inner
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.keys.contains(key)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.keys.iter().map(String::as_str)
+    }
+}
+
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Expr {
+    Key(String),
+    Not(Box<Expr>),
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+}
+
+impl Expr {
+    fn eval(&self, context: &CommandContext) -> bool {
+        match self {
+            Self::Key(key) => context.contains(key),
+            Self::Not(expr) => !expr.eval(context),
+            Self::And(exprs) => exprs.iter().all(|expr| expr.eval(context)),
+            Self::Or(exprs) => exprs.iter().any(|expr| expr.eval(context)),
+        }
+    }
+
+    fn specificity(&self) -> usize {
+        match self {
+            Self::Key(_) => 1,
+            Self::Not(_) => 0,
+            Self::And(exprs) => exprs.iter().map(Self::specificity).sum(),
+            Self::Or(exprs) => exprs.iter().map(Self::specificity).min().unwrap_or(0),
+        }
+    }
+}
+
+/// A boolean expression over context keys, e.g. `"tree && !renaming"` or `"canvas || viewport"`.
+///
+/// Supports key names, `!`, `&&`, `||` and parentheses (`&&` binds tighter than `||`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WhenClause {
+    source: String,
+    expr: Expr,
+}
+
+impl WhenClause {
+    pub fn parse(source: &str) -> anyhow::Result<Self> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            pos: 0,
+        };
+        let expr = parser.or()?;
+        if parser.pos != tokens.len() {
+            anyhow::bail!(
+                "Unexpected {:?} in when-clause {source:?}",
+                tokens[parser.pos]
+            );
+        }
+        Ok(Self {
+            source: source.to_owned(),
+            expr,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn eval(&self, context: &CommandContext) -> bool {
+        self.expr.eval(context)
+    }
+
+    /// How specific the clause is: the number of keys that must be present.
+    ///
+    /// `a && b` is more specific than `a`, which is more specific than no clause (0).
+    /// For `a || b` the least specific branch counts; negations count as 0.
+    pub fn specificity(&self) -> usize {
+        self.expr.specificity()
+    }
+}
+
+impl std::fmt::Display for WhenClause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Key(String),
+    Not,
+    And,
+    Or,
+    Open,
+    Close,
+}
+
+fn tokenize(source: &str) -> anyhow::Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '!' => tokens.push(Token::Not),
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            '&' | '|' => {
+                if chars.next_if(|&(_, next)| next == c).is_none() {
+                    anyhow::bail!("Expected `{c}{c}` in when-clause {source:?}");
+                }
+                tokens.push(if c == '&' { Token::And } else { Token::Or });
+            }
+            c if is_key_char(c) => {
+                let mut end = start + c.len_utf8();
+                while let Some((i, c)) = chars.next_if(|&(_, c)| is_key_char(c)) {
+                    end = i + c.len_utf8();
+                }
+                tokens.push(Token::Key(source[start..end].to_owned()));
+            }
+            c => anyhow::bail!("Unexpected {c:?} in when-clause {source:?}"),
+        }
+    }
+    Ok(tokens)
+}
+
+fn is_key_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '.' | '-' | ':')
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn eat(&mut self, token: &Token) -> bool {
+        let matches = self.tokens.get(self.pos) == Some(token);
+        if matches {
+            self.pos += 1;
+        }
+        matches
+    }
+
+    fn or(&mut self) -> anyhow::Result<Expr> {
+        let mut exprs = vec![self.and()?];
+        while self.eat(&Token::Or) {
+            exprs.push(self.and()?);
+        }
+        Ok(if exprs.len() == 1 {
+            exprs.remove(0)
+        } else {
+            Expr::Or(exprs)
+        })
+    }
+
+    fn and(&mut self) -> anyhow::Result<Expr> {
+        let mut exprs = vec![self.unary()?];
+        while self.eat(&Token::And) {
+            exprs.push(self.unary()?);
+        }
+        Ok(if exprs.len() == 1 {
+            exprs.remove(0)
+        } else {
+            Expr::And(exprs)
+        })
+    }
+
+    fn unary(&mut self) -> anyhow::Result<Expr> {
+        if self.eat(&Token::Not) {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.eat(&Token::Open) {
+            let expr = self.or()?;
+            anyhow::ensure!(self.eat(&Token::Close), "Missing `)` in when-clause");
+            return Ok(expr);
+        }
+        match self.tokens.get(self.pos) {
+            Some(Token::Key(key)) => {
+                self.pos += 1;
+                Ok(Expr::Key(key.clone()))
+            }
+            token => anyhow::bail!("Expected a context key, found {token:?}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CommandContext, WhenClause};
+
+    #[test]
+    fn test_active_keys_follow_focus() {
+        let ctx = egui::Context::default();
+        let mut active = Vec::new();
+        for key in [Some("tree"), Some("canvas"), None] {
+            let _ = ctx.run(Default::default(), |ctx| {
+                // 分发在 UI 之前: 看到上一帧的键.
+                let before = CommandContext::active(ctx);
+                if let Some(key) = key {
+                    CommandContext::push(ctx, key);
+                }
+                active.push((before, CommandContext::active(ctx)));
+            });
+        }
+
+        let keys = |keys: &[&str]| CommandContext::from_keys(keys.iter().copied());
+        assert_eq!(
+            active,
+            vec![
+                (keys(&[]), keys(&["tree"])),
+                (keys(&["tree"]), keys(&["canvas"])),
+                (keys(&["canvas"]), keys(&["canvas"])),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_when_clause() {
+        let context = CommandContext::from_keys(["tree", "dragging"]);
+
+        let eval = |source: &str| WhenClause::parse(source).unwrap().eval(&context);
+        assert!(eval("tree"));
+        assert!(!eval("canvas"));
+        assert!(eval("tree && dragging"));
+        assert!(!eval("tree && !dragging"));
+        assert!(eval("canvas || tree"));
+        assert!(eval("!(canvas || renaming) && tree"));
+
+        let specificity = |source: &str| WhenClause::parse(source).unwrap().specificity();
+        assert_eq!(specificity("tree"), 1);
+        assert_eq!(specificity("tree && dragging"), 2);
+        assert_eq!(specificity("tree && !renaming"), 1);
+        assert_eq!(specificity("tree || canvas && dragging"), 1);
+
+        assert!(WhenClause::parse("tree &&").is_err());
+        assert!(WhenClause::parse("tree & canvas").is_err());
+        assert!(WhenClause::parse("(tree").is_err());
+    }
+}
@@ -2,7 +2,27 @@ use std::collections::BTreeSet;
 
 use egui::{Align2, Key, NumExt as _};
 
-use crate::UICommand;
+use crate::components::command_arguments::{ArgumentValue, CommandArguments, ParameterKind};
+use crate::components::command_registry::{CommandIcon, CommandId, CommandRegistry};
+use crate::components::list_item::{ContentContext, CustomContent, LabelContent, list_item_scope};
+use crate::components::palette_history::PaletteHistory;
+use crate::components::palette_provider::{
+    PaletteItem, PaletteMode, PaletteProvider, ProviderCache,
+};
+use crate::{UICommand, UiExt as _};
+
+/// Queries starting with this prefix only list commands.
+pub const COMMAND_PALETTE_COMMAND_PREFIX: &str = ">";
+
+/// How many recently used entries to show for an empty query.
+const NUM_RECENTLY_USED: usize = 5;
+
+/// Initial width of the palette. The user can resize it horizontally.
+const DEFAULT_WIDTH: f32 = 420.0;
+const MIN_WIDTH: f32 = 240.0;
+
+/// Separator between the category and the text of a row.
+const BREADCRUMB_SEPARATOR: &str = " › ";
 
 #[derive(Default)]
 pub struct CommandPalette {
@@ -11,14 +31,18 @@ pub struct CommandPalette {
     selected_alternative: usize,
 }
 
-/// Either a command, or a URL that we want to open.
+/// Either a command, a URL that we want to open, or an item from a [`PaletteProvider`].
 ///
 /// URL opening is the fallback for the command palette and needs some special treatment since
 /// ui commands usually don't have arbitrary state.
 #[derive(Clone)]
-pub enum CommandPaletteAction {
-    UiCommand(UICommand),
+pub enum CommandPaletteAction<C = UICommand, A = ()> {
+    /// A command from the [`CommandRegistry`].
+    Command(C),
     OpenUrl(CommandPaletteUrl),
+
+    /// An item contributed by a [`PaletteProvider`].
+    Item(A),
 }
 
 #[derive(Clone)]
@@ -30,29 +54,28 @@ pub struct CommandPaletteUrl {
     pub command_text: String,
 }
 
-impl CommandPaletteAction {
-    fn text(&self) -> &str {
-        match self {
-            Self::UiCommand(command) => command.text(),
-            Self::OpenUrl(url) => &url.command_text,
-        }
-    }
+/// 面板中的一个分组 (命令或某个数据源).
+struct Section<C, A> {
+    title: String,
+    rows: Vec<FuzzyMatch<C, A>>,
+    is_loading: bool,
+}
 
-    fn tooltip(&self) -> &'static str {
-        match self {
-            Self::UiCommand(command) => command.tooltip(),
-            Self::OpenUrl(_) => {
-                "Try to open this URL in the viewer. If the contents are already loaded, this will select them."
-            }
-        }
-    }
+/// 参数步骤的状态 (见 [`crate::components::command_arguments`]).
+///
+/// 保存在 egui memory 中, 因为 [`CommandPalette`] 不是命令类型的泛型.
+#[derive(Clone)]
+struct ArgumentFlow<C> {
+    command: C,
 
-    fn formatted_kb_shortcut(&self, egui_ctx: &egui::Context) -> Option<String> {
-        match self {
-            Self::UiCommand(command) => command.formatted_kb_shortcut(egui_ctx),
-            Self::OpenUrl(_) => None,
-        }
-    }
+    /// One value per parameter answered so far.
+    values: Vec<ArgumentValue>,
+
+    /// Query of the command list, restored when going back to it.
+    list_query: String,
+
+    /// Shown when `build` rejected the values.
+    error: Option<String>,
 }
 
 impl CommandPalette {
@@ -60,28 +83,87 @@ impl CommandPalette {
         self.visible ^= true;
     }
 
+    /// Open the palette with the given query, e.g. `"@"` to start in symbol mode.
+    pub fn open_with_query(&mut self, query: impl Into<String>) {
+        self.visible = true;
+        self.query = query.into();
+        self.selected_alternative = 0;
+    }
+
     /// Show the command palette, if it is visible.
+    ///
+    /// Lists the visible and enabled commands of `registry`.
+    /// If `parse_url` accepts the query, opening it as a URL is offered as a fallback.
     #[must_use = "Returns the command that was selected"]
-    pub fn show(
+    pub fn show<C: CommandId>(
         &mut self,
         egui_ctx: &egui::Context,
+        registry: &CommandRegistry<C>,
         parse_url: &dyn Fn(&str) -> Option<CommandPaletteUrl>,
-    ) -> Option<CommandPaletteAction> {
-        self.visible &= !egui_ctx.input_mut(|i| i.key_pressed(Key::Escape));
+    ) -> Option<CommandPaletteAction<C>> {
+        self.show_with_providers::<C, ()>(egui_ctx, registry, &mut [], parse_url)
+    }
+
+    /// Like [`Self::show`], but also lists the items of `providers`, one section per provider.
+    #[must_use = "Returns the command that was selected"]
+    pub fn show_with_providers<C: CommandId, A: Clone + Send + Sync + 'static>(
+        &mut self,
+        egui_ctx: &egui::Context,
+        registry: &CommandRegistry<C>,
+        providers: &mut [&mut dyn PaletteProvider<A>],
+        parse_url: &dyn Fn(&str) -> Option<CommandPaletteUrl>,
+    ) -> Option<CommandPaletteAction<C, A>> {
+        let mut flow = egui_ctx.data(|d| d.get_temp::<ArgumentFlow<C>>(Self::flow_id()));
+
+        if egui_ctx.input(|i| i.key_pressed(Key::Escape)) {
+            // Escape steps back out of the arguments before it closes the palette.
+            match &mut flow {
+                Some(current) => {
+                    if !self.step_back(current) {
+                        flow = None;
+                    }
+                    Self::store_flow(egui_ctx, flow.clone());
+                }
+                None => self.visible = false,
+            }
+        }
+
         if !self.visible {
             self.query.clear();
+            egui_ctx.data_mut(|d| {
+                d.remove::<Vec<Option<ProviderCache<A>>>>(Self::cache_id());
+                d.remove::<ArgumentFlow<C>>(Self::flow_id());
+            });
             return None;
         }
 
+        if let Some(flow) = flow {
+            return Self::window(egui_ctx, |ui| self.arguments_ui(ui, registry, flow));
+        }
+
+        let sections = self.sections(egui_ctx, registry, providers, parse_url);
+        Self::window(egui_ctx, |ui| {
+            self.window_content_ui(ui, registry, sections)
+        })
+    }
+
+    fn window<R>(
+        egui_ctx: &egui::Context,
+        add_contents: impl FnOnce(&mut egui::Ui) -> Option<R>,
+    ) -> Option<R> {
         let screen_rect = egui_ctx.content_rect();
-        let width = 300.0;
-        let max_height = 320.0.at_most(screen_rect.height());
+        let max_width = (0.9 * screen_rect.width()).at_least(MIN_WIDTH);
+        let max_height = 360.0.at_most(screen_rect.height());
 
         egui::Window::new("Command Palette")
             .fixed_pos(screen_rect.center() - 0.5 * max_height * egui::Vec2::Y)
-            .fixed_size([width, max_height])
             .pivot(egui::Align2::CENTER_TOP)
-            .resizable(false)
+            .default_width(DEFAULT_WIDTH.at_most(max_width))
+            .min_width(MIN_WIDTH)
+            .max_width(max_width)
+            .min_height(max_height)
+            .max_height(max_height)
+            .resizable([true, false])
             .scroll(false)
             .title_bar(false)
             .show(egui_ctx, |ui| {
@@ -90,18 +172,178 @@ impl CommandPalette {
                     inner_margin: 2.0.into(),
                     ..Default::default()
                 }
-                .show(ui, |ui| self.window_content_ui(ui, parse_url))
+                .show(ui, add_contents)
                 .inner
             })?
             .inner?
     }
 
+    fn cache_id() -> egui::Id {
+        egui::Id::new("command_palette_providers")
+    }
+
+    fn flow_id() -> egui::Id {
+        egui::Id::new("command_palette_arguments")
+    }
+
+    fn store_flow<C: CommandId>(egui_ctx: &egui::Context, flow: Option<ArgumentFlow<C>>) {
+        egui_ctx.data_mut(|d| match flow {
+            Some(flow) => d.insert_temp(Self::flow_id(), flow),
+            None => d.remove::<ArgumentFlow<C>>(Self::flow_id()),
+        });
+    }
+
+    /// Go back one parameter. Returns `false` if we went back to the command list.
+    fn step_back<C>(&mut self, flow: &mut ArgumentFlow<C>) -> bool {
+        self.selected_alternative = 0;
+        flow.error = None;
+        if let Some(value) = flow.values.pop() {
+            self.query = value.to_input_string();
+            true
+        } else {
+            self.query = std::mem::take(&mut flow.list_query);
+            false
+        }
+    }
+
+    /// Ask for the parameters of `command` instead of emitting it right away.
+    fn start_arguments<C: CommandId>(
+        &mut self,
+        egui_ctx: &egui::Context,
+        command: C,
+        arguments: &CommandArguments<C>,
+    ) {
+        let flow = ArgumentFlow {
+            command,
+            values: Vec::new(),
+            list_query: std::mem::take(&mut self.query),
+            error: None,
+        };
+        self.enter_parameter(arguments, 0);
+        Self::store_flow(egui_ctx, Some(flow));
+    }
+
+    fn enter_parameter<C>(&mut self, arguments: &CommandArguments<C>, index: usize) {
+        self.query = arguments
+            .parameters
+            .get(index)
+            .and_then(|parameter| parameter.default_value.clone())
+            .unwrap_or_default();
+        self.selected_alternative = 0;
+    }
+
+    /// The usage history used to rank entries.
+    pub fn history(egui_ctx: &egui::Context) -> PaletteHistory {
+        PaletteHistory::load_from_memory(egui_ctx)
+    }
+
+    pub fn clear_history(egui_ctx: &egui::Context) {
+        PaletteHistory::default().save_to_memory(egui_ctx);
+    }
+
+    /// 根据当前查询收集所有分组 (异步数据源的结果缓存在 egui memory 中).
+    fn sections<C: CommandId, A: Clone + Send + Sync + 'static>(
+        &self,
+        egui_ctx: &egui::Context,
+        registry: &CommandRegistry<C>,
+        providers: &mut [&mut dyn PaletteProvider<A>],
+        parse_url: &dyn Fn(&str) -> Option<CommandPaletteUrl>,
+    ) -> Vec<Section<C, A>> {
+        let mode = PaletteMode::new(&self.query, providers);
+        let history = PaletteHistory::shared(egui_ctx);
+        let now = PaletteHistory::now();
+        let mut sections = Vec::new();
+
+        if let PaletteMode::Default(query) | PaletteMode::Commands(query) = mode {
+            let mut rows = commands_that_match(egui_ctx, registry, query, &history, now);
+            if let PaletteMode::Default(query) = mode
+                && let Some(url) = parse_url(query)
+            {
+                // Add the special open URL command.
+                rows.push(FuzzyMatch {
+                    action: RowAction::OpenUrl(url),
+                    history_key: None,
+                    score: -1,
+                    fuzzy_match: None,
+                });
+            }
+            sections.push(Section {
+                title: "Commands".to_owned(),
+                rows,
+                is_loading: false,
+            });
+        }
+
+        let mut caches: Vec<Option<ProviderCache<A>>> = egui_ctx
+            .data(|d| d.get_temp(Self::cache_id()))
+            .unwrap_or_default();
+        caches.resize_with(providers.len(), || None);
+
+        for (index, provider) in providers.iter_mut().enumerate() {
+            let query = match mode {
+                PaletteMode::Default(query) if provider.prefix().is_none() => query,
+                PaletteMode::Provider(provider_index, query) if provider_index == index => query,
+                _ => continue,
+            };
+
+            let cache =
+                ProviderCache::refresh(&mut caches[index], egui_ctx, &mut **provider, query);
+            let is_loading = cache.poll();
+
+            let title = provider.title();
+            let rows = if provider.filters_itself() {
+                cache
+                    .items
+                    .iter()
+                    .map(|item| FuzzyMatch {
+                        history_key: Some(item.history_key(title)),
+                        action: RowAction::Item(item.clone()),
+                        score: 0,
+                        fuzzy_match: None,
+                    })
+                    .collect()
+            } else {
+                items_that_match(title, &cache.items, query, &history, now)
+            };
+
+            sections.push(Section {
+                title: provider.title().to_owned(),
+                rows,
+                is_loading,
+            });
+        }
+
+        egui_ctx.data_mut(|d| d.insert_temp(Self::cache_id(), caches));
+
+        let query = match mode {
+            PaletteMode::Default(query)
+            | PaletteMode::Commands(query)
+            | PaletteMode::Provider(_, query) => query,
+        };
+        if query.is_empty() {
+            let recent = recently_used(&mut sections, &history);
+            if !recent.is_empty() {
+                sections.insert(
+                    0,
+                    Section {
+                        title: "Recently used".to_owned(),
+                        rows: recent,
+                        is_loading: false,
+                    },
+                );
+            }
+        }
+
+        sections
+    }
+
     #[must_use = "Returns the command that was selected"]
-    fn window_content_ui(
+    fn window_content_ui<C: CommandId, A: Clone>(
         &mut self,
         ui: &mut egui::Ui,
-        parse_url: &dyn Fn(&str) -> Option<CommandPaletteUrl>,
-    ) -> Option<CommandPaletteAction> {
+        registry: &CommandRegistry<C>,
+        sections: Vec<Section<C, A>>,
+    ) -> Option<CommandPaletteAction<C, A>> {
         // Check _before_ we add the `TextEdit`, so it doesn't steal it.
         let enter_pressed = ui.input_mut(|i| i.consume_key(Default::default(), Key::Enter));
 
@@ -118,102 +360,264 @@ impl CommandPalette {
             false
         };
 
-        let selected_command = egui::ScrollArea::vertical()
+        let selected = egui::ScrollArea::vertical()
             .auto_shrink([false, true])
             .show(ui, |ui| {
-                self.alternatives_ui(ui, enter_pressed, scroll_to_selected_alternative, parse_url)
+                self.alternatives_ui(
+                    ui,
+                    registry,
+                    sections,
+                    enter_pressed,
+                    scroll_to_selected_alternative,
+                )
             })
             .inner;
+        let (selected_command, history_key) = selected?;
 
-        if selected_command.is_some() {
-            *self = Default::default();
+        // 需要参数的命令在参数流程完成后才算使用 (见 `arguments_ui`).
+        if let CommandPaletteAction::Command(command) = &selected_command
+            && let Some(arguments) = registry
+                .get(command)
+                .and_then(|command| command.arguments.as_ref())
+        {
+            self.start_arguments(ui.ctx(), command.clone(), arguments);
+            return None;
         }
 
-        selected_command
+        record_usage(ui.ctx(), history_key.as_deref());
+        *self = Default::default();
+        Some(selected_command)
     }
 
+    /// 参数步骤: 标题 (返回按钮 + 命令 › 参数), 输入框, 以及选择列表或校验信息.
     #[must_use = "Returns the command that was selected"]
-    fn alternatives_ui(
+    fn arguments_ui<C: CommandId, A>(
         &mut self,
         ui: &mut egui::Ui,
-        enter_pressed: bool,
-        mut scroll_to_selected_alternative: bool,
-        parse_url: &dyn Fn(&str) -> Option<CommandPaletteUrl>,
-    ) -> Option<CommandPaletteAction> {
-        scroll_to_selected_alternative |= ui.input(|i| i.key_pressed(Key::ArrowUp));
-        scroll_to_selected_alternative |= ui.input(|i| i.key_pressed(Key::ArrowDown));
+        registry: &CommandRegistry<C>,
+        mut flow: ArgumentFlow<C>,
+    ) -> Option<CommandPaletteAction<C, A>> {
+        let Some(command) = registry.get(&flow.command) else {
+            Self::store_flow::<C>(ui.ctx(), None);
+            return None;
+        };
+        let Some(arguments) = command.arguments.as_ref() else {
+            Self::store_flow::<C>(ui.ctx(), None);
+            return None;
+        };
+        let step = flow.values.len();
+        let Some(parameter) = arguments.parameters.get(step) else {
+            Self::store_flow::<C>(ui.ctx(), None);
+            return None;
+        };
 
-        let item_height = 16.0;
-        let font_id = egui::TextStyle::Button.resolve(ui.style());
+        // Check _before_ we add the `TextEdit`, so it doesn't steal it.
+        let enter_pressed = ui.input_mut(|i| i.consume_key(Default::default(), Key::Enter));
+        let back_pressed = self.query.is_empty() && ui.input(|i| i.key_pressed(Key::Backspace));
+
+        let mut go_back = back_pressed;
+        ui.horizontal(|ui| {
+            go_back |= ui
+                .small_button("Back")
+                .on_hover_text("Back (Escape)")
+                .clicked();
+            ui.label(egui::RichText::new(&command.text).strong());
+            ui.weak(BREADCRUMB_SEPARATOR.trim());
+            ui.label(&parameter.name);
+            if arguments.parameters.len() > 1 {
+                ui.weak(format!("({}/{})", step + 1, arguments.parameters.len()));
+            }
+        });
 
-        let mut num_alternatives: usize = 0;
-        let mut selected_command = None;
+        if go_back {
+            let in_flow = self.step_back(&mut flow);
+            Self::store_flow(ui.ctx(), in_flow.then_some(flow));
+            return None;
+        }
+
+        let text_response = ui.add(
+            egui::TextEdit::singleline(&mut self.query)
+                .hint_text(&parameter.hint)
+                .desired_width(f32::INFINITY)
+                .lock_focus(true),
+        );
+        text_response.request_focus();
+        if text_response.changed() {
+            self.selected_alternative = 0;
+            flow.error = None;
+        }
+
+        let value = match &parameter.kind {
+            ParameterKind::Pick(choices) => {
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| self.choices_ui(ui, choices, enter_pressed))
+                    .inner
+            }
+
+            ParameterKind::Text { .. } | ParameterKind::Number { .. } => {
+                let parsed = parameter.parse(&self.query);
+                match (&parsed, &flow.error) {
+                    (_, Some(error)) | (Err(error), None) if !self.query.trim().is_empty() => {
+                        ui.colored_label(ui.visuals().error_fg_color, error);
+                    }
+                    _ => {
+                        ui.weak("Press Enter to confirm, Escape to go back");
+                    }
+                }
+                parsed.ok().filter(|_| enter_pressed)
+            }
+        };
+
+        let Some(value) = value else {
+            Self::store_flow(ui.ctx(), Some(flow));
+            return None;
+        };
+
+        flow.values.push(value);
+        if flow.values.len() < arguments.parameters.len() {
+            self.enter_parameter(arguments, flow.values.len());
+            Self::store_flow(ui.ctx(), Some(flow));
+            return None;
+        }
+
+        if let Some(command) = arguments.build(&flow.values) {
+            record_usage(ui.ctx(), Some(&command_history_key(&flow.command)));
+            Self::store_flow::<C>(ui.ctx(), None);
+            *self = Default::default();
+            Some(CommandPaletteAction::Command(command))
+        } else {
+            flow.values.pop();
+            flow.error = Some("Invalid value".to_owned());
+            Self::store_flow(ui.ctx(), Some(flow));
+            None
+        }
+    }
 
-        for (i, fuzzy_match) in commands_that_match(&self.query, parse_url)
-            .into_iter()
+    /// The choices of a [`ParameterKind::Pick`], fuzzy-filtered by the query.
+    fn choices_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        choices: &[String],
+        enter_pressed: bool,
+    ) -> Option<ArgumentValue> {
+        let query = self.query.trim().to_lowercase();
+        let mut matches: Vec<(usize, isize)> = choices
+            .iter()
             .enumerate()
-        {
-            let command = fuzzy_match.command.clone();
-            let kb_shortcut_text = command.formatted_kb_shortcut(ui.ctx()).unwrap_or_default();
+            .filter_map(|(index, choice)| {
+                if query.is_empty() {
+                    Some((index, 0))
+                } else {
+                    sublime_fuzzy::best_match(&query, choice).map(|m| (index, m.score()))
+                }
+            })
+            .collect();
+        matches.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
 
-            let (rect, response) = ui.allocate_at_least(
-                egui::vec2(ui.available_width(), item_height),
-                egui::Sense::click(),
-            );
+        self.selected_alternative = self.selected_alternative.saturating_sub(
+            ui.input_mut(|i| i.count_and_consume_key(Default::default(), Key::ArrowUp)),
+        );
+        self.selected_alternative = self.selected_alternative.saturating_add(
+            ui.input_mut(|i| i.count_and_consume_key(Default::default(), Key::ArrowDown)),
+        );
+        self.selected_alternative = self
+            .selected_alternative
+            .clamp(0, matches.len().saturating_sub(1));
 
-            let response = response.on_hover_text(command.tooltip());
+        if matches.is_empty() {
+            ui.weak("No matching choices");
+            return None;
+        }
 
-            if response.clicked() {
-                selected_command = Some(command.clone());
+        let mut chosen = None;
+        list_item_scope(ui, "command_palette_choices", |ui| {
+            for (i, &(index, _)) in matches.iter().enumerate() {
+                let selected = i == self.selected_alternative;
+                let response = ui
+                    .list_item()
+                    .selected(selected)
+                    .show_flat(ui, LabelContent::new(choices[index].as_str()));
+                if response.clicked() || (selected && enter_pressed) {
+                    chosen = Some(ArgumentValue::Pick {
+                        index,
+                        text: choices[index].clone(),
+                    });
+                }
             }
+        });
+        chosen
+    }
 
-            let selected = i == self.selected_alternative;
-            let style = ui.style().interact_selectable(&response, selected);
+    /// Returns the selected row and its key in the [`PaletteHistory`].
+    #[must_use = "Returns the command that was selected"]
+    fn alternatives_ui<C: CommandId, A: Clone>(
+        &mut self,
+        ui: &mut egui::Ui,
+        registry: &CommandRegistry<C>,
+        sections: Vec<Section<C, A>>,
+        enter_pressed: bool,
+        mut scroll_to_selected_alternative: bool,
+    ) -> Option<(CommandPaletteAction<C, A>, Option<String>)> {
+        scroll_to_selected_alternative |= ui.input(|i| i.key_pressed(Key::ArrowUp));
+        scroll_to_selected_alternative |= ui.input(|i| i.key_pressed(Key::ArrowDown));
 
-            if selected {
-                ui.painter().rect_filled(
-                    rect.expand(1.0),
-                    style.corner_radius,
-                    ui.visuals().selection.bg_fill,
-                );
+        // 只有多于一个分组时才显示分组标题.
+        let show_headers = sections.len() > 1;
+
+        let mut num_alternatives: usize = 0;
+        let mut selected_command = None;
 
-                if enter_pressed {
-                    selected_command = Some(command);
+        list_item_scope(ui, "command_palette_rows", |ui| {
+            for section in sections {
+                if section.rows.is_empty() && !section.is_loading {
+                    continue;
                 }
 
-                if scroll_to_selected_alternative {
-                    ui.scroll_to_rect(rect, None);
+                if show_headers {
+                    ui.add_space(4.0);
+                    ui.label(egui::RichText::new(&section.title).small().weak());
+                    ui.add_space(2.0);
                 }
-            }
 
-            let text = format_match(&fuzzy_match, &font_id, style.text_color());
+                if section.is_loading {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.weak("Loading…");
+                    });
+                }
 
-            // TODO(emilk): shorten long text using '…'
-            let galley = text.into_galley(
-                ui,
-                Some(egui::TextWrapMode::Extend),
-                f32::INFINITY,
-                egui::FontSelection::default(),
-            );
-            let text_rect = Align2::LEFT_CENTER
-                .anchor_rect(egui::Rect::from_min_size(rect.left_center(), galley.size()));
-            ui.painter()
-                .galley(text_rect.min, galley, style.text_color());
-
-            ui.painter().text(
-                rect.right_center(),
-                Align2::RIGHT_CENTER,
-                kb_shortcut_text,
-                font_id.clone(),
-                if selected {
-                    style.text_color()
-                } else {
-                    ui.visuals().weak_text_color()
-                },
-            );
+                for fuzzy_match in section.rows {
+                    let i = num_alternatives;
+                    let selected = i == self.selected_alternative;
+                    let has_description = !fuzzy_match.action.tooltip(registry).is_empty();
+
+                    let response = ui
+                        .list_item()
+                        .selected(selected)
+                        .with_height(row_height(ui, has_description))
+                        .show_flat(
+                            ui,
+                            CustomContent::new(|ui, context| {
+                                row_ui(ui, context, registry, &fuzzy_match);
+                            }),
+                        );
+
+                    if response.clicked() || (selected && enter_pressed) {
+                        selected_command = Some((
+                            fuzzy_match.action.clone().into(),
+                            fuzzy_match.history_key.clone(),
+                        ));
+                    }
 
-            num_alternatives += 1;
-        }
+                    if selected && scroll_to_selected_alternative {
+                        ui.scroll_to_rect(response.rect, None);
+                    }
+
+                    num_alternatives += 1;
+                }
+            }
+        });
 
         if num_alternatives == 0 {
             ui.weak("No matching results");
@@ -235,76 +639,368 @@ impl CommandPalette {
     }
 }
 
-struct FuzzyMatch {
-    command: CommandPaletteAction,
+/// 面板中的一行. 提供者条目直接携带其 [`PaletteItem`].
+#[derive(Clone)]
+enum RowAction<C, A> {
+    Command(C),
+    OpenUrl(CommandPaletteUrl),
+    Item(PaletteItem<A>),
+}
+
+impl<C: CommandId, A: Clone> RowAction<C, A> {
+    fn text<'a>(&'a self, registry: &'a CommandRegistry<C>) -> &'a str {
+        match self {
+            Self::Command(command) => registry
+                .get(command)
+                .map_or("", |command| command.text.as_str()),
+            Self::OpenUrl(url) => &url.command_text,
+            Self::Item(item) => &item.text,
+        }
+    }
+
+    fn tooltip<'a>(&'a self, registry: &'a CommandRegistry<C>) -> &'a str {
+        match self {
+            Self::Command(command) => registry
+                .get(command)
+                .map_or("", |command| command.tooltip.as_str()),
+            Self::OpenUrl(_) => {
+                "Try to open this URL in the viewer. If the contents are already loaded, this will select them."
+            }
+            Self::Item(item) => &item.tooltip,
+        }
+    }
+
+    fn category<'a>(&'a self, registry: &'a CommandRegistry<C>) -> Option<&'a str> {
+        match self {
+            Self::Command(command) => registry
+                .get(command)
+                .and_then(|command| command.category.as_deref()),
+            Self::OpenUrl(_) => None,
+            Self::Item(item) => item.category.as_deref(),
+        }
+    }
+
+    fn icon(&self, registry: &CommandRegistry<C>) -> Option<CommandIcon> {
+        match self {
+            Self::Command(command) => registry.get(command).and_then(|command| command.icon),
+            Self::OpenUrl(_) => None,
+            Self::Item(item) => item.icon,
+        }
+    }
+
+    fn formatted_kb_shortcut(
+        &self,
+        registry: &CommandRegistry<C>,
+        egui_ctx: &egui::Context,
+    ) -> Option<String> {
+        match self {
+            Self::Command(command) => registry.formatted_kb_shortcut(command, egui_ctx),
+            Self::OpenUrl(_) => None,
+            Self::Item(item) => item.shortcut_text.clone(),
+        }
+    }
+}
+
+impl<C, A> From<RowAction<C, A>> for CommandPaletteAction<C, A> {
+    fn from(action: RowAction<C, A>) -> Self {
+        match action {
+            RowAction::Command(command) => Self::Command(command),
+            RowAction::OpenUrl(url) => Self::OpenUrl(url),
+            RowAction::Item(item) => Self::Item(item.action),
+        }
+    }
+}
+
+struct FuzzyMatch<C, A> {
+    action: RowAction<C, A>,
+
+    /// Key in the [`PaletteHistory`], if usage of this row is tracked.
+    history_key: Option<String>,
+
     score: isize,
     fuzzy_match: Option<sublime_fuzzy::Match>,
 }
 
-fn commands_that_match(
+fn command_history_key<C: CommandId>(id: &C) -> String {
+    format!("command:{}", id.persistence_key())
+}
+
+fn record_usage(egui_ctx: &egui::Context, key: Option<&str>) {
+    if let Some(key) = key {
+        let mut history = PaletteHistory::load_from_memory(egui_ctx);
+        history.record(key, PaletteHistory::now());
+        history.save_to_memory(egui_ctx);
+    }
+}
+
+/// Take the most recently used rows out of `sections`, most recent first.
+fn recently_used<C, A>(
+    sections: &mut [Section<C, A>],
+    history: &PaletteHistory,
+) -> Vec<FuzzyMatch<C, A>> {
+    let mut recent = Vec::new();
+    for key in history.recent() {
+        if recent.len() == NUM_RECENTLY_USED {
+            break;
+        }
+        for section in sections.iter_mut() {
+            if let Some(index) = section
+                .rows
+                .iter()
+                .position(|row| row.history_key.as_deref() == Some(key))
+            {
+                recent.push(section.rows.remove(index));
+                break;
+            }
+        }
+    }
+    recent
+}
+
+fn commands_that_match<C: CommandId, A>(
+    egui_ctx: &egui::Context,
+    registry: &CommandRegistry<C>,
     query: &str,
-    parse_url: &dyn Fn(&str) -> Option<CommandPaletteUrl>,
-) -> Vec<FuzzyMatch> {
-    use strum::IntoEnumIterator as _;
+    history: &PaletteHistory,
+    now: f64,
+) -> Vec<FuzzyMatch<C, A>> {
+    let available = registry
+        .iter()
+        .filter(|command| command.is_visible(egui_ctx) && command.is_enabled(egui_ctx));
 
     if query.is_empty() {
-        UICommand::iter()
+        available
             .map(|command| FuzzyMatch {
-                command: CommandPaletteAction::UiCommand(command),
+                action: RowAction::Command(command.id.clone()),
+                history_key: Some(command_history_key(&command.id)),
                 score: 0,
                 fuzzy_match: None,
             })
             .collect()
     } else {
         let query_lowercase = query.to_lowercase();
-        let mut matches: Vec<_> = UICommand::iter()
+        let mut matches: Vec<_> = available
             .filter_map(|command| {
-                let target_text = command.text();
+                let target_text = &command.text;
                 sublime_fuzzy::best_match(&query_lowercase, target_text).map(|fuzzy_match| {
+                    let history_key = command_history_key(&command.id);
                     FuzzyMatch {
-                        command: CommandPaletteAction::UiCommand(command),
-                        score: fuzzy_match.score(),
+                        action: RowAction::Command(command.id.clone()),
+                        score: fuzzy_match.score() + history.score_bonus(&history_key, now),
+                        history_key: Some(history_key),
                         fuzzy_match: Some(fuzzy_match),
                     }
                 })
             })
             .collect();
 
-        // Add the special open URL command.
-        if let Some(url) = parse_url(query) {
-            matches.push(FuzzyMatch {
-                command: CommandPaletteAction::OpenUrl(url),
-                score: -1,
+        matches.sort_by_key(|m| -m.score); // highest score first
+        matches
+    }
+}
+
+fn items_that_match<C, A: Clone>(
+    title: &str,
+    items: &[PaletteItem<A>],
+    query: &str,
+    history: &PaletteHistory,
+    now: f64,
+) -> Vec<FuzzyMatch<C, A>> {
+    if query.is_empty() {
+        return items
+            .iter()
+            .map(|item| FuzzyMatch {
+                action: RowAction::Item(item.clone()),
+                history_key: Some(item.history_key(title)),
+                score: 0,
                 fuzzy_match: None,
-            });
+            })
+            .collect();
+    }
+
+    let query_lowercase = query.to_lowercase();
+    let mut matches: Vec<_> = items
+        .iter()
+        .filter_map(|item| {
+            sublime_fuzzy::best_match(&query_lowercase, &item.text).map(|fuzzy_match| {
+                let history_key = item.history_key(title);
+                FuzzyMatch {
+                    action: RowAction::Item(item.clone()),
+                    score: fuzzy_match.score() + history.score_bonus(&history_key, now),
+                    history_key: Some(history_key),
+                    fuzzy_match: Some(fuzzy_match),
+                }
+            })
+        })
+        .collect();
+
+    matches.sort_by_key(|m| -m.score); // highest score first
+    matches
+}
+
+/// Height of a row: one line, or two if the row has a description.
+fn row_height(ui: &egui::Ui, has_description: bool) -> f32 {
+    let single = crate::DesignTokens::list_item_height();
+    if has_description {
+        let text_height = ui.text_style_height(&egui::TextStyle::Button)
+            + ui.text_style_height(&egui::TextStyle::Small);
+        (text_height + 8.0).at_least(single)
+    } else {
+        single
+    }
+}
+
+/// 绘制一行: 图标, 分类面包屑 + 文本 (高亮匹配字符), 弱化的描述行, 右侧快捷键.
+///
+/// 过长的文本以 `…` 截断, 鼠标悬停时显示完整文本.
+fn row_ui<C: CommandId, A: Clone>(
+    ui: &egui::Ui,
+    context: &ContentContext<'_>,
+    registry: &CommandRegistry<C>,
+    fuzzy_match: &FuzzyMatch<C, A>,
+) {
+    let tokens = ui.tokens();
+    let visuals = context.visuals;
+    let painter = ui.painter();
+    let action = &fuzzy_match.action;
+
+    let text_color = visuals.text_color();
+    let weak_color = if visuals.selected {
+        text_color.gamma_multiply(0.75)
+    } else {
+        ui.visuals().weak_text_color()
+    };
+
+    let mut rect = context.rect;
+
+    // Icon column, always reserved so the texts line up.
+    let icon_size = tokens.small_icon_size;
+    let icon_rect = egui::Rect::from_center_size(
+        rect.left_center() + egui::vec2(icon_size.x / 2.0, 0.0),
+        icon_size,
+    );
+    match action.icon(registry) {
+        Some(CommandIcon::Icon(icon)) => {
+            icon.as_image()
+                .tint(visuals.icon_tint())
+                .paint_at(ui, icon_rect);
         }
+        Some(CommandIcon::Phosphor(glyph)) => {
+            painter.text(
+                icon_rect.center(),
+                Align2::CENTER_CENTER,
+                glyph,
+                egui::FontId::proportional(icon_rect.height() * 0.8),
+                visuals.icon_tint(),
+            );
+        }
+        None => {}
+    }
+    rect.min.x += icon_size.x + tokens.text_to_icon_padding();
+
+    // Shortcut on the right.
+    let button_font = egui::TextStyle::Button.resolve(ui.style());
+    if let Some(shortcut) = action.formatted_kb_shortcut(registry, ui.ctx()) {
+        let galley = painter.layout_no_wrap(shortcut, button_font.clone(), weak_color);
+        let pos = Align2::RIGHT_CENTER
+            .align_size_within_rect(galley.size(), rect)
+            .min;
+        rect.max.x -= galley.size().x + 2.0 * tokens.text_to_icon_padding();
+        painter.galley(pos, galley, weak_color);
+    }
 
-        matches.sort_by_key(|m| -m.score); // highest score first
-        matches
+    let width = rect.width().at_least(0.0);
+    let category = action.category(registry);
+    let text = action.text(registry);
+    let description = action.tooltip(registry);
+
+    let mut title_job = title_job(
+        ui,
+        visuals.selected,
+        category,
+        text,
+        fuzzy_match,
+        &button_font,
+        text_color,
+        weak_color,
+    );
+    title_job.wrap = egui::text::TextWrapping::truncate_at_width(width);
+    let title = painter.layout_job(title_job);
+
+    let description = (!description.is_empty()).then(|| {
+        let mut job = egui::text::LayoutJob::single_section(
+            description.to_owned(),
+            egui::text::TextFormat::simple(egui::TextStyle::Small.resolve(ui.style()), weak_color),
+        );
+        job.wrap = egui::text::TextWrapping::truncate_at_width(width);
+        painter.layout_job(job)
+    });
+
+    let total_height = title.size().y + description.as_ref().map_or(0.0, |galley| galley.size().y);
+    let mut pos = egui::pos2(rect.left(), rect.center().y - 0.5 * total_height);
+
+    let elided = title.elided || description.as_ref().is_some_and(|galley| galley.elided);
+    let title_height = title.size().y;
+    painter.galley(pos, title, text_color);
+    pos.y += title_height;
+    if let Some(description) = description {
+        painter.galley(pos, description, weak_color);
+    }
+
+    if elided {
+        let full_text = match category {
+            Some(category) => format!("{category}{BREADCRUMB_SEPARATOR}{text}"),
+            None => text.to_owned(),
+        };
+        let hover_text = if action.tooltip(registry).is_empty() {
+            full_text
+        } else {
+            format!("{full_text}\n\n{}", action.tooltip(registry))
+        };
+        context.response.clone().on_hover_text(hover_text);
     }
 }
 
-fn format_match(
-    m: &FuzzyMatch,
+/// `Category › Text`, with the fuzzy-matched characters highlighted in the selection colors.
+#[expect(clippy::too_many_arguments)]
+fn title_job<C: CommandId, A: Clone>(
+    ui: &egui::Ui,
+    row_selected: bool,
+    category: Option<&str>,
+    text: &str,
+    fuzzy_match: &FuzzyMatch<C, A>,
     font_id: &egui::FontId,
     text_color: egui::Color32,
-) -> egui::WidgetText {
-    let target_text = m.command.text();
-
-    if let Some(fm) = &m.fuzzy_match {
-        let matched_indices: BTreeSet<_> = fm.matched_indices().collect();
+    weak_color: egui::Color32,
+) -> egui::text::LayoutJob {
+    let mut job = egui::text::LayoutJob::default();
+
+    if let Some(category) = category {
+        let format = egui::text::TextFormat::simple(font_id.clone(), weak_color);
+        job.append(category, 0.0, format.clone());
+        job.append(BREADCRUMB_SEPARATOR, 0.0, format);
+    }
 
-        let mut job = egui::text::LayoutJob::default();
-        for (i, c) in target_text.chars().enumerate() {
-            let mut format = egui::text::TextFormat::simple(font_id.clone(), text_color);
-            if matched_indices.contains(&i) {
+    let matched_indices: BTreeSet<usize> = fuzzy_match
+        .fuzzy_match
+        .as_ref()
+        .map(|fm| fm.matched_indices().copied().collect())
+        .unwrap_or_default();
+
+    let selection = ui.visuals().selection;
+    for (i, c) in text.chars().enumerate() {
+        let mut format = egui::text::TextFormat::simple(font_id.clone(), text_color);
+        if matched_indices.contains(&i) {
+            if row_selected {
+                // The row background already uses the selection color.
                 format.underline = egui::Stroke::new(1.0, text_color);
+            } else {
+                format.color = selection.stroke.color;
+                format.background = selection.bg_fill.gamma_multiply(0.35);
             }
-            job.append(&c.to_string(), 0.0, format);
         }
-
-        job.into()
-    } else {
-        egui::RichText::new(target_text).color(text_color).into()
+        job.append(&c.to_string(), 0.0, format);
     }
+
+    job
 }
@@ -0,0 +1,860 @@
+//! 应用自定义命令注册表.
+//!
+//! 应用注册自己的命令 (文本, 提示, 图标, 默认快捷键, 分类, 启用/可见条件),
+//! [`crate::CommandPalette`]、快捷键分发和菜单按钮都基于注册表工作.
+//! [`crate::UICommand`] 作为可选的预设: [`crate::UICommand::registry`].
+//!
+//! ```ignore
+//! #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! enum AppCommand { Save, Export }
+//! impl CommandId for AppCommand {}
+//!
+//! let mut registry = CommandRegistry::new();
+//! registry.register(
+//!     Command::new(AppCommand::Save, "Save")
+//!         .tooltip("Save the current document")
+//!         .shortcut(KeyboardShortcut::new(Modifiers::COMMAND, Key::S))
+//!         .category("File"),
+//! );
+//!
+//! if let Some(command) = registry.listen_for_kb_shortcut(ctx) {
+//!     // ...
+//! }
+//! registry.pending_key_sequence_ui(ctx); // 显示 `Ctrl+K …` 等待提示
+//! ```
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use egui::KeyboardShortcut;
+use indexmap::IndexMap;
+use smallvec::SmallVec;
+
+use crate::components::command_arguments::{ArgumentValue, CommandArguments, CommandParameter};
+use crate::components::command_context::{CommandContext, WhenClause};
+use crate::components::key_sequence::{
+    DEFAULT_KEY_SEQUENCE_TIMEOUT, KeySequence, PendingKeySequence,
+};
+use crate::components::keymap::{Bindings, Keymap};
+use crate::extensions::context_ext::ContextExt as _;
+
+/// Identifies a command in a [`CommandRegistry`].
+///
+/// Usually a plain `enum` owned by the app: `impl CommandId for AppCommand {}`.
+pub trait CommandId:
+    Clone + Eq + std::hash::Hash + std::fmt::Debug + Send + Sync + 'static
+{
+    /// Stable key used when persisting data about this command.
+    ///
+    /// Defaults to the `Debug` representation.
+    fn persistence_key(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+impl CommandId for &'static str {
+    fn persistence_key(&self) -> String {
+        (*self).to_owned()
+    }
+}
+
+impl CommandId for String {
+    fn persistence_key(&self) -> String {
+        self.clone()
+    }
+}
+
+/// Interface for sending commands from a [`CommandRegistry`].
+pub trait CommandSender<C> {
+    fn send(&self, command: C);
+}
+
+/// 命令图标.
+#[derive(Clone, Copy, Debug)]
+pub enum CommandIcon {
+    /// One of the kit's SVG icons.
+    Icon(&'static crate::Icon),
+
+    /// A Phosphor icon glyph.
+    Phosphor(&'static str),
+}
+
+/// Predicate evaluated every frame, e.g. to enable/disable a command.
+pub type CommandPredicate = Arc<dyn Fn(&egui::Context) -> bool + Send + Sync>;
+
+/// A single registered command.
+#[derive(Clone)]
+pub struct Command<C: CommandId> {
+    pub id: C,
+    pub text: String,
+    pub tooltip: String,
+    pub icon: Option<CommandIcon>,
+
+    /// All default keyboard shortcuts, with the primary first.
+    pub shortcuts: SmallVec<[KeyboardShortcut; 2]>,
+
+    /// Default multi-step shortcuts, e.g. `Ctrl+K Ctrl+S`. Come after [`Self::shortcuts`].
+    pub sequences: SmallVec<[KeySequence; 1]>,
+
+    /// Used to group commands, e.g. `"File"` or `"View"`.
+    pub category: Option<String>,
+
+    /// Does this command open a link (shows a pointing-hand cursor)?
+    pub is_link: bool,
+
+    /// Only dispatch the shortcuts while this clause matches the active [`CommandContext`].
+    pub when: Option<WhenClause>,
+
+    /// Values the [`crate::CommandPalette`] asks for before emitting the command.
+    pub arguments: Option<CommandArguments<C>>,
+
+    enabled: Option<CommandPredicate>,
+    visible: Option<CommandPredicate>,
+}
+
+impl<C: CommandId> Command<C> {
+    pub fn new(id: C, text: impl Into<String>) -> Self {
+        Self {
+            id,
+            text: text.into(),
+            tooltip: String::new(),
+            icon: None,
+            shortcuts: SmallVec::new(),
+            sequences: SmallVec::new(),
+            category: None,
+            is_link: false,
+            when: None,
+            arguments: None,
+            enabled: None,
+            visible: None,
+        }
+    }
+
+    #[inline]
+    pub fn tooltip(mut self, tooltip: impl Into<String>) -> Self {
+        self.tooltip = tooltip.into();
+        self
+    }
+
+    #[inline]
+    pub fn icon(mut self, icon: &'static crate::Icon) -> Self {
+        self.icon = Some(CommandIcon::Icon(icon));
+        self
+    }
+
+    #[inline]
+    pub fn phosphor_icon(mut self, icon: &'static str) -> Self {
+        self.icon = Some(CommandIcon::Phosphor(icon));
+        self
+    }
+
+    /// Add a default keyboard shortcut. The first one added is the primary shortcut.
+    #[inline]
+    pub fn shortcut(mut self, shortcut: KeyboardShortcut) -> Self {
+        self.shortcuts.push(shortcut);
+        self
+    }
+
+    #[inline]
+    pub fn shortcuts(mut self, shortcuts: impl IntoIterator<Item = KeyboardShortcut>) -> Self {
+        self.shortcuts.extend(shortcuts);
+        self
+    }
+
+    /// Add a default multi-step shortcut, e.g. `Ctrl+K Ctrl+S` or `G G`.
+    #[inline]
+    pub fn sequence(mut self, steps: impl IntoIterator<Item = KeyboardShortcut>) -> Self {
+        self.sequences.push(KeySequence::new(steps));
+        self
+    }
+
+    #[inline]
+    pub fn category(mut self, category: impl Into<String>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    #[inline]
+    pub fn link(mut self, is_link: bool) -> Self {
+        self.is_link = is_link;
+        self
+    }
+
+    /// Only dispatch the shortcuts of this command in the given context,
+    /// e.g. `"tree"` or `"canvas && !textInputFocus"`.
+    ///
+    /// Panics if the expression is invalid, see [`Self::try_when`] for expressions that are not
+    /// known at compile time (e.g. loaded from a config file).
+    #[inline]
+    pub fn when(self, expression: &str) -> Self {
+        match self.try_when(expression) {
+            Ok(command) => command,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Like [`Self::when`], but returns an error if the expression is invalid.
+    pub fn try_when(mut self, expression: &str) -> anyhow::Result<Self> {
+        self.when = Some(WhenClause::parse(expression)?);
+        Ok(self)
+    }
+
+    /// Ask for `parameters` in the command palette, then emit the command returned by `build`.
+    ///
+    /// See [`crate::components::command_arguments`].
+    #[inline]
+    pub fn arguments(
+        mut self,
+        parameters: impl IntoIterator<Item = CommandParameter>,
+        build: impl Fn(&[ArgumentValue]) -> Option<C> + Send + Sync + 'static,
+    ) -> Self {
+        self.arguments = Some(CommandArguments::new(parameters, build));
+        self
+    }
+
+    /// Only allow running the command while `enabled` returns true.
+    #[inline]
+    pub fn enabled_when(
+        mut self,
+        enabled: impl Fn(&egui::Context) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.enabled = Some(Arc::new(enabled));
+        self
+    }
+
+    /// Only show the command (menus, palette) while `visible` returns true.
+    #[inline]
+    pub fn visible_when(
+        mut self,
+        visible: impl Fn(&egui::Context) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.visible = Some(Arc::new(visible));
+        self
+    }
+
+    /// Like [`Self::enabled_when`], for an already registered command.
+    pub fn set_enabled_when(
+        &mut self,
+        enabled: impl Fn(&egui::Context) -> bool + Send + Sync + 'static,
+    ) {
+        self.enabled = Some(Arc::new(enabled));
+    }
+
+    pub fn is_enabled(&self, egui_ctx: &egui::Context) -> bool {
+        self.enabled
+            .as_ref()
+            .is_none_or(|enabled| enabled(egui_ctx))
+    }
+
+    pub fn is_visible(&self, egui_ctx: &egui::Context) -> bool {
+        self.visible
+            .as_ref()
+            .is_none_or(|visible| visible(egui_ctx))
+    }
+
+    /// Does the [`Self::when`] clause match `context`?
+    pub fn is_active_in(&self, context: &CommandContext) -> bool {
+        self.when.as_ref().is_none_or(|when| when.eval(context))
+    }
+
+    /// See [`WhenClause::specificity`].
+    pub fn specificity(&self) -> usize {
+        self.when.as_ref().map_or(0, WhenClause::specificity)
+    }
+
+    /// Primary keyboard shortcut
+    pub fn primary_kb_shortcut(&self) -> Option<KeyboardShortcut> {
+        self.shortcuts.first().copied()
+    }
+
+    /// All default bindings: single shortcuts first, then sequences.
+    pub fn default_bindings(&self) -> Bindings {
+        self.shortcuts
+            .iter()
+            .map(|shortcut| KeySequence::from(*shortcut))
+            .chain(self.sequences.iter().cloned())
+            .collect()
+    }
+}
+
+/// All commands known to the app, in registration order.
+pub struct CommandRegistry<C: CommandId> {
+    commands: IndexMap<C, Command<C>>,
+
+    /// User overrides of the default shortcuts.
+    keymap: Keymap,
+
+    /// Max time between two steps of a multi-step shortcut.
+    key_sequence_timeout: Duration,
+}
+
+impl<C: CommandId> Default for CommandRegistry<C> {
+    fn default() -> Self {
+        Self {
+            commands: IndexMap::new(),
+            keymap: Keymap::default(),
+            key_sequence_timeout: DEFAULT_KEY_SEQUENCE_TIMEOUT,
+        }
+    }
+}
+
+impl<C: CommandId> Clone for CommandRegistry<C> {
+    fn clone(&self) -> Self {
+        Self {
+            commands: self.commands.clone(),
+            keymap: self.keymap.clone(),
+            key_sequence_timeout: self.key_sequence_timeout,
+        }
+    }
+}
+
+impl<C: CommandId> CommandRegistry<C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a command, replacing any previous command with the same id.
+    pub fn register(&mut self, command: Command<C>) -> &mut Self {
+        self.commands.insert(command.id.clone(), command);
+        self
+    }
+
+    pub fn unregister(&mut self, id: &C) -> Option<Command<C>> {
+        self.commands.shift_remove(id)
+    }
+
+    pub fn get(&self, id: &C) -> Option<&Command<C>> {
+        self.commands.get(id)
+    }
+
+    pub fn get_mut(&mut self, id: &C) -> Option<&mut Command<C>> {
+        self.commands.get_mut(id)
+    }
+
+    /// All commands, in registration order.
+    pub fn iter(&self) -> impl Iterator<Item = &Command<C>> {
+        self.commands.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    pub fn keymap_mut(&mut self) -> &mut Keymap {
+        &mut self.keymap
+    }
+
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
+    }
+
+    pub fn key_sequence_timeout(&self) -> Duration {
+        self.key_sequence_timeout
+    }
+
+    /// Max time between two steps of a multi-step shortcut before it is abandoned.
+    pub fn set_key_sequence_timeout(&mut self, timeout: Duration) {
+        self.key_sequence_timeout = timeout;
+    }
+
+    /// Effective key bindings of a command: the user override if any, else the defaults.
+    pub fn bindings(&self, id: &C) -> Bindings {
+        if let Some(bindings) = self.keymap.get_override(id) {
+            return bindings.clone();
+        }
+        self.get(id)
+            .map(Command::default_bindings)
+            .unwrap_or_default()
+    }
+
+    /// Effective primary key binding
+    pub fn primary_binding(&self, id: &C) -> Option<KeySequence> {
+        self.bindings(id).into_iter().next()
+    }
+
+    pub fn is_enabled(&self, id: &C, egui_ctx: &egui::Context) -> bool {
+        self.get(id)
+            .is_some_and(|command| command.is_enabled(egui_ctx))
+    }
+
+    pub fn is_visible(&self, id: &C, egui_ctx: &egui::Context) -> bool {
+        self.get(id)
+            .is_some_and(|command| command.is_visible(egui_ctx))
+    }
+
+    /// Return the keyboard shortcut for this command, nicely formatted
+    pub fn formatted_kb_shortcut(&self, id: &C, egui_ctx: &egui::Context) -> Option<String> {
+        // Note: we only show the primary shortcut to the user.
+        // The fallbacks are there for people who have muscle memory for the other shortcuts.
+        self.primary_binding(id)
+            .map(|sequence| sequence.format(egui_ctx))
+    }
+
+    /// Check the input for any shortcut of an enabled command, consuming it.
+    ///
+    /// Multi-step shortcuts are tracked across frames: the first steps are consumed
+    /// and remembered until the sequence completes, a non-matching key is pressed,
+    /// `Escape` is pressed or [`Self::key_sequence_timeout`] expires.
+    ///
+    /// Longer sequences take priority: if the keys pressed so far trigger a command but also
+    /// start a longer sequence (e.g. `G` and `G G`), the command is only triggered once the
+    /// timeout expires or a non-matching key is pressed.
+    #[must_use = "Returns the Command that was triggered by some keyboard shortcut"]
+    pub fn listen_for_kb_shortcut(&self, egui_ctx: &egui::Context) -> Option<C> {
+        if crate::components::KeybindingEditor::is_recording(egui_ctx) {
+            PendingKeySequence::clear(egui_ctx);
+            return None; // 正在录制新的快捷键
+        }
+
+        let bindings = self.active_bindings(egui_ctx);
+
+        let mut pending = PendingKeySequence::load(egui_ctx);
+        if pending.is_expired(self.key_sequence_timeout) {
+            // 没有等到更长的序列: 触发已完成的较短绑定.
+            PendingKeySequence::clear(egui_ctx);
+            if let Some(completed) = Self::completed(&bindings, &pending.steps) {
+                return Some(completed);
+            }
+            pending = PendingKeySequence::default();
+        }
+
+        if !pending.is_empty()
+            && egui_ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape))
+        {
+            PendingKeySequence::clear(egui_ctx);
+            return None;
+        }
+
+        // 只考虑以已按下前缀开头的序列的下一步.
+        let candidates: Vec<(KeyboardShortcut, KeyboardShortcut)> = bindings
+            .iter()
+            .filter(|(sequence, _)| {
+                sequence.len() > pending.steps.len() && sequence.starts_with(&pending.steps)
+            })
+            .map(|(sequence, _)| {
+                let step = sequence.steps()[pending.steps.len()];
+                (step, step)
+            })
+            .collect();
+
+        let Some(step) = consume_kb_shortcut(egui_ctx, candidates.into_iter()) else {
+            if !pending.is_empty() {
+                let other_key_pressed = egui_ctx.input(|i| {
+                    i.events
+                        .iter()
+                        .any(|event| matches!(event, egui::Event::Key { pressed: true, .. }))
+                });
+                if other_key_pressed {
+                    // 不匹配的按键: 放弃序列 (已完成的较短绑定仍然触发).
+                    let completed = Self::completed(&bindings, &pending.steps);
+                    PendingKeySequence::clear(egui_ctx);
+                    return completed;
+                } else if let Some(last) = pending.last_key_time {
+                    egui_ctx.request_repaint_after(
+                        self.key_sequence_timeout.saturating_sub(last.elapsed()),
+                    );
+                }
+            }
+            pending.store(egui_ctx);
+            return None;
+        };
+
+        pending.steps.push(step);
+        pending.last_key_time = Some(web_time::Instant::now());
+
+        // 还有以此开头的更长序列时先等待.
+        let has_longer = bindings.iter().any(|(sequence, _)| {
+            sequence.len() > pending.steps.len() && sequence.starts_with(&pending.steps)
+        });
+        let completed = Self::completed(&bindings, &pending.steps);
+
+        if completed.is_some() && !has_longer {
+            pending = PendingKeySequence::default();
+        } else {
+            egui_ctx.request_repaint_after(self.key_sequence_timeout);
+        }
+        pending.store(egui_ctx);
+
+        if has_longer { None } else { completed }
+    }
+
+    /// The command bound to exactly `steps`.
+    ///
+    /// `bindings` is sorted by specificity, so the most specific command wins.
+    fn completed(bindings: &[(KeySequence, &Command<C>)], steps: &[KeyboardShortcut]) -> Option<C> {
+        if steps.is_empty() {
+            return None;
+        }
+        bindings
+            .iter()
+            .find(|(sequence, _)| sequence.steps() == steps)
+            .map(|(_, command)| command.id.clone())
+    }
+
+    /// Bindings of all enabled commands whose when-clause matches the active context,
+    /// most specific first.
+    fn active_bindings(&self, egui_ctx: &egui::Context) -> Vec<(KeySequence, &Command<C>)> {
+        let context = CommandContext::active(egui_ctx);
+        let mut bindings: Vec<(KeySequence, &Command<C>)> = self
+            .iter()
+            .filter(|command| command.is_enabled(egui_ctx) && command.is_active_in(&context))
+            .flat_map(|command| {
+                self.bindings(&command.id)
+                    .into_iter()
+                    .map(move |sequence| (sequence, command))
+            })
+            .collect();
+        bindings.sort_by_key(|(_, command)| std::cmp::Reverse(command.specificity()));
+        bindings
+    }
+
+    /// While a multi-step shortcut is being typed, show the steps pressed so far
+    /// and the commands they can still lead to, at the bottom of the screen.
+    pub fn pending_key_sequence_ui(&self, egui_ctx: &egui::Context) {
+        let pending = PendingKeySequence::load(egui_ctx);
+        if pending.is_empty() || pending.is_expired(self.key_sequence_timeout) {
+            return;
+        }
+
+        let os = egui_ctx.os();
+        let continuations: Vec<(KeySequence, &Command<C>)> = self
+            .active_bindings(egui_ctx)
+            .into_iter()
+            .filter(|(sequence, _)| {
+                sequence.len() > pending.steps.len() && sequence.starts_with(&pending.steps)
+            })
+            .collect();
+
+        egui::Area::new(egui::Id::new("pending_key_sequence_ui"))
+            .order(egui::Order::Tooltip)
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -24.0))
+            .interactable(false)
+            .show(egui_ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    let mut help = crate::components::Help::new_without_title().control(
+                        "Waiting for next key…",
+                        (
+                            crate::components::basic::IconText::from_key_sequence(
+                                os,
+                                &pending.steps,
+                            ),
+                            " …",
+                        ),
+                    );
+                    for (sequence, command) in continuations {
+                        help = help.control(
+                            command.text.clone(),
+                            crate::components::basic::IconText::from_key_sequence(
+                                os,
+                                &sequence.steps()[pending.steps.len()..],
+                            ),
+                        );
+                    }
+                    help.ui(ui);
+                });
+            });
+    }
+
+    /// Show this command as a menu-button.
+    ///
+    /// If clicked, enqueue the command.
+    /// Returns `None` if the command is not registered or not visible.
+    pub fn menu_button_ui(
+        &self,
+        ui: &mut egui::Ui,
+        id: &C,
+        command_sender: &impl CommandSender<C>,
+    ) -> Option<egui::Response> {
+        let command = self.get(id)?;
+        if !command.is_visible(ui.ctx()) {
+            return None;
+        }
+
+        let button = self.menu_button(ui.ctx(), id)?;
+        let mut response = ui
+            .add_enabled(command.is_enabled(ui.ctx()), button)
+            .on_hover_text(&command.tooltip);
+
+        if command.is_link {
+            response = response.on_hover_cursor(egui::CursorIcon::PointingHand);
+        }
+
+        if response.clicked() {
+            command_sender.send(id.clone());
+            ui.close();
+        }
+
+        Some(response)
+    }
+
+    pub fn menu_button(&self, egui_ctx: &egui::Context, id: &C) -> Option<egui::Button<'static>> {
+        let command = self.get(id)?;
+        let tokens = egui_ctx.tokens();
+
+        let mut button = match command.icon {
+            Some(CommandIcon::Icon(icon)) => egui::Button::image_and_text(
+                icon.as_image()
+                    .tint(tokens.label_button_icon_color)
+                    .fit_to_exact_size(tokens.small_icon_size),
+                command.text.clone(),
+            ),
+            Some(CommandIcon::Phosphor(icon)) => {
+                egui::Button::new(format!("{icon} {}", command.text))
+            }
+            None => egui::Button::new(command.text.clone()),
+        };
+
+        if let Some(shortcut_text) = self.formatted_kb_shortcut(id, egui_ctx) {
+            button = button.shortcut_text(shortcut_text);
+        }
+
+        Some(button)
+    }
+
+    /// Show name of command and how to activate it
+    pub fn tooltip_ui(&self, ui: &mut egui::Ui, id: &C) {
+        let Some(command) = self.get(id) else {
+            return;
+        };
+        let os = ui.ctx().os();
+
+        if let Some(sequence) = self.primary_binding(id) {
+            crate::components::Help::new_without_title()
+                .control(command.text.clone(), sequence.atoms(os))
+                .ui(ui);
+        } else {
+            ui.label(&command.text);
+        }
+
+        ui.set_max_width(220.0);
+        ui.label(&command.tooltip);
+    }
+}
+
+/// Consume the first pressed shortcut among `commands`.
+pub(crate) fn consume_kb_shortcut<C>(
+    egui_ctx: &egui::Context,
+    commands: impl Iterator<Item = (KeyboardShortcut, C)>,
+) -> Option<C> {
+    let anything_has_focus = egui_ctx.memory(|mem| mem.focused().is_some());
+
+    let mut commands: Vec<(KeyboardShortcut, C)> = commands.collect();
+
+    // If the user pressed `Cmd-Shift-S` then egui will match that
+    // with both `Cmd-Shift-S` and `Cmd-S`.
+    // The reason is that `Shift` (and `Alt`) are sometimes required to produce certain keys,
+    // such as `+` (`Shift =` on an american keyboard).
+    // The result of this is that we must check for `Cmd-Shift-S` before `Cmd-S`, etc.
+    // So we order the commands here so that the commands with `Shift` and `Alt` in them
+    // are checked first.
+    commands.sort_by_key(|(kb_shortcut, _cmd)| {
+        let num_shift_alts = kb_shortcut.modifiers.shift as i32 + kb_shortcut.modifiers.alt as i32;
+        -num_shift_alts // most first
+    });
+
+    egui_ctx.input_mut(|input| {
+        for (kb_shortcut, command) in commands {
+            if anything_has_focus {
+                // If a text edit has focus, is should usually get exclusive access to that input.
+                // For instance: use alt-arrows to move the cursor a whole word (at least on mac).
+                // The exception are shortcuts with ctrl/cmd in them:
+                let is_command = kb_shortcut.modifiers.command
+                    || kb_shortcut.modifiers.mac_cmd
+                    || kb_shortcut.modifiers.ctrl;
+                if !is_command {
+                    continue; // ignore
+                }
+            }
+
+            if input.consume_shortcut(&kb_shortcut) {
+                // Clear the shortcut key from input to prevent it from propagating to other UI component.
+                input.keys_down.remove(&kb_shortcut.logical_key);
+                return Some(command);
+            }
+        }
+        None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{Key, KeyboardShortcut, Modifiers};
+
+    use super::{Command, CommandRegistry};
+    use crate::components::command_context::CommandContext;
+
+    fn press(key: Key, modifiers: Modifiers) -> egui::RawInput {
+        egui::RawInput {
+            modifiers,
+            events: vec![egui::Event::Key {
+                key,
+                physical_key: None,
+                pressed: true,
+                repeat: false,
+                modifiers,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_listen_for_kb_shortcut() {
+        let mut registry = CommandRegistry::new();
+        registry
+            .register(
+                Command::new("save", "Save")
+                    .shortcut(KeyboardShortcut::new(Modifiers::CTRL, Key::S)),
+            )
+            .register(
+                Command::new("save_as", "Save as…").shortcut(KeyboardShortcut::new(
+                    Modifiers::CTRL | Modifiers::SHIFT,
+                    Key::S,
+                )),
+            )
+            .register(
+                Command::new("export", "Export")
+                    .shortcut(KeyboardShortcut::new(Modifiers::CTRL, Key::E))
+                    .enabled_when(|_| false),
+            );
+
+        let ctx = egui::Context::default();
+        let mut triggered = Vec::new();
+        for (key, modifiers) in [
+            (Key::S, Modifiers::CTRL),
+            (Key::S, Modifiers::CTRL | Modifiers::SHIFT),
+            (Key::E, Modifiers::CTRL),
+        ] {
+            let _ = ctx.run(press(key, modifiers), |ctx| {
+                triggered.push(registry.listen_for_kb_shortcut(ctx));
+            });
+        }
+
+        // The more specific shortcut wins, and disabled commands are never dispatched.
+        assert_eq!(triggered, vec![Some("save"), Some("save_as"), None]);
+    }
+
+    #[test]
+    fn test_key_sequence() {
+        let mut registry = CommandRegistry::new();
+        registry
+            .register(Command::new("save_all", "Save all").sequence([
+                KeyboardShortcut::new(Modifiers::CTRL, Key::K),
+                KeyboardShortcut::new(Modifiers::NONE, Key::S),
+            ]))
+            .register(
+                Command::new("search", "Search")
+                    .shortcut(KeyboardShortcut::new(Modifiers::NONE, Key::S)),
+            );
+
+        let ctx = egui::Context::default();
+        let mut triggered = Vec::new();
+        for (key, modifiers) in [
+            (Key::K, Modifiers::CTRL),
+            (Key::S, Modifiers::NONE),
+            (Key::S, Modifiers::NONE),
+            (Key::K, Modifiers::CTRL),
+            (Key::Escape, Modifiers::NONE),
+            (Key::S, Modifiers::NONE),
+        ] {
+            let _ = ctx.run(press(key, modifiers), |ctx| {
+                triggered.push(registry.listen_for_kb_shortcut(ctx));
+            });
+        }
+
+        // `S` completes the pending sequence instead of triggering `search`, and `Escape` abandons it.
+        assert_eq!(
+            triggered,
+            vec![
+                None,
+                Some("save_all"),
+                Some("search"),
+                None,
+                None,
+                Some("search")
+            ]
+        );
+        assert_eq!(
+            registry.formatted_kb_shortcut(&"save_all", &ctx).as_deref(),
+            Some("Ctrl+K S")
+        );
+    }
+
+    #[test]
+    fn test_longer_sequence_priority() {
+        let g = KeyboardShortcut::new(Modifiers::NONE, Key::G);
+        let mut registry = CommandRegistry::new();
+        registry
+            .register(Command::new("git", "Git").shortcut(g))
+            .register(Command::new("go_to_top", "Go to top").sequence([g, g]));
+
+        let ctx = egui::Context::default();
+        let mut triggered = Vec::new();
+        for key in [Key::G, Key::G, Key::G, Key::X] {
+            let _ = ctx.run(press(key, Modifiers::NONE), |ctx| {
+                triggered.push(registry.listen_for_kb_shortcut(ctx));
+            });
+        }
+
+        // `G` waits for a second `G`, and fires on its own once another key is pressed.
+        assert_eq!(triggered, vec![None, Some("go_to_top"), None, Some("git")]);
+
+        // …or once the timeout expires.
+        registry.set_key_sequence_timeout(std::time::Duration::ZERO);
+        let _ = ctx.run(press(Key::G, Modifiers::NONE), |ctx| {
+            assert_eq!(registry.listen_for_kb_shortcut(ctx), None);
+        });
+        let _ = ctx.run(Default::default(), |ctx| {
+            assert_eq!(registry.listen_for_kb_shortcut(ctx), Some("git"));
+        });
+    }
+
+    #[test]
+    fn test_when_clause_dispatch() {
+        let delete = KeyboardShortcut::new(Modifiers::NONE, Key::Delete);
+        let mut registry = CommandRegistry::new();
+        registry
+            .register(Command::new("delete", "Delete").shortcut(delete))
+            .register(
+                Command::new("delete_node", "Delete node")
+                    .shortcut(delete)
+                    .when("tree"),
+            )
+            .register(
+                Command::new("delete_shape", "Delete shape")
+                    .shortcut(delete)
+                    .when("canvas"),
+            );
+
+        let ctx = egui::Context::default();
+        let mut triggered = Vec::new();
+        for key in [None, Some("tree"), Some("canvas")] {
+            let _ = ctx.run(press(Key::Delete, Modifiers::NONE), |ctx| {
+                if let Some(key) = key {
+                    CommandContext::push(ctx, key);
+                }
+                triggered.push(registry.listen_for_kb_shortcut(ctx));
+            });
+            // Keys stay active for one more frame.
+            let _ = ctx.run(Default::default(), |_| {});
+        }
+
+        assert_eq!(
+            triggered,
+            vec![Some("delete"), Some("delete_node"), Some("delete_shape")]
+        );
+
+        assert!(
+            Command::new("broken", "Broken")
+                .try_when("tree &&")
+                .is_err()
+        );
+    }
+}
@@ -1,13 +1,24 @@
 //! Dialog - 定制对话框组件
 //!
-//! 支持两种类型:
+//! 支持以下类型:
 //! - `Text`: 普通文本对话框 (不可取消)
 //! - `Cancellable`: 可取消对话框 (带取消按钮和回调)
+//! - `Confirm`: 确认对话框 ("确定要删除吗?"), 可使用危险样式
+//! - `Prompt`: 文本输入对话框 (可校验)
+//! - `Choice`: 多选一对话框
+//! - `Progress`: 进度对话框 (进度条, 当前步骤, 剩余时间, 取消), 通过 [`ProgressHandle`] 从任意线程更新
+//!
+//! 确认 / 输入 / 选择对话框的结果 ([`DialogResult`]) 可以按 key 轮询,
+//! 也可以用 [`Dialog::result_future`] 作为 future 等待.
+//! 键盘: `Enter` = 主按钮, `Escape` = 取消 (只作用于最上层的对话框).
+//!
+//! 对话框登记在 [`ModalStack`] 中: 再次显示已存在的对话框会把它移到最上层,
+//! 只有最上层的模态框接收输入, 关闭后焦点恢复到打开前的控件.
 //!
 //! # 使用示例
 //!
 //! ```rust,no_run
-//! use egui_kit::dialog::Dialog;
+//! use egui_kit::dialog::{Dialog, DialogResult};
 //!
 //! // 普通文本
 //! Dialog::show_text(&ctx, "loading", "加载中...");
@@ -23,15 +34,54 @@
 //!     |id| { cancel_download(id); },
 //! );
 //!
+//! // 危险操作确认
+//! Dialog::show_confirm_danger(&ctx, "delete", "Delete file?", "This cannot be undone.", "Delete");
+//!
+//! // 文本输入 (带校验)
+//! Dialog::show_prompt_with_validation(&ctx, "rename", "Rename", "New name:", "untitled", |name| {
+//!     if name.is_empty() { Err("Name must not be empty".to_owned()) } else { Ok(()) }
+//! });
+//!
+//! // 进度 (后台任务通过 handle 更新, 取消按钮会取消 promise)
+//! let handle = Dialog::show_progress(&ctx, "import", "Importing");
+//! let promise = Promise::spawn(&runtime, &ctx, {
+//!     let handle = handle.clone();
+//!     async move {
+//!         for (i, file) in files.iter().enumerate() {
+//!             handle.update(i as f32 / files.len() as f32, format!("Reading {file}"));
+//!             import(file).await;
+//!         }
+//!         handle.finish();
+//!     }
+//! });
+//! handle.cancel_promise(&promise);
+//!
+//! // 轮询结果
+//! if let Some(result) = Dialog::take_result(&ctx, "delete") {
+//!     if result.is_confirmed() { delete_file(); }
+//! }
+//! if let Some(name) = Dialog::take_result(&ctx, "rename").and_then(DialogResult::into_text) {
+//!     rename(name);
+//! }
+//!
 //! // 渲染 (在 update 末尾调用)
 //! Dialog::render(&ctx);
 //! ```
 
 use std::any::Any;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Poll, Waker};
 
 use egui::{Align2, Color32, Context, Id, Order, Pos2, Sense};
 use indexmap::IndexMap;
+use web_time::{Duration, Instant};
+
+use crate::components::basic::button::ReButton;
+use crate::components::modal_stack::ModalStack;
+use crate::utils::promise::{Promise, PromiseCanceller};
 
 /// 取消回调 (带用户数据)
 pub type CancelCallback = Arc<dyn Fn(Box<dyn Any + Send + Sync>) + Send + Sync>;
@@ -42,13 +92,260 @@ struct DialogData {
     on_cancel: CancelCallback,
 }
 
+/// 文本输入校验, `Err` 为显示给用户的错误信息
+pub type PromptValidator = Arc<dyn Fn(&str) -> Result<(), String> + Send + Sync>;
+
+/// 确认 / 输入 / 选择对话框的结果
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DialogResult {
+    /// 点击了主按钮 (确认对话框)
+    Confirmed,
+
+    /// 点击了取消, 按了 `Escape`, 或对话框被关闭
+    Cancelled,
+
+    /// 输入对话框确认的文本
+    Text(String),
+
+    /// 选择对话框中选中的索引
+    Choice(usize),
+}
+
+impl DialogResult {
+    pub fn is_confirmed(&self) -> bool {
+        matches!(self, Self::Confirmed | Self::Text(_) | Self::Choice(_))
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, Self::Cancelled)
+    }
+
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Self::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn into_text(self) -> Option<String> {
+        match self {
+            Self::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn choice(&self) -> Option<usize> {
+        match self {
+            Self::Choice(index) => Some(*index),
+            _ => None,
+        }
+    }
+}
+
+/// 对话框类型
+#[derive(Clone)]
+enum DialogKind {
+    /// 文本 (可选 spinner 和取消按钮)
+    Message {
+        show_spinner: bool,
+        cancel_text: Option<String>,
+    },
+    Confirm {
+        confirm_text: String,
+        cancel_text: String,
+        danger: bool,
+    },
+    Prompt {
+        confirm_text: String,
+        cancel_text: String,
+        validate: Option<PromptValidator>,
+    },
+    Choice {
+        choices: Vec<String>,
+        cancel_text: String,
+    },
+    Progress {
+        handle: ProgressHandle,
+        cancel_text: Option<String>,
+    },
+}
+
+/// 进度对话框的共享状态
+struct ProgressState {
+    /// `None` = 不确定进度 (动画进度条)
+    fraction: Option<f32>,
+    step: String,
+    started: Instant,
+    cancelled: bool,
+    finished: bool,
+    cancellers: Vec<PromiseCanceller>,
+    on_cancel: Vec<Box<dyn FnOnce() + Send>>,
+}
+
+/// 更新进度对话框的句柄, 见 [`Dialog::show_progress`].
+///
+/// 可 Clone, 可以发送到任意线程 / 异步任务. 每次更新都会请求重绘.
+#[derive(Clone)]
+pub struct ProgressHandle {
+    ctx: Context,
+    state: Arc<Mutex<ProgressState>>,
+}
+
+impl ProgressHandle {
+    fn new(ctx: &Context) -> Self {
+        Self {
+            ctx: ctx.clone(),
+            state: Arc::new(Mutex::new(ProgressState {
+                fraction: None,
+                step: String::new(),
+                started: Instant::now(),
+                cancelled: false,
+                finished: false,
+                cancellers: Vec::new(),
+                on_cancel: Vec::new(),
+            })),
+        }
+    }
+
+    fn with_state<R>(&self, f: impl FnOnce(&mut ProgressState) -> R) -> R {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut state)
+    }
+
+    /// 设置进度 (0.0 ~ 1.0)
+    pub fn set_progress(&self, fraction: f32) {
+        self.with_state(|state| state.fraction = Some(fraction.clamp(0.0, 1.0)));
+        self.ctx.request_repaint();
+    }
+
+    /// 设置当前步骤的文本, 如 "Reading data.csv"
+    pub fn set_step(&self, step: impl Into<String>) {
+        let step = step.into();
+        self.with_state(|state| state.step = step);
+        self.ctx.request_repaint();
+    }
+
+    /// 同时设置进度和步骤
+    pub fn update(&self, fraction: f32, step: impl Into<String>) {
+        let step = step.into();
+        self.with_state(|state| {
+            state.fraction = Some(fraction.clamp(0.0, 1.0));
+            state.step = step;
+        });
+        self.ctx.request_repaint();
+    }
+
+    /// 任务完成, 关闭对话框 (结果为 [`DialogResult::Confirmed`])
+    pub fn finish(&self) {
+        self.with_state(|state| state.finished = true);
+        self.ctx.request_repaint();
+    }
+
+    /// 取消任务: 取消关联的 promise 并调用 `on_cancel` 回调. 对话框随后关闭 (结果为 [`DialogResult::Cancelled`]).
+    ///
+    /// 取消按钮和 `Escape` 会调用它. 只有第一次调用生效.
+    pub fn cancel(&self) {
+        let (cancellers, on_cancel) = self.with_state(|state| {
+            if state.cancelled || state.finished {
+                return Default::default();
+            }
+            state.cancelled = true;
+            (
+                std::mem::take(&mut state.cancellers),
+                std::mem::take(&mut state.on_cancel),
+            )
+        });
+
+        // 在锁外调用, 回调中可以再使用 handle
+        for canceller in cancellers {
+            canceller.cancel();
+        }
+        for on_cancel in on_cancel {
+            on_cancel();
+        }
+        self.ctx.request_repaint();
+    }
+
+    /// 取消时同时取消 `promise`
+    pub fn cancel_promise<T: Send + 'static>(&self, promise: &Promise<T>) {
+        self.cancel_with(promise.canceller());
+    }
+
+    /// 取消时调用 [`PromiseCanceller::cancel`]
+    pub fn cancel_with(&self, canceller: PromiseCanceller) {
+        let cancelled = self.with_state(|state| {
+            if !state.cancelled {
+                state.cancellers.push(canceller.clone());
+            }
+            state.cancelled
+        });
+        if cancelled {
+            canceller.cancel();
+        }
+    }
+
+    /// 取消时调用 `f` (用于线程等非 promise 的任务)
+    pub fn on_cancel(&self, f: impl FnOnce() + Send + 'static) {
+        let cancelled = self.with_state(|state| {
+            if !state.cancelled {
+                state.on_cancel.push(Box::new(f));
+                return None;
+            }
+            Some(f)
+        });
+        if let Some(f) = cancelled {
+            f();
+        }
+    }
+
+    /// 任务可以轮询它来提前结束
+    pub fn is_cancelled(&self) -> bool {
+        self.with_state(|state| state.cancelled)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.with_state(|state| state.finished)
+    }
+
+    pub fn progress(&self) -> Option<f32> {
+        self.with_state(|state| state.fraction)
+    }
+
+    /// 按已用时间线性估计的剩余时间. 进度太小时 (< 1%) 或刚开始 (< 1s) 时为 `None`.
+    pub fn eta(&self) -> Option<Duration> {
+        self.with_state(|state| {
+            let fraction = state.fraction?;
+            estimate_remaining(state.started.elapsed(), fraction)
+        })
+    }
+}
+
+fn estimate_remaining(elapsed: Duration, fraction: f32) -> Option<Duration> {
+    if fraction < 0.01 || elapsed < Duration::from_secs(1) {
+        return None;
+    }
+    Some(elapsed.mul_f32((1.0 - fraction) / fraction))
+}
+
+/// "About 2 min 5 s remaining"
+fn format_eta(eta: Duration) -> String {
+    let secs = eta.as_secs();
+    if secs < 60 {
+        format!("About {} s remaining", secs.max(1))
+    } else if secs < 3600 {
+        format!("About {} min {} s remaining", secs / 60, secs % 60)
+    } else {
+        format!("About {} h {} min remaining", secs / 3600, secs % 3600 / 60)
+    }
+}
+
 /// 对话框渲染信息 (可 Clone, 用于渲染)
 #[derive(Clone)]
 struct DialogRender {
+    title: Option<String>,
     message: String,
-    show_spinner: bool,
-    cancel_text: Option<String>,
-    size: Option<egui::Vec2>,  // None = 自适应
+    kind: DialogKind,
+    size: Option<egui::Vec2>, // None = 自适应
 }
 
 /// 对话框状态 (存储在 egui memory)
@@ -58,6 +355,54 @@ struct DialogState {
     renders: Arc<IndexMap<String, DialogRender>>,
     /// 待取消的 key
     pending_cancel: Option<String>,
+    /// 尚未被取走的结果
+    results: IndexMap<String, DialogResult>,
+    /// [`Dialog::result_future`] 的结果槽, 每个 key 一个
+    futures: HashMap<String, Arc<Mutex<ResultSlot>>>,
+}
+
+/// [`Dialog::result_future`] 与渲染之间共享的结果槽
+#[derive(Default)]
+struct ResultSlot {
+    result: Option<DialogResult>,
+    waker: Option<Waker>,
+}
+
+impl ResultSlot {
+    fn resolved(result: DialogResult) -> Arc<Mutex<Self>> {
+        Arc::new(Mutex::new(Self {
+            result: Some(result),
+            waker: None,
+        }))
+    }
+
+    fn resolve(slot: &Mutex<Self>, result: DialogResult) {
+        let mut slot = slot.lock().unwrap_or_else(|e| e.into_inner());
+        slot.result = Some(result);
+        if let Some(waker) = slot.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Resolves to the [`DialogResult`] of a dialog. See [`Dialog::result_future`].
+pub struct DialogFuture {
+    slot: Arc<Mutex<ResultSlot>>,
+}
+
+impl Future for DialogFuture {
+    type Output = DialogResult;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<DialogResult> {
+        let mut slot = self.slot.lock().unwrap_or_else(|e| e.into_inner());
+        match slot.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
 }
 
 /// 对话框数据存储 (全局静态, 存储回调)
@@ -113,17 +458,19 @@ impl Dialog {
         size: Option<egui::Vec2>,
     ) {
         let key = key.into();
-        Self::with_state(ctx, |state| {
-            Arc::make_mut(&mut state.renders).insert(
-                key,
-                DialogRender {
-                    message: message.into(),
+        Self::insert_on_top(
+            ctx,
+            key,
+            DialogRender {
+                title: None,
+                message: message.into(),
+                kind: DialogKind::Message {
                     show_spinner,
                     cancel_text: None,
-                    size,
                 },
-            );
-        });
+                size,
+            },
+        );
     }
 
     /// 显示可取消对话框 (带用户数据)
@@ -138,7 +485,16 @@ impl Dialog {
         T: Send + Sync + 'static,
         F: Fn(T) + Send + Sync + 'static,
     {
-        Self::show_cancellable_impl(ctx, key, message, cancel_text, true, None, user_data, on_cancel);
+        Self::show_cancellable_impl(
+            ctx,
+            key,
+            message,
+            cancel_text,
+            true,
+            None,
+            user_data,
+            on_cancel,
+        );
     }
 
     /// 显示可取消对话框 (自定义大小)
@@ -154,7 +510,16 @@ impl Dialog {
         T: Send + Sync + 'static,
         F: Fn(T) + Send + Sync + 'static,
     {
-        Self::show_cancellable_impl(ctx, key, message, cancel_text, true, Some(size), user_data, on_cancel);
+        Self::show_cancellable_impl(
+            ctx,
+            key,
+            message,
+            cancel_text,
+            true,
+            Some(size),
+            user_data,
+            on_cancel,
+        );
     }
 
     /// 显示可取消对话框 (可配置 spinner)
@@ -170,7 +535,16 @@ impl Dialog {
         T: Send + Sync + 'static,
         F: Fn(T) + Send + Sync + 'static,
     {
-        Self::show_cancellable_impl(ctx, key, message, cancel_text, show_spinner, None, user_data, on_cancel);
+        Self::show_cancellable_impl(
+            ctx,
+            key,
+            message,
+            cancel_text,
+            show_spinner,
+            None,
+            user_data,
+            on_cancel,
+        );
     }
 
     /// 内部实现
@@ -191,17 +565,19 @@ impl Dialog {
         let cancel_text = cancel_text.into();
 
         // 存储渲染信息
-        Self::with_state(ctx, |state| {
-            Arc::make_mut(&mut state.renders).insert(
-                key.clone(),
-                DialogRender {
-                    message: message.into(),
+        Self::insert_on_top(
+            ctx,
+            key.clone(),
+            DialogRender {
+                title: None,
+                message: message.into(),
+                kind: DialogKind::Message {
                     show_spinner,
                     cancel_text: Some(cancel_text),
-                    size,
                 },
-            );
-        });
+                size,
+            },
+        );
 
         // 存储回调数据
         dialog_data().lock().unwrap().insert(
@@ -217,21 +593,281 @@ impl Dialog {
         );
     }
 
-    /// 关闭指定对话框
-    pub fn close(ctx: &Context, key: impl AsRef<str>) {
-        let key = key.as_ref();
+    /// 显示确认对话框 ("确定" / "取消")
+    pub fn show_confirm(
+        ctx: &Context,
+        key: impl Into<String>,
+        title: impl Into<String>,
+        message: impl Into<String>,
+    ) {
+        Self::show_confirm_with(ctx, key, title, message, "OK", "Cancel", false);
+    }
+
+    /// 显示危险操作确认对话框, 主按钮使用危险样式 (如 "Delete")
+    pub fn show_confirm_danger(
+        ctx: &Context,
+        key: impl Into<String>,
+        title: impl Into<String>,
+        message: impl Into<String>,
+        confirm_text: impl Into<String>,
+    ) {
+        Self::show_confirm_with(ctx, key, title, message, confirm_text, "Cancel", true);
+    }
+
+    /// 显示确认对话框 (自定义按钮)
+    pub fn show_confirm_with(
+        ctx: &Context,
+        key: impl Into<String>,
+        title: impl Into<String>,
+        message: impl Into<String>,
+        confirm_text: impl Into<String>,
+        cancel_text: impl Into<String>,
+        danger: bool,
+    ) {
+        Self::show_impl(
+            ctx,
+            key.into(),
+            DialogRender {
+                title: Some(title.into()),
+                message: message.into(),
+                kind: DialogKind::Confirm {
+                    confirm_text: confirm_text.into(),
+                    cancel_text: cancel_text.into(),
+                    danger,
+                },
+                size: None,
+            },
+        );
+    }
+
+    /// 显示文本输入对话框, `initial` 为初始文本
+    pub fn show_prompt(
+        ctx: &Context,
+        key: impl Into<String>,
+        title: impl Into<String>,
+        message: impl Into<String>,
+        initial: impl Into<String>,
+    ) {
+        Self::show_prompt_impl(
+            ctx,
+            key.into(),
+            title.into(),
+            message.into(),
+            initial.into(),
+            None,
+        );
+    }
+
+    /// 显示文本输入对话框 (带校验, 校验失败时不能确认)
+    pub fn show_prompt_with_validation(
+        ctx: &Context,
+        key: impl Into<String>,
+        title: impl Into<String>,
+        message: impl Into<String>,
+        initial: impl Into<String>,
+        validate: impl Fn(&str) -> Result<(), String> + Send + Sync + 'static,
+    ) {
+        Self::show_prompt_impl(
+            ctx,
+            key.into(),
+            title.into(),
+            message.into(),
+            initial.into(),
+            Some(Arc::new(validate)),
+        );
+    }
+
+    fn show_prompt_impl(
+        ctx: &Context,
+        key: String,
+        title: String,
+        message: String,
+        initial: String,
+        validate: Option<PromptValidator>,
+    ) {
+        ctx.data_mut(|d| d.insert_temp(Self::prompt_text_id(&key), initial));
+        Self::show_impl(
+            ctx,
+            key,
+            DialogRender {
+                title: Some(title),
+                message,
+                kind: DialogKind::Prompt {
+                    confirm_text: "OK".to_owned(),
+                    cancel_text: "Cancel".to_owned(),
+                    validate,
+                },
+                size: None,
+            },
+        );
+    }
+
+    /// 显示多选一对话框. 第一个选项是主按钮 (`Enter`).
+    pub fn show_choice<S: Into<String>>(
+        ctx: &Context,
+        key: impl Into<String>,
+        title: impl Into<String>,
+        message: impl Into<String>,
+        choices: impl IntoIterator<Item = S>,
+    ) {
+        Self::show_impl(
+            ctx,
+            key.into(),
+            DialogRender {
+                title: Some(title.into()),
+                message: message.into(),
+                kind: DialogKind::Choice {
+                    choices: choices.into_iter().map(Into::into).collect(),
+                    cancel_text: "Cancel".to_owned(),
+                },
+                size: None,
+            },
+        );
+    }
+
+    /// 显示进度对话框 (带取消按钮), 返回用于更新进度的句柄.
+    ///
+    /// 任务完成时调用 [`ProgressHandle::finish`]. 点击取消 / `Escape` 会调用 [`ProgressHandle::cancel`].
+    pub fn show_progress(
+        ctx: &Context,
+        key: impl Into<String>,
+        title: impl Into<String>,
+    ) -> ProgressHandle {
+        Self::show_progress_impl(ctx, key.into(), title.into(), Some("Cancel".to_owned()))
+    }
+
+    /// 显示进度对话框 (不可取消)
+    pub fn show_progress_uncancellable(
+        ctx: &Context,
+        key: impl Into<String>,
+        title: impl Into<String>,
+    ) -> ProgressHandle {
+        Self::show_progress_impl(ctx, key.into(), title.into(), None)
+    }
+
+    fn show_progress_impl(
+        ctx: &Context,
+        key: String,
+        title: String,
+        cancel_text: Option<String>,
+    ) -> ProgressHandle {
+        let handle = ProgressHandle::new(ctx);
+        Self::show_impl(
+            ctx,
+            key,
+            DialogRender {
+                title: Some(title),
+                message: String::new(),
+                kind: DialogKind::Progress {
+                    handle: handle.clone(),
+                    cancel_text,
+                },
+                size: None,
+            },
+        );
+        handle
+    }
+
+    fn show_impl(ctx: &Context, key: String, render: DialogRender) {
+        Self::with_state(ctx, |state| {
+            state.results.shift_remove(&key);
+        });
+        Self::insert_on_top(ctx, key, render);
+    }
+
+    /// 插入到最上层 (已存在的对话框移到最上层)
+    fn insert_on_top(ctx: &Context, key: String, render: DialogRender) {
+        ModalStack::raise(ctx, Self::area_id(&key));
         Self::with_state(ctx, |state| {
+            let renders = Arc::make_mut(&mut state.renders);
+            renders.shift_remove(&key);
+            renders.insert(key, render);
+        });
+    }
+
+    fn area_id(key: &str) -> Id {
+        Id::new(format!("dialog_{}", key))
+    }
+
+    fn prompt_text_id(key: &str) -> Id {
+        Id::new(("dialog_prompt_text", key))
+    }
+
+    /// 取走对话框的结果 (只返回一次). 对话框仍在显示时返回 `None`.
+    pub fn take_result(ctx: &Context, key: impl AsRef<str>) -> Option<DialogResult> {
+        Self::with_state(ctx, |state| state.results.shift_remove(key.as_ref()))
+    }
+
+    /// 等待对话框结果的 future. 应在 `show_*` 之后调用.
+    ///
+    /// 结果同时也可以用 [`Self::take_result`] 取得.
+    /// 每个 key 只有一个等待者: 再次调用会让之前的 future 立即以 [`DialogResult::Cancelled`] 结束.
+    /// 对话框没有显示时, 返回的 future 立即以尚未取走的结果 (没有则为取消) 结束.
+    pub fn result_future(ctx: &Context, key: impl Into<String>) -> DialogFuture {
+        let key = key.into();
+        let slot = Self::with_state(ctx, |state| {
+            if !state.renders.contains_key(&key) {
+                let result = state.results.get(&key).cloned();
+                return ResultSlot::resolved(result.unwrap_or(DialogResult::Cancelled));
+            }
+            let slot = Arc::new(Mutex::new(ResultSlot::default()));
+            if let Some(previous) = state.futures.insert(key, slot.clone()) {
+                ResultSlot::resolve(&previous, DialogResult::Cancelled);
+            }
+            slot
+        });
+        DialogFuture { slot }
+    }
+
+    /// 结束对话框, 记录结果并唤醒等待者
+    fn finish(ctx: &Context, key: &str, result: DialogResult) {
+        let slot = Self::with_state(ctx, |state| {
+            Arc::make_mut(&mut state.renders).shift_remove(key);
+            state.results.insert(key.to_owned(), result.clone());
+            state.futures.remove(key)
+        });
+        ctx.data_mut(|d| d.remove::<String>(Self::prompt_text_id(key)));
+        ModalStack::close(ctx, Self::area_id(key));
+
+        if let Some(slot) = slot {
+            ResultSlot::resolve(&slot, result);
+        }
+    }
+
+    /// 移除文本对话框. 文本对话框没有结果, 只唤醒等待者.
+    fn dismiss(ctx: &Context, key: &str) {
+        let slot = Self::with_state(ctx, |state| {
             Arc::make_mut(&mut state.renders).shift_remove(key);
+            state.futures.remove(key)
         });
+        ModalStack::close(ctx, Self::area_id(key));
+
+        if let Some(slot) = slot {
+            ResultSlot::resolve(&slot, DialogResult::Cancelled);
+        }
+    }
+
+    /// 关闭指定对话框
+    pub fn close(ctx: &Context, key: impl AsRef<str>) {
+        let key = key.as_ref();
+        let render = Self::with_state(ctx, |state| state.renders.get(key).cloned());
         dialog_data().lock().unwrap().shift_remove(key);
+        match render {
+            Some(render) if matches!(render.kind, DialogKind::Message { .. }) => {
+                Self::dismiss(ctx, key);
+            }
+            Some(_) => Self::finish(ctx, key, DialogResult::Cancelled),
+            None => {}
+        }
     }
 
     /// 关闭所有对话框
     pub fn close_all(ctx: &Context) {
-        Self::with_state(ctx, |state| {
-            Arc::make_mut(&mut state.renders).clear();
-        });
-        dialog_data().lock().unwrap().clear();
+        let keys: Vec<String> =
+            Self::with_state(ctx, |state| state.renders.keys().cloned().collect());
+        for key in keys {
+            Self::close(ctx, key);
+        }
     }
 
     /// 检查是否有对话框显示
@@ -251,9 +887,7 @@ impl Dialog {
 
         if let Some(key) = pending {
             // 移除渲染信息
-            Self::with_state(ctx, |state| {
-                Arc::make_mut(&mut state.renders).shift_remove(&key);
-            });
+            Self::dismiss(ctx, &key);
 
             // 执行回调
             if let Some(data) = dialog_data().lock().unwrap().shift_remove(&key) {
@@ -266,13 +900,30 @@ impl Dialog {
 
         // 记录本帧点击的取消按钮
         let mut clicked_cancel: Option<String> = None;
+        let mut finished = Vec::new();
+
+        // 先全部登记到模态框栈, 再按顺序渲染
+        for key in renders.keys() {
+            ModalStack::show(ctx, Self::area_id(key));
+        }
 
         for (key, render) in renders.iter() {
-            if Self::render_one(ctx, key, render) {
-                clicked_cancel = Some(key.clone());
+            let is_top = ModalStack::is_top(ctx, Self::area_id(key));
+            match Self::render_one(ctx, key, render, is_top) {
+                Some(DialogResult::Cancelled)
+                    if matches!(render.kind, DialogKind::Message { .. }) =>
+                {
+                    clicked_cancel = Some(key.clone());
+                }
+                Some(result) => finished.push((key.clone(), result)),
+                None => {}
             }
         }
 
+        for (key, result) in finished {
+            Self::finish(ctx, &key, result);
+        }
+
         // 设置 pending_cancel (下一帧处理)
         if let Some(key) = clicked_cancel {
             Self::with_state(ctx, |state| {
@@ -281,16 +932,27 @@ impl Dialog {
         }
     }
 
-    /// 渲染单个对话框, 返回是否点击了取消按钮
-    fn render_one(ctx: &Context, key: &str, render: &DialogRender) -> bool {
-        let mut cancelled = false;
+    /// 渲染单个对话框, 返回本帧的结果 (可取消的文本对话框被取消时为 [`DialogResult::Cancelled`])
+    fn render_one(
+        ctx: &Context,
+        key: &str,
+        render: &DialogRender,
+        is_top: bool,
+    ) -> Option<DialogResult> {
+        let mut result = None;
 
         // 对话框 (包含遮罩和内容, 避免焦点切换)
-        egui::Area::new(Id::new(format!("dialog_{}", key)))
+        egui::Area::new(Self::area_id(key))
             .fixed_pos(Pos2::ZERO)
             .order(Order::Foreground)
             .interactable(true)
             .show(ctx, |ui| {
+                // 阻止下层的交互 (包括 Tab 焦点切换)
+                ui.ctx().memory_mut(|m| m.set_modal_layer(ui.layer_id()));
+                if is_top {
+                    ui.ctx().move_to_top(ui.layer_id());
+                }
+
                 let screen = ctx.input(|i| i.content_rect());
 
                 // 绘制半透明遮罩
@@ -300,70 +962,362 @@ impl Dialog {
                 // 绘制对话框内容 (居中)
                 let center = screen.center();
 
-                if let Some(size) = render.size {
-                    // 固定大小
-                    let content_rect = egui::Rect::from_center_size(center, size);
-                    ui.allocate_ui_at_rect(content_rect, |ui| {
-                        cancelled = Self::render_dialog_content(ui, render);
-                    });
-                } else {
-                    // 自适应大小
-                    ui.allocate_ui_at_rect(
-                        egui::Rect::from_center_size(center, egui::vec2(300.0, 100.0)),
-                        |ui| {
-                            egui::Frame::popup(ui.style())
-                                .fill(ui.style().visuals.window_fill)
-                                .inner_margin(egui::Margin::same(24))
-                                .show(ui, |ui| {
-                                    ui.vertical_centered(|ui| {
-                                        if render.show_spinner {
-                                            ui.spinner();
-                                            ui.add_space(12.0);
-                                        }
-
-                                        ui.label(&render.message);
-
-                                        if let Some(cancel_text) = &render.cancel_text {
-                                            ui.add_space(16.0);
-                                            if ui.button(cancel_text).clicked() {
-                                                cancelled = true;
-                                            }
-                                        }
-                                    });
-                                });
-                        },
-                    );
-                }
+                // None = 自适应大小
+                let size = render.size.unwrap_or(egui::vec2(300.0, 100.0));
+                let content_rect = egui::Rect::from_center_size(center, size);
+                ui.allocate_ui_at_rect(content_rect, |ui| {
+                    result = Self::render_dialog_content(ui, key, render, is_top);
+                });
             });
 
-        cancelled
+        result
     }
 
-    /// 渲染对话框内容 (固定大小模式)
-    fn render_dialog_content(ui: &mut egui::Ui, render: &DialogRender) -> bool {
-        let mut cancelled = false;
+    /// 渲染对话框内容
+    fn render_dialog_content(
+        ui: &mut egui::Ui,
+        key: &str,
+        render: &DialogRender,
+        is_top: bool,
+    ) -> Option<DialogResult> {
+        // 键盘只作用于最上层的对话框
+        let (enter_pressed, escape_pressed) = if is_top {
+            ui.input_mut(|i| {
+                (
+                    i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                    i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+                )
+            })
+        } else {
+            (false, false)
+        };
 
         egui::Frame::popup(ui.style())
             .fill(ui.style().visuals.window_fill)
             .inner_margin(egui::Margin::same(24))
-            .show(ui, |ui| {
-                ui.vertical_centered(|ui| {
-                    if render.show_spinner {
-                        ui.spinner();
-                        ui.add_space(12.0);
+            .show(ui, |ui| match &render.kind {
+                DialogKind::Message {
+                    show_spinner,
+                    cancel_text,
+                } => {
+                    let mut cancelled = false;
+                    ui.vertical_centered(|ui| {
+                        if *show_spinner {
+                            ui.spinner();
+                            ui.add_space(12.0);
+                        }
+
+                        ui.label(&render.message);
+
+                        if let Some(cancel_text) = cancel_text {
+                            ui.add_space(16.0);
+                            cancelled = ui.button(cancel_text).clicked() || escape_pressed;
+                        }
+                    });
+                    cancelled.then_some(DialogResult::Cancelled)
+                }
+
+                kind => {
+                    ui.set_min_width(320.0);
+                    if let Some(title) = &render.title {
+                        ui.label(egui::RichText::new(title).heading().strong());
+                        ui.add_space(8.0);
+                    }
+                    if !render.message.is_empty() {
+                        ui.label(&render.message);
                     }
+                    Self::render_kind_ui(ui, key, kind, enter_pressed, escape_pressed)
+                }
+            })
+            .inner
+    }
+
+    /// 确认 / 输入 / 选择对话框的内容和按钮
+    fn render_kind_ui(
+        ui: &mut egui::Ui,
+        key: &str,
+        kind: &DialogKind,
+        enter_pressed: bool,
+        escape_pressed: bool,
+    ) -> Option<DialogResult> {
+        let mut result = escape_pressed.then_some(DialogResult::Cancelled);
+
+        match kind {
+            DialogKind::Message { .. } => {}
+
+            DialogKind::Confirm {
+                confirm_text,
+                cancel_text,
+                danger,
+            } => {
+                ui.add_space(16.0);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    let confirm = ReButton::new(confirm_text.as_str());
+                    let confirm = if *danger {
+                        confirm.danger()
+                    } else {
+                        confirm.primary()
+                    };
+                    if ui.add(confirm).clicked() || enter_pressed {
+                        result = Some(DialogResult::Confirmed);
+                    }
+                    if ui
+                        .add(ReButton::new(cancel_text.as_str()).secondary())
+                        .clicked()
+                    {
+                        result = Some(DialogResult::Cancelled);
+                    }
+                });
+            }
 
-                    ui.label(&render.message);
+            DialogKind::Prompt {
+                confirm_text,
+                cancel_text,
+                validate,
+            } => {
+                let text_id = Self::prompt_text_id(key);
+                let mut text = ui
+                    .data(|d| d.get_temp::<String>(text_id))
+                    .unwrap_or_default();
 
-                    if let Some(cancel_text) = &render.cancel_text {
+                ui.add_space(8.0);
+                let response =
+                    ui.add(egui::TextEdit::singleline(&mut text).desired_width(f32::INFINITY));
+                if !response.has_focus() && !ui.ctx().memory(|m| m.focused().is_some()) {
+                    response.request_focus();
+                }
+
+                let validation = validate.as_ref().map_or(Ok(()), |validate| validate(&text));
+                if let Err(error) = &validation {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+
+                ui.add_space(16.0);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    let confirm = ui.add_enabled(
+                        validation.is_ok(),
+                        ReButton::new(confirm_text.as_str()).primary(),
+                    );
+                    if validation.is_ok() && (confirm.clicked() || enter_pressed) {
+                        result = Some(DialogResult::Text(text.clone()));
+                    }
+                    if ui
+                        .add(ReButton::new(cancel_text.as_str()).secondary())
+                        .clicked()
+                    {
+                        result = Some(DialogResult::Cancelled);
+                    }
+                });
+
+                ui.data_mut(|d| d.insert_temp(text_id, text));
+            }
+
+            DialogKind::Progress {
+                handle,
+                cancel_text,
+            } => {
+                if handle.is_finished() {
+                    return Some(DialogResult::Confirmed);
+                }
+                if handle.is_cancelled() {
+                    return Some(DialogResult::Cancelled);
+                }
+
+                let (fraction, step) =
+                    handle.with_state(|state| (state.fraction, state.step.clone()));
+
+                ui.add_space(8.0);
+                if !step.is_empty() {
+                    ui.add(egui::Label::new(step).truncate());
+                }
+                let progress_bar = match fraction {
+                    Some(fraction) => egui::ProgressBar::new(fraction).show_percentage(),
+                    None => egui::ProgressBar::new(0.0).animate(true),
+                };
+                ui.add(progress_bar);
+                if let Some(eta) = handle.eta() {
+                    ui.weak(format_eta(eta));
+                    // 每秒刷新剩余时间
+                    ui.ctx().request_repaint_after(Duration::from_secs(1));
+                }
+
+                match cancel_text {
+                    Some(cancel_text) => {
                         ui.add_space(16.0);
-                        if ui.button(cancel_text).clicked() {
-                            cancelled = true;
-                        }
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui
+                                .add(ReButton::new(cancel_text.as_str()).secondary())
+                                .clicked()
+                            {
+                                result = Some(DialogResult::Cancelled);
+                            }
+                        });
+                    }
+                    // 不可取消: 忽略 Escape
+                    None => result = None,
+                }
+
+                if result.is_some() {
+                    handle.cancel();
+                }
+            }
+
+            DialogKind::Choice {
+                choices,
+                cancel_text,
+            } => {
+                ui.add_space(16.0);
+                for (index, choice) in choices.iter().enumerate() {
+                    let button = ReButton::new(choice.as_str());
+                    let button = if index == 0 {
+                        button.primary()
+                    } else {
+                        button.secondary()
+                    };
+                    if ui.add_sized([ui.available_width(), 0.0], button).clicked()
+                        || (index == 0 && enter_pressed)
+                    {
+                        result = Some(DialogResult::Choice(index));
+                    }
+                    ui.add_space(4.0);
+                }
+                ui.add_space(4.0);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui
+                        .add(ReButton::new(cancel_text.as_str()).secondary())
+                        .clicked()
+                    {
+                        result = Some(DialogResult::Cancelled);
                     }
                 });
-            });
+            }
+        }
 
-        cancelled
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dialog, DialogResult, estimate_remaining, format_eta};
+    use std::future::Future as _;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::task::{Context, Poll, Waker};
+    use web_time::Duration;
+
+    #[test]
+    fn test_dialog_result() {
+        let ctx = egui::Context::default();
+
+        Dialog::show_confirm_danger(&ctx, "delete", "Delete?", "", "Delete");
+        let mut future = Box::pin(Dialog::result_future(&ctx, "delete"));
+        let mut cx = Context::from_waker(Waker::noop());
+        assert!(future.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(Dialog::take_result(&ctx, "delete"), None);
+
+        Dialog::finish(&ctx, "delete", DialogResult::Confirmed);
+        assert!(!Dialog::has(&ctx, "delete"));
+        assert_eq!(
+            future.as_mut().poll(&mut cx),
+            Poll::Ready(DialogResult::Confirmed)
+        );
+        assert_eq!(
+            Dialog::take_result(&ctx, "delete"),
+            Some(DialogResult::Confirmed)
+        );
+        assert_eq!(Dialog::take_result(&ctx, "delete"), None);
+
+        // 关闭视为取消
+        Dialog::show_prompt(&ctx, "rename", "Rename", "", "a");
+        Dialog::close(&ctx, "rename");
+        assert_eq!(
+            Dialog::take_result(&ctx, "rename"),
+            Some(DialogResult::Cancelled)
+        );
+
+        // 对话框没有显示时立即结束
+        let mut future = Box::pin(Dialog::result_future(&ctx, "missing"));
+        assert_eq!(
+            future.as_mut().poll(&mut cx),
+            Poll::Ready(DialogResult::Cancelled)
+        );
+
+        // 每个 key 只有一个等待者
+        Dialog::show_choice(&ctx, "pick", "Pick", "", ["a", "b"]);
+        let mut first = Box::pin(Dialog::result_future(&ctx, "pick"));
+        let mut second = Box::pin(Dialog::result_future(&ctx, "pick"));
+        assert_eq!(
+            first.as_mut().poll(&mut cx),
+            Poll::Ready(DialogResult::Cancelled)
+        );
+        Dialog::finish(&ctx, "pick", DialogResult::Choice(1));
+        assert_eq!(
+            second.as_mut().poll(&mut cx),
+            Poll::Ready(DialogResult::Choice(1))
+        );
+
+        // 文本对话框没有结果
+        Dialog::show_text(&ctx, "loading", "Loading");
+        let mut future = Box::pin(Dialog::result_future(&ctx, "loading"));
+        Dialog::close(&ctx, "loading");
+        assert_eq!(
+            future.as_mut().poll(&mut cx),
+            Poll::Ready(DialogResult::Cancelled)
+        );
+        assert_eq!(Dialog::take_result(&ctx, "loading"), None);
+
+        // 不同的 context 互不影响
+        let other = egui::Context::default();
+        Dialog::show_confirm(&other, "delete", "Delete?", "");
+        let mut future = Box::pin(Dialog::result_future(&other, "delete"));
+        Dialog::show_confirm(&ctx, "delete", "Delete?", "");
+        Dialog::finish(&ctx, "delete", DialogResult::Confirmed);
+        assert!(future.as_mut().poll(&mut cx).is_pending());
+    }
+
+    #[test]
+    fn test_progress_handle() {
+        let ctx = egui::Context::default();
+
+        let handle = Dialog::show_progress(&ctx, "import", "Importing");
+        handle.update(1.5, "Reading");
+        assert_eq!(handle.progress(), Some(1.0));
+
+        let called = Arc::new(AtomicBool::new(false));
+        handle.on_cancel({
+            let called = called.clone();
+            move || called.store(true, Ordering::Relaxed)
+        });
+        std::thread::spawn({
+            let handle = handle.clone();
+            move || handle.cancel()
+        })
+        .join()
+        .unwrap();
+        assert!(handle.is_cancelled());
+        assert!(called.load(Ordering::Relaxed));
+
+        // 取消之后注册的回调立即调用
+        let late = Arc::new(AtomicBool::new(false));
+        handle.on_cancel({
+            let late = late.clone();
+            move || late.store(true, Ordering::Relaxed)
+        });
+        assert!(late.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_eta() {
+        assert_eq!(estimate_remaining(Duration::from_millis(500), 0.5), None);
+        assert_eq!(estimate_remaining(Duration::from_secs(10), 0.0), None);
+        assert_eq!(
+            estimate_remaining(Duration::from_secs(10), 0.25),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            format_eta(Duration::from_secs(125)),
+            "About 2 min 5 s remaining"
+        );
     }
 }
@@ -202,6 +202,11 @@ impl FilterState {
     /// In this mode, the filter is active as soon as the query is non-empty. The session remains
     /// active until the query is cleared.
     pub fn search_field_ui(&mut self, ui: &mut egui::Ui) {
+        self.search_field_ui_with_hint(ui, "Search for entity…");
+    }
+
+    /// Same as [`Self::search_field_ui`], with a custom hint text.
+    pub fn search_field_ui_with_hint(&mut self, ui: &mut egui::Ui, hint_text: &str) {
         let inner_state = self.inner_state.get_or_insert_with(Default::default);
 
         let textedit_id = ui.id().with("textedit");
@@ -243,7 +248,7 @@ impl FilterState {
                             egui::TextEdit::singleline(&mut inner_state.filter_query)
                                 .id(textedit_id)
                                 .frame(false)
-                                .hint_text("Search for entity…")
+                                .hint_text(hint_text)
                                 .desired_width(ui.available_width()),
                         )
                     });
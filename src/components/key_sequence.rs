@@ -0,0 +1,148 @@
+//! 多步快捷键 (Key sequence / chord).
+//!
+//! 一个 [`KeySequence`] 由一个或多个按键组合依次组成, 例如 `Ctrl+K Ctrl+S` 或 `G G`.
+//! 只有一步的序列就是普通快捷键.
+//!
+//! 分发时, 已按下但尚未完成的前缀保存在 egui memory 中 ([`PendingKeySequence`]),
+//! 超时或按下不匹配的按键后清除. 正在等待后续按键时可用
+//! [`CommandRegistry::pending_key_sequence_ui`](crate::CommandRegistry::pending_key_sequence_ui) 显示提示.
+
+use std::time::Duration;
+
+use egui::os::OperatingSystem;
+use egui::{Atoms, KeyboardShortcut};
+use smallvec::SmallVec;
+
+use crate::components::basic::IconText;
+use crate::components::keymap::{format_shortcut, parse_shortcut};
+
+/// 默认的序列超时时间: 两次按键之间超过该时间则放弃已按下的前缀.
+pub const DEFAULT_KEY_SEQUENCE_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// One or more keyboard shortcuts pressed one after the other.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct KeySequence(SmallVec<[KeyboardShortcut; 2]>);
+
+impl KeySequence {
+    /// A sequence of the given steps. Must not be empty.
+    pub fn new(steps: impl IntoIterator<Item = KeyboardShortcut>) -> Self {
+        let steps: SmallVec<[KeyboardShortcut; 2]> = steps.into_iter().collect();
+        debug_assert!(!steps.is_empty(), "A key sequence needs at least one step");
+        Self(steps)
+    }
+
+    pub fn steps(&self) -> &[KeyboardShortcut] {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Is this a plain, single-step shortcut?
+    pub fn as_single(&self) -> Option<KeyboardShortcut> {
+        match self.0.as_slice() {
+            [shortcut] => Some(*shortcut),
+            _ => None,
+        }
+    }
+
+    /// Does this sequence start with `prefix`?
+    pub fn starts_with(&self, prefix: &[KeyboardShortcut]) -> bool {
+        self.0.starts_with(prefix)
+    }
+
+    pub fn push(&mut self, step: KeyboardShortcut) {
+        self.0.push(step);
+    }
+
+    /// Human readable, platform specific text, e.g. `Ctrl+K Ctrl+S` or `⌘K ⌘S`.
+    pub fn format(&self, egui_ctx: &egui::Context) -> String {
+        format_steps(egui_ctx, &self.0)
+    }
+
+    /// Icons and text for display in help and tooltips.
+    pub fn atoms(&self, os: OperatingSystem) -> Atoms<'static> {
+        IconText::from_key_sequence(os, &self.0)
+    }
+
+    /// Portable text used for persistence, e.g. `"Cmd+K Cmd+S"`.
+    pub fn to_portable_string(&self) -> String {
+        self.0
+            .iter()
+            .map(format_shortcut)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Parse the text produced by [`Self::to_portable_string`].
+    pub fn parse(text: &str) -> Option<Self> {
+        let steps = text
+            .split_whitespace()
+            .map(parse_shortcut)
+            .collect::<Option<SmallVec<_>>>()?;
+        (!steps.is_empty()).then_some(Self(steps))
+    }
+}
+
+impl From<KeyboardShortcut> for KeySequence {
+    fn from(shortcut: KeyboardShortcut) -> Self {
+        Self::new([shortcut])
+    }
+}
+
+pub(crate) fn format_steps(egui_ctx: &egui::Context, steps: &[KeyboardShortcut]) -> String {
+    steps
+        .iter()
+        .map(|step| egui_ctx.format_shortcut(step))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 已按下但尚未完成的序列前缀.
+#[derive(Clone, Debug, Default)]
+pub struct PendingKeySequence {
+    pub steps: SmallVec<[KeyboardShortcut; 2]>,
+    pub(crate) last_key_time: Option<web_time::Instant>,
+}
+
+impl PendingKeySequence {
+    fn id() -> egui::Id {
+        egui::Id::new("pending_key_sequence")
+    }
+
+    /// The prefix pressed so far, if any.
+    pub fn load(egui_ctx: &egui::Context) -> Self {
+        egui_ctx
+            .data(|d| d.get_temp(Self::id()))
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn store(self, egui_ctx: &egui::Context) {
+        egui_ctx.data_mut(|d| {
+            if self.steps.is_empty() {
+                d.remove::<Self>(Self::id());
+            } else {
+                d.insert_temp(Self::id(), self);
+            }
+        });
+    }
+
+    /// Forget the prefix pressed so far.
+    pub fn clear(egui_ctx: &egui::Context) {
+        egui_ctx.data_mut(|d| d.remove::<Self>(Self::id()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub(crate) fn is_expired(&self, timeout: Duration) -> bool {
+        self.last_key_time
+            .is_some_and(|last| last.elapsed() >= timeout)
+    }
+}
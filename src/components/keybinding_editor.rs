@@ -0,0 +1,341 @@
+//! 快捷键编辑面板.
+//!
+//! 列出 [`CommandRegistry`] 中的所有命令及其当前快捷键.
+//! 点击 "Record" 后按下新的组合键即可录制, `Escape` 取消.
+//! 继续按下更多组合键可录制多步快捷键 (如 `Ctrl+K Ctrl+S`):
+//! 按 `Enter` 或停顿超过 [`CommandRegistry::key_sequence_timeout`] 后完成录制.
+//! 录制期间 [`CommandRegistry::listen_for_kb_shortcut`] 不会分发任何命令.
+//!
+//! ```ignore
+//! if KeybindingEditor::new("keybindings").ui(ui, &mut registry) {
+//!     registry.keymap().save(&keymap_path)?;
+//! }
+//! ```
+
+use egui::{AtomLayout, KeyboardShortcut, Modifiers, RichText, TextStyle};
+use smallvec::SmallVec;
+
+use crate::UiExt as _;
+use crate::components::basic::IconText;
+use crate::components::command_registry::{CommandId, CommandRegistry};
+use crate::components::key_sequence::KeySequence;
+use crate::components::keymap::{KeymapConflict, find_conflicts};
+
+/// 编辑器状态 (存储在 egui memory 中).
+#[derive(Clone)]
+struct EditorState<C> {
+    filter: String,
+    recording: Option<C>,
+
+    /// Steps recorded so far.
+    recorded: SmallVec<[KeyboardShortcut; 2]>,
+    last_capture: Option<web_time::Instant>,
+}
+
+impl<C> Default for EditorState<C> {
+    fn default() -> Self {
+        Self {
+            filter: String::new(),
+            recording: None,
+            recorded: SmallVec::new(),
+            last_capture: None,
+        }
+    }
+}
+
+impl<C> EditorState<C> {
+    fn stop_recording(&mut self) {
+        self.recording = None;
+        self.recorded.clear();
+        self.last_capture = None;
+    }
+}
+
+/// A panel for remapping the shortcuts of a [`CommandRegistry`].
+pub struct KeybindingEditor {
+    id: egui::Id,
+}
+
+impl KeybindingEditor {
+    pub fn new(id_salt: impl std::hash::Hash) -> Self {
+        Self {
+            id: egui::Id::new(id_salt),
+        }
+    }
+
+    fn recording_id() -> egui::Id {
+        egui::Id::new("keybinding_editor_recording")
+    }
+
+    /// Is any editor currently recording a shortcut?
+    ///
+    /// Also true for one frame after recording, so the recorded key press isn't dispatched.
+    pub fn is_recording(ctx: &egui::Context) -> bool {
+        let pass_nr = ctx.cumulative_pass_nr();
+        ctx.data(|d| d.get_temp::<u64>(Self::recording_id()))
+            .is_some_and(|last| pass_nr <= last + 1)
+    }
+
+    /// Stop recording, if any.
+    pub fn cancel_recording<C: CommandId>(&self, ctx: &egui::Context) {
+        ctx.data_mut(|d| {
+            d.get_temp_mut_or_default::<EditorState<C>>(self.id)
+                .stop_recording()
+        });
+    }
+
+    /// Show the editor. Returns `true` if the keymap was changed.
+    pub fn ui<C: CommandId>(&self, ui: &mut egui::Ui, registry: &mut CommandRegistry<C>) -> bool {
+        let mut state: EditorState<C> = ui.data(|d| d.get_temp(self.id)).unwrap_or_default();
+        let mut changed = false;
+        let os = ui.ctx().os();
+
+        if let Some(recording) = state.recording.clone() {
+            ui.data_mut(|d| d.insert_temp(Self::recording_id(), ui.ctx().cumulative_pass_nr()));
+
+            let timeout = registry.key_sequence_timeout();
+            let finish = match capture_shortcut(ui.ctx(), !state.recorded.is_empty()) {
+                Some(Capture::Cancel) => {
+                    state.stop_recording();
+                    false
+                }
+                Some(Capture::Finish) => true,
+                Some(Capture::Shortcut(shortcut)) => {
+                    state.recorded.push(shortcut);
+                    state.last_capture = Some(web_time::Instant::now());
+                    ui.ctx().request_repaint_after(timeout);
+                    false
+                }
+                None => state
+                    .last_capture
+                    .is_some_and(|last| last.elapsed() >= timeout),
+            };
+
+            if finish && !state.recorded.is_empty() {
+                let sequence = KeySequence::new(state.recorded.drain(..));
+                registry.keymap_mut().set(&recording, [sequence]);
+                state.stop_recording();
+                changed = true;
+            }
+        }
+
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut state.filter)
+                    .hint_text(format!(
+                        "{} Filter commands",
+                        egui_phosphor::regular::MAGNIFYING_GLASS
+                    ))
+                    .desired_width(220.0),
+            );
+
+            ui.add_enabled_ui(!registry.keymap().is_empty(), |ui| {
+                if ui.button("Reset all").clicked() {
+                    registry.keymap_mut().reset_all();
+                    state.stop_recording();
+                    changed = true;
+                }
+            });
+        });
+
+        let conflicts = find_conflicts(registry, os);
+        let filter = state.filter.to_lowercase();
+        let ids: Vec<C> = registry
+            .iter()
+            .filter(|command| command.is_visible(ui.ctx()))
+            .filter(|command| {
+                filter.is_empty()
+                    || command.text.to_lowercase().contains(&filter)
+                    || command
+                        .category
+                        .as_ref()
+                        .is_some_and(|c| c.to_lowercase().contains(&filter))
+            })
+            .map(|command| command.id.clone())
+            .collect();
+
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                egui::Grid::new(self.id.with("grid"))
+                    .num_columns(3)
+                    .striped(true)
+                    .spacing([12.0, 6.0])
+                    .show(ui, |ui| {
+                        for id in ids {
+                            let Some(command) = registry.get(&id) else {
+                                continue;
+                            };
+
+                            ui.horizontal(|ui| {
+                                if let Some(category) = &command.category {
+                                    ui.weak(format!("{category}:"));
+                                }
+                                ui.label(&command.text);
+                                if let Some(when) = &command.when {
+                                    ui.weak(format!("when {when}"));
+                                }
+                            })
+                            .response
+                            .on_hover_text(&command.tooltip);
+
+                            let is_recording = state.recording.as_ref() == Some(&id);
+                            ui.horizontal(|ui| {
+                                if is_recording {
+                                    let text = if state.recorded.is_empty() {
+                                        "Press a shortcut…"
+                                    } else {
+                                        "… (Enter to finish)"
+                                    };
+                                    if !state.recorded.is_empty() {
+                                        bindings_ui(
+                                            ui,
+                                            os,
+                                            &[KeySequence::new(state.recorded.iter().copied())],
+                                        );
+                                    }
+                                    ui.label(
+                                        RichText::new(text)
+                                            .italics()
+                                            .color(ui.tokens().info_text_color),
+                                    );
+                                } else {
+                                    bindings_ui(ui, os, &registry.bindings(&id));
+                                }
+
+                                for conflict in
+                                    conflicts.iter().filter(|conflict| conflict.involves(&id))
+                                {
+                                    ui.label(
+                                        RichText::new(egui_phosphor::regular::WARNING)
+                                            .color(ui.visuals().warn_fg_color),
+                                    )
+                                    .on_hover_text(conflict_text(registry, conflict, &id));
+                                }
+                            });
+
+                            ui.horizontal(|ui| {
+                                let record_text = if is_recording { "Cancel" } else { "Record" };
+                                if ui.small_button(record_text).clicked() {
+                                    state.stop_recording();
+                                    state.recording = (!is_recording).then(|| id.clone());
+                                }
+
+                                let is_overridden = registry.keymap().is_overridden(&id);
+                                if ui
+                                    .add_enabled(is_overridden, egui::Button::new("Reset").small())
+                                    .clicked()
+                                {
+                                    registry.keymap_mut().reset(&id);
+                                    changed = true;
+                                }
+
+                                let has_bindings = !registry.bindings(&id).is_empty();
+                                if ui
+                                    .add_enabled(has_bindings, egui::Button::new("Clear").small())
+                                    .clicked()
+                                {
+                                    registry.keymap_mut().unbind(&id);
+                                    changed = true;
+                                }
+                            });
+
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        ui.data_mut(|d| d.insert_temp(self.id, state));
+        changed
+    }
+}
+
+fn bindings_ui(ui: &mut egui::Ui, os: egui::os::OperatingSystem, bindings: &[KeySequence]) {
+    if bindings.is_empty() {
+        ui.weak("—");
+        return;
+    }
+
+    let color = ui.visuals().widgets.inactive.text_color();
+    ui.style_mut().override_text_style = Some(TextStyle::Monospace);
+    ui.visuals_mut().override_text_color = Some(color);
+    for (i, sequence) in bindings.iter().enumerate() {
+        if 0 < i {
+            ui.weak("/");
+        }
+        let mut atoms = IconText::from_key_sequence(os, sequence.steps());
+        atoms.map_images(|image| image.tint(color));
+        AtomLayout::new(atoms).gap(2.0).show(ui);
+    }
+}
+
+fn conflict_text<C: CommandId>(
+    registry: &CommandRegistry<C>,
+    conflict: &KeymapConflict<C>,
+    id: &C,
+) -> String {
+    let name = |id: &C| {
+        registry
+            .get(id)
+            .map_or_else(|| format!("{id:?}"), |command| command.text.clone())
+    };
+    match conflict {
+        KeymapConflict::Commands { a, b, .. } => {
+            let other = if a == id { b } else { a };
+            format!("Also used by \"{}\"", name(other))
+        }
+        KeymapConflict::Reserved { reason, .. } => {
+            format!("Reserved by the operating system ({reason})")
+        }
+    }
+}
+
+enum Capture {
+    Cancel,
+    Finish,
+    Shortcut(KeyboardShortcut),
+}
+
+/// Take the first key press out of the input.
+///
+/// `Enter` finishes the recording once at least one step has been recorded.
+fn capture_shortcut(ctx: &egui::Context, has_steps: bool) -> Option<Capture> {
+    let is_mac = ctx.os().is_mac();
+    ctx.input_mut(|input| {
+        let index = input.events.iter().position(|event| {
+            matches!(
+                event,
+                egui::Event::Key {
+                    pressed: true,
+                    repeat: false,
+                    ..
+                }
+            )
+        })?;
+
+        let egui::Event::Key { key, modifiers, .. } = input.events.remove(index) else {
+            unreachable!()
+        };
+        input.keys_down.remove(&key);
+
+        if key == egui::Key::Escape && modifiers.is_none() {
+            return Some(Capture::Cancel);
+        }
+        if key == egui::Key::Enter && modifiers.is_none() && has_steps {
+            return Some(Capture::Finish);
+        }
+
+        // 统一为跨平台的 `Cmd` (Mac 上的 ⌘, 其它平台的 Ctrl).
+        let modifiers = if modifiers.command {
+            Modifiers {
+                ctrl: is_mac && modifiers.ctrl,
+                mac_cmd: false,
+                ..modifiers
+            }
+        } else {
+            modifiers
+        };
+
+        Some(Capture::Shortcut(KeyboardShortcut::new(modifiers, key)))
+    })
+}
@@ -0,0 +1,510 @@
+//! 用户可重映射的快捷键 (Keymap).
+//!
+//! [`Keymap`] 以 [`CommandRegistry`] 中注册的默认快捷键为基础, 只保存用户覆盖的部分.
+//! 覆盖可以保存为 RON 文件, 或保存在 egui 的持久化 memory 中.
+//!
+//! 快捷键使用可读的文本格式, 与平台无关:
+//! - `Cmd` 表示 [`Modifiers::COMMAND`] (Mac 上为 ⌘, 其它平台为 Ctrl)
+//! - 例如 `"Cmd+Shift+S"`, `"Alt+F4"`, `"F11"`
+//! - 多步快捷键以空格分隔, 例如 `"Cmd+K Cmd+S"`, `"G G"`
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use egui::os::OperatingSystem;
+use egui::{Key, KeyboardShortcut, Modifiers};
+use smallvec::SmallVec;
+
+use crate::components::command_registry::{CommandId, CommandRegistry};
+use crate::components::key_sequence::KeySequence;
+
+/// Effective key bindings of a command, with the primary first.
+pub type Bindings = SmallVec<[KeySequence; 2]>;
+
+/// User overrides on top of the default shortcuts of a [`CommandRegistry`].
+///
+/// Overrides are keyed by [`CommandId::persistence_key`], so they survive
+/// commands being added or removed between app versions.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Keymap {
+    overrides: BTreeMap<String, Bindings>,
+}
+
+impl Keymap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The user override for a command, if any. An empty override means "unbound".
+    pub fn get_override<C: CommandId>(&self, id: &C) -> Option<&Bindings> {
+        self.overrides.get(&id.persistence_key())
+    }
+
+    pub fn is_overridden<C: CommandId>(&self, id: &C) -> bool {
+        self.overrides.contains_key(&id.persistence_key())
+    }
+
+    /// Replace the key bindings of a command.
+    pub fn set<C: CommandId>(&mut self, id: &C, bindings: impl IntoIterator<Item = KeySequence>) {
+        self.overrides
+            .insert(id.persistence_key(), bindings.into_iter().collect());
+    }
+
+    /// Remove all key bindings of a command, including its defaults.
+    pub fn unbind<C: CommandId>(&mut self, id: &C) {
+        self.overrides.insert(id.persistence_key(), Bindings::new());
+    }
+
+    /// Go back to the default shortcuts of a command.
+    pub fn reset<C: CommandId>(&mut self, id: &C) {
+        self.overrides.remove(&id.persistence_key());
+    }
+
+    pub fn reset_all(&mut self) {
+        self.overrides.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.overrides.is_empty()
+    }
+
+    // ---------------------------------------------------------------------
+    // 序列化
+
+    /// Serialize the overrides as RON (`{ "SaveRecording": ["Cmd+S"], "Save": ["Cmd+K S"], … }`).
+    pub fn to_ron(&self) -> anyhow::Result<String> {
+        let map: BTreeMap<&str, Vec<String>> = self
+            .overrides
+            .iter()
+            .map(|(key, bindings)| {
+                (
+                    key.as_str(),
+                    bindings
+                        .iter()
+                        .map(KeySequence::to_portable_string)
+                        .collect(),
+                )
+            })
+            .collect();
+        Ok(ron::ser::to_string_pretty(
+            &map,
+            ron::ser::PrettyConfig::default(),
+        )?)
+    }
+
+    pub fn from_ron(text: &str) -> anyhow::Result<Self> {
+        let map: BTreeMap<String, Vec<String>> = ron::from_str(text)?;
+        let mut overrides = BTreeMap::new();
+        for (key, bindings) in map {
+            let bindings = bindings
+                .iter()
+                .map(|text| {
+                    KeySequence::parse(text)
+                        .ok_or_else(|| anyhow::anyhow!("Invalid shortcut {text:?} for {key:?}"))
+                })
+                .collect::<anyhow::Result<Bindings>>()?;
+            overrides.insert(key, bindings);
+        }
+        Ok(Self { overrides })
+    }
+
+    /// Load overrides from a RON file. A missing file yields an empty keymap.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = std::fs::read_to_string(path)?;
+        Self::from_ron(&text)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.to_ron()?)?;
+        Ok(())
+    }
+
+    /// Load overrides from egui's persisted memory (requires egui `persistence` to survive restarts).
+    pub fn load_from_memory(ctx: &egui::Context, id: egui::Id) -> Self {
+        let text = ctx.data_mut(|d| d.get_persisted::<String>(id));
+        match text.as_deref().map(Self::from_ron) {
+            Some(Ok(keymap)) => keymap,
+            Some(Err(err)) => {
+                log::warn!("Failed to load keymap from memory: {err}");
+                Self::default()
+            }
+            None => Self::default(),
+        }
+    }
+
+    pub fn save_to_memory(&self, ctx: &egui::Context, id: egui::Id) {
+        match self.to_ron() {
+            Ok(text) => ctx.data_mut(|d| d.insert_persisted(id, text)),
+            Err(err) => log::warn!("Failed to save keymap to memory: {err}"),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// 冲突检测
+
+/// A problem with the effective keymap of a [`CommandRegistry`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KeymapConflict<C> {
+    /// Two commands share the same shortcut in the same when-clause context.
+    Commands { sequence: KeySequence, a: C, b: C },
+
+    /// A command uses a shortcut the operating system handles itself.
+    Reserved {
+        sequence: KeySequence,
+        command: C,
+        reason: &'static str,
+    },
+}
+
+impl<C: CommandId> KeymapConflict<C> {
+    pub fn involves(&self, id: &C) -> bool {
+        match self {
+            Self::Commands { a, b, .. } => a == id || b == id,
+            Self::Reserved { command, .. } => command == id,
+        }
+    }
+
+    pub fn sequence(&self) -> &KeySequence {
+        match self {
+            Self::Commands { sequence, .. } | Self::Reserved { sequence, .. } => sequence,
+        }
+    }
+}
+
+/// The keys that `modifiers` stand for on `os`: `command` is `Cmd` on Mac and `Ctrl` elsewhere.
+fn physical_modifiers(os: OperatingSystem, modifiers: Modifiers) -> Modifiers {
+    if os.is_mac() {
+        Modifiers {
+            mac_cmd: modifiers.mac_cmd || modifiers.command,
+            command: false,
+            ..modifiers
+        }
+    } else {
+        Modifiers {
+            ctrl: modifiers.ctrl || modifiers.command,
+            mac_cmd: false,
+            command: false,
+            ..modifiers
+        }
+    }
+}
+
+/// Would `a` and `b` be triggered by the same key press on `os`?
+///
+/// On Mac `Cmd+X` and `Ctrl+X` are different keys, elsewhere `command` is `Ctrl`.
+pub fn shortcuts_clash(os: OperatingSystem, a: KeyboardShortcut, b: KeyboardShortcut) -> bool {
+    a.logical_key == b.logical_key
+        && physical_modifiers(os, a.modifiers) == physical_modifiers(os, b.modifiers)
+}
+
+/// Would `a` and `b` be triggered by the same keys on `os`?
+///
+/// A sequence that merely starts with a shorter one (`G` and `G G`) is not a clash:
+/// the shorter one is triggered once the longer one can no longer complete,
+/// see [`CommandRegistry::listen_for_kb_shortcut`](crate::CommandRegistry::listen_for_kb_shortcut).
+pub fn sequences_clash(os: OperatingSystem, a: &KeySequence, b: &KeySequence) -> bool {
+    a.len() == b.len()
+        && a.steps()
+            .iter()
+            .zip(b.steps())
+            .all(|(a, b)| shortcuts_clash(os, *a, *b))
+}
+
+/// Shortcuts handled by the operating system before the app sees them.
+pub fn os_reserved_shortcuts(os: OperatingSystem) -> &'static [(KeyboardShortcut, &'static str)] {
+    const fn shortcut(modifiers: Modifiers, key: Key) -> KeyboardShortcut {
+        KeyboardShortcut::new(modifiers, key)
+    }
+
+    const MAC: &[(KeyboardShortcut, &str)] = &[
+        (shortcut(Modifiers::MAC_CMD, Key::Tab), "App switcher"),
+        (shortcut(Modifiers::MAC_CMD, Key::Space), "Spotlight"),
+        (shortcut(Modifiers::MAC_CMD, Key::H), "Hide application"),
+        (shortcut(Modifiers::MAC_CMD, Key::M), "Minimize window"),
+        (
+            shortcut(Modifiers::MAC_CMD.plus(Modifiers::ALT), Key::Escape),
+            "Force quit",
+        ),
+        (
+            shortcut(Modifiers::MAC_CMD.plus(Modifiers::CTRL), Key::Q),
+            "Lock screen",
+        ),
+    ];
+
+    const WINDOWS: &[(KeyboardShortcut, &str)] = &[
+        (shortcut(Modifiers::ALT, Key::Tab), "App switcher"),
+        (shortcut(Modifiers::ALT, Key::Escape), "Cycle windows"),
+        (shortcut(Modifiers::CTRL, Key::Escape), "Start menu"),
+        (
+            shortcut(Modifiers::CTRL.plus(Modifiers::ALT), Key::Delete),
+            "Security screen",
+        ),
+        (
+            shortcut(Modifiers::CTRL.plus(Modifiers::SHIFT), Key::Escape),
+            "Task manager",
+        ),
+    ];
+
+    const NIX: &[(KeyboardShortcut, &str)] = &[
+        (shortcut(Modifiers::ALT, Key::Tab), "App switcher"),
+        (
+            shortcut(Modifiers::CTRL.plus(Modifiers::ALT), Key::Delete),
+            "Log out",
+        ),
+        (
+            shortcut(Modifiers::CTRL.plus(Modifiers::ALT), Key::T),
+            "Open terminal",
+        ),
+    ];
+
+    match os {
+        OperatingSystem::Mac | OperatingSystem::IOS => MAC,
+        OperatingSystem::Windows => WINDOWS,
+        OperatingSystem::Nix | OperatingSystem::Android => NIX,
+        OperatingSystem::Unknown => &[],
+    }
+}
+
+fn is_reserved(os: OperatingSystem, shortcut: KeyboardShortcut) -> Option<&'static str> {
+    os_reserved_shortcuts(os)
+        .iter()
+        .find_map(|(reserved, reason)| shortcuts_clash(os, *reserved, shortcut).then_some(*reason))
+}
+
+/// All conflicts in the effective shortcuts of `registry`.
+pub fn find_conflicts<C: CommandId>(
+    registry: &CommandRegistry<C>,
+    os: OperatingSystem,
+) -> Vec<KeymapConflict<C>> {
+    let bindings: Vec<(C, KeySequence)> = registry
+        .iter()
+        .flat_map(|command| {
+            registry
+                .bindings(&command.id)
+                .into_iter()
+                .map(|sequence| (command.id.clone(), sequence))
+        })
+        .collect();
+
+    let mut conflicts = Vec::new();
+    for (i, (a, a_sequence)) in bindings.iter().enumerate() {
+        if let Some(reason) = a_sequence
+            .steps()
+            .iter()
+            .find_map(|step| is_reserved(os, *step))
+        {
+            conflicts.push(KeymapConflict::Reserved {
+                sequence: a_sequence.clone(),
+                command: a.clone(),
+                reason,
+            });
+        }
+
+        for (b, b_sequence) in &bindings[i + 1..] {
+            // 不同 when 子句的命令由分发时的上下文区分, 不算冲突.
+            let same_context = registry.get(a).map(|command| &command.when)
+                == registry.get(b).map(|command| &command.when);
+            if a != b && same_context && sequences_clash(os, a_sequence, b_sequence) {
+                conflicts.push(KeymapConflict::Commands {
+                    sequence: a_sequence.clone(),
+                    a: a.clone(),
+                    b: b.clone(),
+                });
+            }
+        }
+    }
+    conflicts
+}
+
+// ---------------------------------------------------------------------------
+// 文本格式
+
+/// Format a shortcut in the portable text format, e.g. `"Cmd+Shift+S"`.
+pub fn format_shortcut(shortcut: &KeyboardShortcut) -> String {
+    let Modifiers {
+        alt,
+        ctrl,
+        shift,
+        mac_cmd,
+        command,
+    } = shortcut.modifiers;
+
+    let mut parts = Vec::new();
+    if ctrl && !command {
+        parts.push("Ctrl");
+    }
+    if command {
+        parts.push("Cmd");
+    }
+    if mac_cmd && !command {
+        parts.push("MacCmd");
+    }
+    if alt {
+        parts.push("Alt");
+    }
+    if shift {
+        parts.push("Shift");
+    }
+    parts.push(shortcut.logical_key.name());
+    parts.join("+")
+}
+
+/// Parse the portable text format produced by [`format_shortcut`].
+pub fn parse_shortcut(text: &str) -> Option<KeyboardShortcut> {
+    let mut modifiers = Modifiers::NONE;
+    let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+
+    // `Cmd++` etc.: the key itself may be a `+`.
+    if text.ends_with("++") {
+        parts.truncate(parts.len().saturating_sub(2));
+        parts.push("+");
+    }
+
+    let (key, modifier_parts) = parts.split_last()?;
+    for part in modifier_parts {
+        modifiers |= match part.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => Modifiers::CTRL,
+            "cmd" | "command" => Modifiers::COMMAND,
+            "maccmd" => Modifiers::MAC_CMD,
+            "alt" | "option" => Modifiers::ALT,
+            "shift" => Modifiers::SHIFT,
+            _ => return None,
+        };
+    }
+
+    Some(KeyboardShortcut::new(modifiers, Key::from_name(key)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::os::OperatingSystem;
+    use egui::{Key, KeyboardShortcut, Modifiers};
+
+    use super::{
+        Keymap, KeymapConflict, find_conflicts, format_shortcut, parse_shortcut, shortcuts_clash,
+    };
+    use crate::components::command_registry::{Command, CommandRegistry};
+    use crate::components::key_sequence::KeySequence;
+
+    #[test]
+    fn test_shortcut_text_roundtrip() {
+        for shortcut in [
+            KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::S),
+            KeyboardShortcut::new(Modifiers::CTRL, Key::Comma),
+            KeyboardShortcut::new(Modifiers::ALT, Key::F4),
+            KeyboardShortcut::new(Modifiers::NONE, Key::Space),
+            KeyboardShortcut::new(Modifiers::COMMAND, Key::Plus),
+        ] {
+            let text = format_shortcut(&shortcut);
+            assert_eq!(parse_shortcut(&text), Some(shortcut), "{text}");
+        }
+
+        assert_eq!(
+            format_shortcut(&KeyboardShortcut::new(
+                Modifiers::COMMAND | Modifiers::SHIFT,
+                Key::S
+            )),
+            "Cmd+Shift+S"
+        );
+        assert_eq!(parse_shortcut("Hyper+S"), None);
+
+        let sequence = KeySequence::new([
+            KeyboardShortcut::new(Modifiers::COMMAND, Key::K),
+            KeyboardShortcut::new(Modifiers::NONE, Key::S),
+        ]);
+        assert_eq!(sequence.to_portable_string(), "Cmd+K S");
+        assert_eq!(KeySequence::parse("Cmd+K S"), Some(sequence));
+    }
+
+    #[test]
+    fn test_keymap_overrides() {
+        let mut registry = CommandRegistry::new();
+        registry
+            .register(
+                Command::new("save", "Save")
+                    .shortcut(KeyboardShortcut::new(Modifiers::COMMAND, Key::S)),
+            )
+            .register(Command::new("sync", "Sync"));
+
+        let mut keymap = Keymap::new();
+        keymap.set(
+            &"sync",
+            [KeyboardShortcut::new(Modifiers::COMMAND, Key::S).into()],
+        );
+        registry.set_keymap(keymap.clone());
+
+        let conflicts = find_conflicts(&registry, OperatingSystem::Nix);
+        assert_eq!(
+            conflicts,
+            vec![KeymapConflict::Commands {
+                sequence: KeyboardShortcut::new(Modifiers::COMMAND, Key::S).into(),
+                a: "save",
+                b: "sync",
+            }]
+        );
+
+        // Unbinding removes the conflict.
+        keymap.unbind(&"save");
+        registry.set_keymap(keymap.clone());
+        assert!(find_conflicts(&registry, OperatingSystem::Nix).is_empty());
+        assert!(registry.bindings(&"save").is_empty());
+
+        // A shortcut that starts a sequence is not a conflict: the sequence takes priority.
+        keymap.set(
+            &"save",
+            [KeySequence::new([
+                KeyboardShortcut::new(Modifiers::COMMAND, Key::S),
+                KeyboardShortcut::new(Modifiers::NONE, Key::S),
+            ])],
+        );
+        registry.set_keymap(keymap.clone());
+        assert!(find_conflicts(&registry, OperatingSystem::Nix).is_empty());
+
+        let restored = Keymap::from_ron(&keymap.to_ron().unwrap()).unwrap();
+        assert_eq!(restored, keymap);
+    }
+
+    #[test]
+    fn test_reserved_shortcuts() {
+        let mut registry = CommandRegistry::new();
+        registry.register(
+            Command::new("switch", "Switch")
+                .shortcut(KeyboardShortcut::new(Modifiers::ALT, Key::Tab)),
+        );
+
+        assert!(matches!(
+            find_conflicts(&registry, OperatingSystem::Windows).as_slice(),
+            [KeymapConflict::Reserved {
+                command: "switch",
+                ..
+            }]
+        ));
+        assert!(find_conflicts(&registry, OperatingSystem::Mac).is_empty());
+    }
+
+    #[test]
+    fn test_shortcuts_clash_per_os() {
+        let cmd_x = KeyboardShortcut::new(Modifiers::COMMAND, Key::X);
+        let ctrl_x = KeyboardShortcut::new(Modifiers::CTRL, Key::X);
+        let mac_cmd_x = KeyboardShortcut::new(Modifiers::MAC_CMD, Key::X);
+
+        assert!(shortcuts_clash(OperatingSystem::Windows, cmd_x, ctrl_x));
+        assert!(shortcuts_clash(OperatingSystem::Nix, cmd_x, ctrl_x));
+        assert!(!shortcuts_clash(OperatingSystem::Mac, cmd_x, ctrl_x));
+        assert!(shortcuts_clash(OperatingSystem::Mac, cmd_x, mac_cmd_x));
+        assert!(!shortcuts_clash(
+            OperatingSystem::Mac,
+            cmd_x,
+            KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::X)
+        ));
+    }
+}
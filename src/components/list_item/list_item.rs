@@ -344,6 +344,39 @@ impl ListItem {
         .response
     }
 
+    /// Draw the item as one row of a flattened hierarchy, e.g. the tree column of a table.
+    ///
+    /// Unlike [`Self::show_hierarchical_with_children`], the children are not nested in the item:
+    /// the caller indents the row and keeps track of the open state. Pass `None` as `openness` for
+    /// leaf nodes, which are then aligned with the labels of collapsible nodes.
+    ///
+    /// Returns the item response and, for collapsible nodes, the collapsing triangle response.
+    ///
+    /// *Important*: must be called while nested in a [`super::list_item_scope`].
+    pub fn show_hierarchical_row(
+        mut self,
+        ui: &mut Ui,
+        id: egui::Id,
+        openness: Option<f32>,
+        content: impl ListItemContent,
+    ) -> (Response, Option<Response>) {
+        self.collapse_openness = openness;
+
+        // Note: the purpose of the scope is to minimise interferences on subsequent items' id
+        let response = ui
+            .scope(|ui| {
+                let tokens = ui.tokens();
+                let extra_indent = if openness.is_some() {
+                    0.0
+                } else {
+                    tokens.small_icon_size.x + tokens.text_to_icon_padding()
+                };
+                self.ui(ui, Some(id), extra_indent, Box::new(content))
+            })
+            .inner;
+        (response.response, response.collapse_response)
+    }
+
     /// Draw the item as a non-leaf node from a hierarchical list.
     ///
     /// The `id` should be globally unique! You can use `ui.make_persistent_id(…)` for that. The
@@ -0,0 +1,433 @@
+//! 声明式菜单栏 (MenuBar).
+//!
+//! 菜单由引用 [`CommandRegistry`] 中命令的条目组成, 自动显示命令的快捷键, 启用状态和提示.
+//! 标题中 `&` 之后的字母是助记符: 按住 `Alt` 时带下划线, `Alt + 字母` 打开该菜单.
+//! 菜单打开后可用方向键导航 (`←` / `→` 切换菜单, `↑` / `↓` 切换条目, `Enter` 执行).
+//!
+//! 模型很轻量, 可以每帧重新构建, 这样勾选状态总是最新的:
+//!
+//! ```ignore
+//! let menu_bar = MenuBar::new()
+//!     .menu(
+//!         Menu::new("&File")
+//!             .command(AppCommand::Open)
+//!             .submenu(Menu::new("Recent").commands(recent_commands))
+//!             .separator()
+//!             .command(AppCommand::Quit),
+//!     )
+//!     .menu(
+//!         Menu::new("&View")
+//!             .checkbox(AppCommand::ToggleSidebar, self.show_sidebar)
+//!             .separator()
+//!             .radio_group([
+//!                 (AppCommand::Theme(Theme::Light), self.theme == Theme::Light),
+//!                 (AppCommand::Theme(Theme::Dark), self.theme == Theme::Dark),
+//!             ]),
+//!     );
+//!
+//! menu_bar.ui(ui, &registry, &command_sender);
+//! ```
+
+use egui::containers::menu::{MenuConfig, SubMenuButton};
+use egui::{AtomExt as _, Key, Modifiers, Popup};
+
+use crate::components::command_registry::{CommandId, CommandRegistry, CommandSender};
+use crate::components::menu::menu_style;
+use crate::extensions::context_ext::ContextExt as _;
+
+/// A single entry of a [`Menu`].
+pub enum MenuItem<C: CommandId> {
+    /// Runs the command when clicked.
+    Command(C),
+
+    /// A command shown with a check mark when `checked`.
+    Checkbox {
+        command: C,
+        checked: bool,
+    },
+
+    /// A command shown with a dot when `selected`. Consecutive radio items form a group.
+    Radio {
+        command: C,
+        selected: bool,
+    },
+
+    Separator,
+    Submenu(Menu<C>),
+}
+
+impl<C: CommandId> MenuItem<C> {
+    fn command(&self) -> Option<&C> {
+        match self {
+            Self::Command(command)
+            | Self::Checkbox { command, .. }
+            | Self::Radio { command, .. } => Some(command),
+            Self::Separator | Self::Submenu(_) => None,
+        }
+    }
+
+    fn is_visible(&self, registry: &CommandRegistry<C>, egui_ctx: &egui::Context) -> bool {
+        match self {
+            Self::Separator => false,
+            Self::Submenu(menu) => menu
+                .items
+                .iter()
+                .any(|item| item.is_visible(registry, egui_ctx)),
+            _ => self
+                .command()
+                .is_some_and(|command| registry.is_visible(command, egui_ctx)),
+        }
+    }
+}
+
+/// A menu or submenu.
+pub struct Menu<C: CommandId> {
+    /// The title without the mnemonic marker.
+    pub title: String,
+
+    /// Char index of the mnemonic letter in [`Self::title`].
+    pub mnemonic: Option<usize>,
+
+    pub items: Vec<MenuItem<C>>,
+}
+
+impl<C: CommandId> Menu<C> {
+    /// `title` may mark its mnemonic with `&`, e.g. `"&File"`. Use `&&` for a literal `&`.
+    pub fn new(title: &str) -> Self {
+        let (title, mnemonic) = parse_mnemonic(title);
+        Self {
+            title,
+            mnemonic,
+            items: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn item(mut self, item: MenuItem<C>) -> Self {
+        self.items.push(item);
+        self
+    }
+
+    #[inline]
+    pub fn command(self, command: C) -> Self {
+        self.item(MenuItem::Command(command))
+    }
+
+    #[inline]
+    pub fn commands(mut self, commands: impl IntoIterator<Item = C>) -> Self {
+        self.items
+            .extend(commands.into_iter().map(MenuItem::Command));
+        self
+    }
+
+    #[inline]
+    pub fn checkbox(self, command: C, checked: bool) -> Self {
+        self.item(MenuItem::Checkbox { command, checked })
+    }
+
+    #[inline]
+    pub fn radio(self, command: C, selected: bool) -> Self {
+        self.item(MenuItem::Radio { command, selected })
+    }
+
+    /// Add `(command, selected)` pairs as radio items.
+    #[inline]
+    pub fn radio_group(mut self, items: impl IntoIterator<Item = (C, bool)>) -> Self {
+        self.items.extend(
+            items
+                .into_iter()
+                .map(|(command, selected)| MenuItem::Radio { command, selected }),
+        );
+        self
+    }
+
+    #[inline]
+    pub fn separator(self) -> Self {
+        self.item(MenuItem::Separator)
+    }
+
+    #[inline]
+    pub fn submenu(self, menu: Self) -> Self {
+        self.item(MenuItem::Submenu(menu))
+    }
+
+    /// The title, with the mnemonic underlined if `underline`.
+    fn title_text(&self, ui: &egui::Ui, underline: bool) -> egui::WidgetText {
+        let Some(mnemonic) = self.mnemonic.filter(|_| underline) else {
+            return self.title.clone().into();
+        };
+
+        // `PLACEHOLDER` takes the button's text color.
+        let format = egui::TextFormat::simple(
+            egui::TextStyle::Button.resolve(ui.style()),
+            egui::Color32::PLACEHOLDER,
+        );
+        let mut job = egui::text::LayoutJob::default();
+        for (i, c) in self.title.chars().enumerate() {
+            let mut format = format.clone();
+            if i == mnemonic {
+                format.underline = egui::Stroke::new(1.0, egui::Color32::PLACEHOLDER);
+            }
+            job.append(c.encode_utf8(&mut [0; 4]), 0.0, format);
+        }
+        job.into()
+    }
+
+    fn mnemonic_key(&self) -> Option<Key> {
+        let c = self.title.chars().nth(self.mnemonic?)?;
+        Key::from_name(&c.to_ascii_uppercase().to_string())
+    }
+
+    /// Show the items of this menu.
+    ///
+    /// Hidden commands are skipped, as are separators that would end up first, last or doubled.
+    pub fn items_ui(
+        &self,
+        ui: &mut egui::Ui,
+        registry: &CommandRegistry<C>,
+        command_sender: &impl CommandSender<C>,
+    ) {
+        self.items_ui_impl(ui, registry, command_sender, false);
+    }
+
+    fn items_ui_impl(
+        &self,
+        ui: &mut egui::Ui,
+        registry: &CommandRegistry<C>,
+        command_sender: &impl CommandSender<C>,
+        mut focus_first: bool,
+    ) {
+        let mut needs_separator = false;
+        let mut any_shown = false;
+
+        for item in &self.items {
+            if matches!(item, MenuItem::Separator) {
+                needs_separator = any_shown;
+                continue;
+            }
+            if !item.is_visible(registry, ui.ctx()) {
+                continue;
+            }
+            if needs_separator {
+                ui.separator();
+                needs_separator = false;
+            }
+            any_shown = true;
+
+            let response = match item {
+                MenuItem::Command(command) => registry.menu_button_ui(ui, command, command_sender),
+                MenuItem::Checkbox { command, checked } => toggle_button_ui(
+                    ui,
+                    registry,
+                    command,
+                    command_sender,
+                    checked.then_some("✔"),
+                ),
+                MenuItem::Radio { command, selected } => toggle_button_ui(
+                    ui,
+                    registry,
+                    command,
+                    command_sender,
+                    selected.then_some("•"),
+                ),
+                MenuItem::Submenu(menu) => Some(
+                    SubMenuButton::new(menu.title.clone())
+                        .config(MenuConfig::new().style(menu_style()))
+                        .ui(ui, |ui| menu.items_ui(ui, registry, command_sender))
+                        .0,
+                ),
+                MenuItem::Separator => None,
+            };
+
+            if focus_first && let Some(response) = response {
+                response.request_focus();
+                focus_first = false;
+            }
+        }
+    }
+}
+
+/// A menu bar made of [`Menu`]s.
+pub struct MenuBar<C: CommandId> {
+    pub menus: Vec<Menu<C>>,
+}
+
+impl<C: CommandId> Default for MenuBar<C> {
+    fn default() -> Self {
+        Self { menus: Vec::new() }
+    }
+}
+
+impl<C: CommandId> MenuBar<C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn menu(mut self, menu: Menu<C>) -> Self {
+        self.menus.push(menu);
+        self
+    }
+
+    /// Show the whole menu bar.
+    pub fn ui(
+        &self,
+        ui: &mut egui::Ui,
+        registry: &CommandRegistry<C>,
+        command_sender: &impl CommandSender<C>,
+    ) {
+        egui::MenuBar::new()
+            .style(menu_style())
+            .config(MenuConfig::new().style(menu_style()))
+            .ui(ui, |ui| self.menus_ui(ui, registry, command_sender));
+    }
+
+    /// Show only the menu buttons, e.g. inside an existing [`egui::MenuBar`] next to other widgets.
+    pub fn menus_ui(
+        &self,
+        ui: &mut egui::Ui,
+        registry: &CommandRegistry<C>,
+        command_sender: &impl CommandSender<C>,
+    ) {
+        let egui_ctx = ui.ctx().clone();
+        let focus_first_id = ui.id().with("menu_bar_focus_first");
+        let alt_held = egui_ctx.input(|i| i.modifiers.alt);
+
+        let mnemonic_pressed = self.menus.iter().position(|menu| {
+            menu.mnemonic_key()
+                .is_some_and(|key| egui_ctx.input_mut(|i| i.consume_key(Modifiers::ALT, key)))
+        });
+
+        // Index of the menu opened with the keyboard last frame.
+        let focus_first = egui_ctx.data_mut(|d| d.remove_temp::<usize>(focus_first_id));
+
+        let mut popup_ids = Vec::with_capacity(self.menus.len());
+        for (index, menu) in self.menus.iter().enumerate() {
+            if !menu
+                .items
+                .iter()
+                .any(|item| item.is_visible(registry, &egui_ctx))
+            {
+                popup_ids.push(None);
+                continue;
+            }
+
+            let focus_this = focus_first == Some(index);
+            let (response, _) =
+                egui::containers::menu::MenuButton::new(menu.title_text(ui, alt_held))
+                    .ui(ui, |ui| {
+                        menu.items_ui_impl(ui, registry, command_sender, focus_this)
+                    });
+            popup_ids.push(Some(Popup::default_response_id(&response)));
+        }
+
+        // Open a menu with the keyboard, moving focus to its first item:
+        let open_index = popup_ids
+            .iter()
+            .position(|id| id.is_some_and(|id| Popup::is_id_open(&egui_ctx, id)));
+        let target = if let Some(index) = mnemonic_pressed {
+            Some(index)
+        } else if let Some(open_index) = open_index {
+            let left = egui_ctx.input_mut(|i| i.consume_key(Modifiers::NONE, Key::ArrowLeft));
+            let right = egui_ctx.input_mut(|i| i.consume_key(Modifiers::NONE, Key::ArrowRight));
+            if left {
+                neighbor(&popup_ids, open_index, -1)
+            } else if right {
+                neighbor(&popup_ids, open_index, 1)
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        if let Some((index, popup_id)) =
+            target.and_then(|index| popup_ids[index].map(|popup_id| (index, popup_id)))
+        {
+            Popup::open_id(&egui_ctx, popup_id);
+            egui_ctx.data_mut(|d| d.insert_temp(focus_first_id, index));
+            egui_ctx.request_repaint();
+        }
+    }
+}
+
+/// Next shown menu in `direction`, wrapping around.
+fn neighbor(popup_ids: &[Option<egui::Id>], index: usize, direction: isize) -> Option<usize> {
+    let len = popup_ids.len() as isize;
+    (1..len)
+        .map(|step| (index as isize + direction * step).rem_euclid(len) as usize)
+        .find(|&i| popup_ids[i].is_some())
+}
+
+/// A command button with a leading check mark or radio dot.
+fn toggle_button_ui<C: CommandId>(
+    ui: &mut egui::Ui,
+    registry: &CommandRegistry<C>,
+    id: &C,
+    command_sender: &impl CommandSender<C>,
+    indicator: Option<&'static str>,
+) -> Option<egui::Response> {
+    let command = registry.get(id)?;
+    let tokens = ui.ctx().tokens();
+
+    let indicator =
+        egui::Atom::from(indicator.unwrap_or_default()).atom_size(tokens.small_icon_size);
+    let mut button = egui::Button::new((indicator, command.text.clone()));
+    if let Some(shortcut_text) = registry.formatted_kb_shortcut(id, ui.ctx()) {
+        button = button.shortcut_text(shortcut_text);
+    }
+
+    let response = ui
+        .add_enabled(command.is_enabled(ui.ctx()), button)
+        .on_hover_text(&command.tooltip);
+
+    if response.clicked() {
+        command_sender.send(id.clone());
+        ui.close();
+    }
+
+    Some(response)
+}
+
+/// `"&File"` → `("File", Some(0))`. `&&` is a literal `&`.
+fn parse_mnemonic(title: &str) -> (String, Option<usize>) {
+    let mut text = String::with_capacity(title.len());
+    let mut mnemonic = None;
+    let mut chars = title.chars().peekable();
+    let mut index = 0;
+    while let Some(c) = chars.next() {
+        if c == '&' {
+            match chars.peek() {
+                Some('&') => {
+                    chars.next();
+                }
+                Some(_) => {
+                    if mnemonic.is_none() {
+                        mnemonic = Some(index);
+                    }
+                    continue;
+                }
+                None => {}
+            }
+        }
+        text.push(c);
+        index += 1;
+    }
+    (text, mnemonic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_mnemonic;
+
+    #[test]
+    fn test_parse_mnemonic() {
+        assert_eq!(parse_mnemonic("&File"), ("File".to_owned(), Some(0)));
+        assert_eq!(parse_mnemonic("E&xit"), ("Exit".to_owned(), Some(1)));
+        assert_eq!(
+            parse_mnemonic("Save && &Quit"),
+            ("Save & Quit".to_owned(), Some(7))
+        );
+        assert_eq!(parse_mnemonic("View"), ("View".to_owned(), None));
+    }
+}
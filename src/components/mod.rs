@@ -4,30 +4,56 @@ pub mod basic;
 
 // 组件模块
 pub mod alert;
+pub mod banner;
 pub mod command;
+pub mod command_arguments;
+pub mod command_context;
 pub mod command_palette;
+pub mod command_registry;
 pub mod dialog;
 pub mod filter;
 pub mod help;
+pub mod key_sequence;
+pub mod keybinding_editor;
+pub mod keymap;
 pub mod list_item;
 pub mod menu;
+pub mod menu_bar;
 pub mod modal;
+pub mod modal_stack;
 pub mod notifications;
+pub mod palette_history;
+pub mod palette_provider;
 pub mod section_header;
 pub mod tree;
+pub mod undo_stack;
+pub mod wizard;
 
 // Re-exports
 pub use basic::*;
 
 pub use alert::*;
+pub use banner::*;
 pub use command::*;
+pub use command_arguments::*;
+pub use command_context::*;
 pub use command_palette::*;
+pub use command_registry::*;
 pub use dialog::*;
 pub use filter::*;
 pub use help::*;
+pub use key_sequence::*;
+pub use keybinding_editor::*;
+pub use keymap::*;
 pub use list_item::*;
 pub use menu::*;
+pub use menu_bar::*;
 pub use modal::*;
+pub use modal_stack::*;
 pub use notifications::*;
+pub use palette_history::*;
+pub use palette_provider::*;
 pub use section_header::*;
 pub use tree::*;
+pub use undo_stack::*;
+pub use wizard::*;
@@ -1,6 +1,10 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
 use egui::emath::NumExt as _;
 use egui::{Frame, ModalResponse};
 
+use crate::components::modal_stack::ModalStack;
+
 use crate::extensions::context_ext::ContextExt as _;
 use crate::{DesignTokens, UiExt as _};
 use egui_phosphor::regular::X;
@@ -26,20 +30,30 @@ use egui_phosphor::regular::X;
 /// });
 /// # });
 /// ```
+///
+/// Open modals are tracked on the [`ModalStack`]: a modal opened from within another modal is
+/// stacked on top of it, only the top-most one reacts to `Escape`, and focus is restored when it
+/// closes.
 pub struct ModalHandler {
+    id: egui::Id,
     modal: Option<ModalWrapper>,
     allow_escape: bool,
     should_open: bool,
     should_close: bool,
+    queued: bool,
 }
 
 impl Default for ModalHandler {
     fn default() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
         Self {
+            id: egui::Id::new(("modal_handler", NEXT_ID.fetch_add(1, Ordering::Relaxed))),
             modal: None,
             allow_escape: true,
             should_open: false,
             should_close: false,
+            queued: false,
         }
     }
 }
@@ -56,7 +70,15 @@ impl ModalHandler {
         self.should_open = true;
     }
 
+    /// Open the modal once all open modals are closed and all modals queued before it have been
+    /// shown, e.g. to present several messages one after another.
+    pub fn open_queued(&mut self) {
+        self.queued = true;
+    }
+
     /// Close the modal the next time the [`ModalHandler::ui`] method is called.
+    ///
+    /// Also removes it from the queue.
     pub fn close(&mut self) {
         self.should_close = true;
     }
@@ -68,16 +90,34 @@ impl ModalHandler {
         make_modal: impl FnOnce() -> ModalWrapper,
         content_ui: impl FnOnce(&mut egui::Ui) -> R,
     ) -> Option<R> {
-        if self.modal.is_none() && self.should_open {
-            self.modal = Some(make_modal());
-            self.should_open = false;
+        if self.queued {
+            if self.should_close {
+                ModalStack::cancel_queued(ctx, self.id);
+                self.queued = false;
+                self.should_close = false;
+            } else {
+                ModalStack::enqueue(ctx, self.id);
+            }
+        }
+
+        if self.modal.is_none() {
+            if self.should_open {
+                self.modal = Some(make_modal());
+                self.should_open = false;
+            } else if self.queued && ModalStack::dequeue(ctx, self.id) {
+                self.modal = Some(make_modal());
+                self.queued = false;
+            }
         }
 
         if let Some(modal) = &mut self.modal {
+            ModalStack::show(ctx, self.id);
             let response = modal.ui(ctx, content_ui);
 
             if self.should_close || (self.allow_escape && response.should_close()) {
                 self.modal = None;
+                self.should_close = false;
+                ModalStack::close(ctx, self.id);
             }
 
             Some(response.inner)
@@ -90,6 +130,11 @@ impl ModalHandler {
     pub fn is_open(&self) -> bool {
         self.modal.is_some()
     }
+
+    /// Whether the modal waits in the queue, see [`Self::open_queued`].
+    pub fn is_queued(&self) -> bool {
+        self.queued
+    }
 }
 
 /// Show a modal window with Rerun style using [`egui::Modal`].
@@ -103,6 +148,7 @@ impl ModalHandler {
 /// boilerplate.
 pub struct ModalWrapper {
     title: String,
+    pub(crate) id: egui::Id,
     min_width: Option<f32>,
     max_width: Option<f32>,
     min_height: Option<f32>,
@@ -117,6 +163,7 @@ impl ModalWrapper {
     pub fn new(title: &str) -> Self {
         Self {
             title: title.to_owned(),
+            id: egui::Id::new(title),
             min_width: None,
             max_width: None,
             min_height: None,
@@ -127,6 +174,15 @@ impl ModalWrapper {
         }
     }
 
+    /// Set the id of the modal window. Defaults to an id derived from the title.
+    ///
+    /// Needed when several modals can share the same title.
+    #[inline]
+    pub fn id(mut self, id: egui::Id) -> Self {
+        self.id = id;
+        self
+    }
+
     /// Set the minimum width of the modal window.
     #[inline]
     pub fn min_width(mut self, min_width: f32) -> Self {
@@ -195,7 +251,7 @@ impl ModalWrapper {
         content_ui: impl FnOnce(&mut egui::Ui) -> R,
     ) -> ModalResponse<R> {
         let tokens = ctx.tokens();
-        let id = egui::Id::new(&self.title);
+        let id = self.id;
 
         let mut area = egui::Modal::default_area(id);
         if let Some(default_height) = self.default_height {
@@ -0,0 +1,217 @@
+//! 模态框栈 (Modal stack).
+//!
+//! [`crate::modal::ModalHandler`] 和 [`crate::Dialog`] 打开的模态框都登记在同一个栈中:
+//! - 在模态框中打开的模态框压到栈顶, 只有栈顶的模态框接收输入和 `Escape`
+//! - 焦点被限制在栈顶的模态框内, 关闭时恢复到打开前获得焦点的控件
+//! - 排队的模态框在栈为空时依次打开 (见 [`crate::modal::ModalHandler::open_queued`])
+//!
+//! 栈保存在 egui memory 中. 模态框在显示的每一帧调用 [`ModalStack::show`];
+//! 连续两帧没有显示的模态框会被自动移出栈.
+
+use std::collections::VecDeque;
+
+use egui::{Context, Id};
+
+#[derive(Clone)]
+struct ModalEntry {
+    id: Id,
+
+    /// The widget that had focus before this modal opened.
+    restore_focus: Option<Id>,
+
+    /// The pass in which the modal was last shown.
+    last_pass: u64,
+}
+
+#[derive(Clone, Default)]
+struct ModalStackState {
+    /// Bottom to top.
+    stack: Vec<ModalEntry>,
+    queue: VecDeque<Id>,
+}
+
+/// The stack of open modals, see the [module docs](self).
+pub struct ModalStack;
+
+impl ModalStack {
+    fn with_state<R>(ctx: &Context, f: impl FnOnce(&mut ModalStackState) -> R) -> R {
+        let id = Id::new("modal_stack");
+        let mut state: ModalStackState = ctx.data(|d| d.get_temp(id)).unwrap_or_default();
+
+        // 移除不再显示的模态框 (例如 owner 被 drop)
+        let pass = ctx.cumulative_pass_nr();
+        let mut restore_focus = None;
+        while let Some(index) = state
+            .stack
+            .iter()
+            .position(|entry| entry.last_pass + 1 < pass)
+        {
+            restore_focus = Self::remove_entry(&mut state, index).or(restore_focus);
+        }
+        if let Some(focus) = restore_focus {
+            ctx.memory_mut(|m| m.request_focus(focus));
+        }
+
+        let result = f(&mut state);
+        ctx.data_mut(|d| d.insert_temp(id, state));
+        result
+    }
+
+    /// Removes the entry at `index`, returning the focus to restore if it was the top-most.
+    fn remove_entry(state: &mut ModalStackState, index: usize) -> Option<Id> {
+        let entry = state.stack.remove(index);
+        if let Some(above) = state.stack.get_mut(index) {
+            // The modal above restores to where this one would have.
+            above.restore_focus = entry.restore_focus;
+            None
+        } else {
+            entry.restore_focus
+        }
+    }
+
+    /// Call every frame the modal `id` is shown. Returns `true` if it is the top-most modal.
+    ///
+    /// A modal that isn't on the stack yet is pushed on top, remembering the focused widget.
+    /// Focus outside of the top-most modal is taken away.
+    pub fn show(ctx: &Context, id: Id) -> bool {
+        let pass = ctx.cumulative_pass_nr();
+        let is_top = Self::with_state(ctx, |state| {
+            if let Some(entry) = state.stack.iter_mut().find(|entry| entry.id == id) {
+                entry.last_pass = pass;
+            } else {
+                state.queue.retain(|queued| *queued != id);
+                state.stack.push(ModalEntry {
+                    id,
+                    restore_focus: ctx.memory(|m| m.focused()),
+                    last_pass: pass,
+                });
+            }
+            state.stack.last().is_some_and(|entry| entry.id == id)
+        });
+
+        if is_top {
+            Self::trap_focus(ctx);
+        }
+        is_top
+    }
+
+    /// Remove the modal `id` from the stack, restoring the focus if it was the top-most.
+    pub fn close(ctx: &Context, id: Id) {
+        let restore_focus = Self::with_state(ctx, |state| {
+            state.queue.retain(|queued| *queued != id);
+            let index = state.stack.iter().position(|entry| entry.id == id)?;
+            Self::remove_entry(state, index)
+        });
+        if let Some(focus) = restore_focus {
+            ctx.memory_mut(|m| m.request_focus(focus));
+        }
+    }
+
+    /// Move the open modal `id` to the top of the stack. Does nothing if it isn't open.
+    pub fn raise(ctx: &Context, id: Id) {
+        Self::with_state(ctx, |state| {
+            if let Some(index) = state.stack.iter().position(|entry| entry.id == id) {
+                let entry = state.stack.remove(index);
+                state.stack.push(entry);
+            }
+        });
+    }
+
+    /// Whether `id` is the top-most open modal.
+    pub fn is_top(ctx: &Context, id: Id) -> bool {
+        Self::top(ctx) == Some(id)
+    }
+
+    /// The top-most open modal.
+    pub fn top(ctx: &Context) -> Option<Id> {
+        Self::with_state(ctx, |state| state.stack.last().map(|entry| entry.id))
+    }
+
+    pub fn contains(ctx: &Context, id: Id) -> bool {
+        Self::with_state(ctx, |state| state.stack.iter().any(|entry| entry.id == id))
+    }
+
+    /// Number of open modals.
+    pub fn len(ctx: &Context) -> usize {
+        Self::with_state(ctx, |state| state.stack.len())
+    }
+
+    pub fn is_empty(ctx: &Context) -> bool {
+        Self::len(ctx) == 0
+    }
+
+    /// Queue the modal `id` to open once no modal is open and all modals queued before it have opened.
+    pub fn enqueue(ctx: &Context, id: Id) {
+        Self::with_state(ctx, |state| {
+            if !state.queue.contains(&id) {
+                state.queue.push_back(id);
+            }
+        });
+    }
+
+    pub fn is_queued(ctx: &Context, id: Id) -> bool {
+        Self::with_state(ctx, |state| state.queue.contains(&id))
+    }
+
+    /// Whether it is the queued modal `id`'s turn to open.
+    ///
+    /// If so, it is removed from the queue and the caller should open it right away.
+    pub fn dequeue(ctx: &Context, id: Id) -> bool {
+        Self::with_state(ctx, |state| {
+            let turn = state.stack.is_empty() && state.queue.front() == Some(&id);
+            if turn {
+                state.queue.pop_front();
+            }
+            turn
+        })
+    }
+
+    /// Remove `id` from the queue without opening it.
+    pub fn cancel_queued(ctx: &Context, id: Id) {
+        Self::with_state(ctx, |state| state.queue.retain(|queued| *queued != id));
+    }
+
+    /// Take the focus away from widgets below the top-most modal layer.
+    ///
+    /// egui already restricts `Tab` navigation to the modal layer, but a widget focused
+    /// before the modal opened would otherwise keep receiving keyboard input.
+    fn trap_focus(ctx: &Context) {
+        let Some(focused) = ctx.memory(|m| m.focused()) else {
+            return;
+        };
+        let Some(response) = ctx.read_response(focused) else {
+            return;
+        };
+        if !ctx.memory(|m| m.allows_interaction(response.layer_id)) {
+            ctx.memory_mut(|m| m.surrender_focus(focused));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ModalStack;
+    use egui::Id;
+
+    #[test]
+    fn test_modal_stack() {
+        let ctx = egui::Context::default();
+        let (a, b, c) = (Id::new("a"), Id::new("b"), Id::new("c"));
+
+        assert!(ModalStack::show(&ctx, a));
+        assert!(ModalStack::show(&ctx, b));
+        assert!(!ModalStack::show(&ctx, a));
+        assert_eq!(ModalStack::top(&ctx), Some(b));
+
+        ModalStack::enqueue(&ctx, c);
+        assert!(!ModalStack::dequeue(&ctx, c));
+
+        ModalStack::close(&ctx, b);
+        assert!(ModalStack::is_top(&ctx, a));
+        ModalStack::close(&ctx, a);
+        assert!(ModalStack::is_empty(&ctx));
+
+        assert!(ModalStack::dequeue(&ctx, c));
+        assert!(!ModalStack::is_queued(&ctx, c));
+    }
+}
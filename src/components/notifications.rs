@@ -6,7 +6,39 @@
 //! ## Special cased text
 //! - If a notifications text contains `"\nDetails:"` the section after that
 //!   will be displayed inside a collapsible details header.
-
+//!
+//! ## Log records
+//! [`NotificationUi::add_log`] turns log records into notifications if they pass the
+//! [`LogFilter`]. With the `logger` feature, [`NotificationUi::receive_kit_logs`] forwards
+//! everything logged through [`crate::utils::logger::KitLogger`].
+//!
+//! Repeated notifications (same level and text) are merged into one with a counter badge.
+//!
+//! ## Actions and progress
+//! Notifications can have action buttons (e.g. "Undo"); clicks are reported through
+//! [`NotificationCenter::take_actions`]. A progress notification ([`NotificationCenter::progress`])
+//! stays until it is updated in place to a final success or error.
+//!
+//! Toast timers pause while hovered. At most [`NotificationUi::with_max_toasts`] toasts are shown
+//! at once, the rest are collapsed into "+N more".
+//!
+//! ## Toast placement
+//! [`ToastOptions`] choose the anchor (any corner or edge), the stacking direction, the
+//! animation and the maximum width. Colors and icons come from the [`crate::DesignTokens`].
+//!
+//! ## History
+//! With the `serde` feature, [`NotificationUi::with_persistence`] keeps the notification history
+//! in the persisted egui memory (level, text, details, link and time), limited by
+//! [`NotificationPersistence`]. The panel can filter by level, mark all as read and clear all.
+//!
+//! ## From any thread
+//! [`NotificationCenter`] queues notifications without an [`egui::Context`], e.g. from a
+//! background task. They are picked up by the [`NotificationUi`] (shown as a toast and kept in
+//! the history), or by [`crate::GlobalToast::render`] in apps without one.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
 use std::time::Duration;
 
 use egui::{NumExt as _, Widget as _};
@@ -23,6 +55,7 @@ pub struct LogMsg {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NotificationLevel {
     Tip,
     Info,
@@ -33,14 +66,31 @@ pub enum NotificationLevel {
 
 impl NotificationLevel {
     fn color(&self, ui: &egui::Ui) -> egui::Color32 {
+        let tokens = ui.tokens();
+        match self {
+            Self::Tip | Self::Info => tokens.info_text_color,
+            Self::Warning => tokens.warn_fg_color,
+            Self::Error => tokens.error_fg_color,
+            Self::Success => tokens.success_text_color,
+        }
+    }
+
+    /// Same as [`crate::components::alert::Alert`] of the matching kind.
+    fn alert_visuals<'a>(&self, ui: &'a egui::Ui) -> &'a crate::foundation::AlertVisuals {
+        let tokens = ui.tokens();
         match self {
-            Self::Tip | Self::Info => ui.tokens().info_text_color,
-            Self::Warning => ui.style().visuals.warn_fg_color,
-            Self::Error => ui.style().visuals.error_fg_color,
-            Self::Success => ui.tokens().success_text_color,
+            Self::Tip | Self::Info => &tokens.alert_info,
+            Self::Success => &tokens.alert_success,
+            Self::Warning => &tokens.alert_warning,
+            Self::Error => &tokens.alert_error,
         }
     }
 
+    /// Whether a notification of `level` is shown by the panel filter `self` (tips count as info).
+    fn matches(self, level: Self) -> bool {
+        self == level || (self == Self::Info && level == Self::Tip)
+    }
+
     fn icon_str(&self) -> &'static str {
         match self {
             Self::Tip | Self::Info => INFO,
@@ -61,16 +111,69 @@ impl From<log::Level> for NotificationLevel {
     }
 }
 
-fn is_relevant(target: &str, level: log::Level) -> bool {
-    let is_rerun_crate = target.starts_with("rerun") || target.starts_with("re_");
-    if !is_rerun_crate {
-        return false;
+/// Decides which log records become notifications.
+///
+/// Each rule gives the minimum level for targets starting with a prefix; the longest matching
+/// prefix wins. Targets without a matching rule use the default level.
+///
+/// ```
+/// # use egui_kit::components::notifications::LogFilter;
+/// use log::LevelFilter;
+///
+/// // Warnings and errors from everywhere, but also info from our own crates:
+/// let filter = LogFilter::new(LevelFilter::Warn)
+///     .target("my_app", LevelFilter::Info)
+///     .target("my_app::noisy", LevelFilter::Error);
+///
+/// assert!(filter.is_relevant("my_app::ui", log::Level::Info));
+/// assert!(!filter.is_relevant("my_app::noisy", log::Level::Warn));
+/// assert!(!filter.is_relevant("wgpu_core", log::Level::Info));
+/// ```
+#[derive(Clone, Debug)]
+pub struct LogFilter {
+    default_level: log::LevelFilter,
+    rules: Vec<(String, log::LevelFilter)>,
+}
+
+impl Default for LogFilter {
+    /// Warnings and errors from all targets.
+    fn default() -> Self {
+        Self::new(log::LevelFilter::Warn)
     }
+}
 
-    matches!(
-        level,
-        log::Level::Warn | log::Level::Error | log::Level::Info
-    )
+impl LogFilter {
+    /// Use `default_level` for targets without a matching rule.
+    pub fn new(default_level: log::LevelFilter) -> Self {
+        Self {
+            default_level,
+            rules: Vec::new(),
+        }
+    }
+
+    /// Set the minimum level for targets starting with `prefix`.
+    ///
+    /// Use [`log::LevelFilter::Off`] to ignore a target entirely.
+    #[inline]
+    pub fn target(mut self, prefix: impl Into<String>, level: log::LevelFilter) -> Self {
+        let prefix = prefix.into();
+        self.rules.retain(|(existing, _)| *existing != prefix);
+        self.rules.push((prefix, level));
+        self
+    }
+
+    /// The minimum level for `target`.
+    pub fn level_for(&self, target: &str) -> log::LevelFilter {
+        self.rules
+            .iter()
+            .filter(|(prefix, _)| target.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.default_level, |(_, level)| *level)
+    }
+
+    pub fn is_relevant(&self, target: &str, level: log::Level) -> bool {
+        level <= self.level_for(target)
+    }
 }
 
 fn notification_panel_popup_id() -> egui::Id {
@@ -78,6 +181,8 @@ fn notification_panel_popup_id() -> egui::Id {
 }
 
 /// A link to some URL.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Link {
     pub text: String,
     pub url: String,
@@ -97,8 +202,41 @@ impl egui::Widget for Link {
     }
 }
 
+/// Identifies a [`Notification`], e.g. to update it in place.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NotificationId(u64);
+
+impl NotificationId {
+    fn next() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// A button on a notification, see [`Notification::with_action`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NotificationButton {
+    /// Reported in [`NotificationAction::action`].
+    pub action: String,
+    pub label: String,
+}
+
+/// A notification button was clicked, see [`NotificationCenter::take_actions`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NotificationAction {
+    pub notification: NotificationId,
+    pub action: String,
+}
+
 /// A notification to show the user
+///
+/// With the `serde` feature only the level, text, details, link, time, read state and count
+/// are serialized; a deserialized notification has no toast.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Notification {
+    #[cfg_attr(feature = "serde", serde(skip, default = "NotificationId::next"))]
+    id: NotificationId,
     level: NotificationLevel,
     text: String,
 
@@ -106,31 +244,82 @@ pub struct Notification {
     details: Option<String>,
     link: Option<Link>,
 
+    #[cfg_attr(feature = "serde", serde(skip))]
+    buttons: Vec<NotificationButton>,
+
+    /// If set, shows a progress bar and the toast doesn't time out.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    progress: Option<f32>,
+
     /// If set, the notification will NEVER be shown again
     /// if the user has dismissed it.
+    #[cfg_attr(feature = "serde", serde(skip))]
     permanent_dismiss_id: Option<egui::Id>,
 
     /// When this notification was added to the list.
+    #[cfg_attr(feature = "serde", serde(with = "serde_system_time"))]
     created_at: web_time::SystemTime,
 
     /// Time to live for toasts, the notification itself lives until dismissed.
+    #[cfg_attr(feature = "serde", serde(skip))]
     toast_ttl: Duration,
 
+    /// The initial [`Self::toast_ttl`], for the timer bar.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    toast_duration: Duration,
+
     /// Whether this notification has been read.
     is_unread: bool,
+
+    /// How many times this notification was added, see [`NotificationUi::add`].
+    count: usize,
+}
+
+/// [`web_time::SystemTime`] as seconds since the unix epoch.
+#[cfg(feature = "serde")]
+mod serde_system_time {
+    use std::time::Duration;
+
+    pub fn serialize<S: serde::Serializer>(
+        time: &web_time::SystemTime,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let secs = time
+            .duration_since(web_time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        serde::Serialize::serialize(&secs, serializer)
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<web_time::SystemTime, D::Error> {
+        let secs: f64 = serde::Deserialize::deserialize(deserializer)?;
+        Ok(web_time::UNIX_EPOCH + Duration::try_from_secs_f64(secs).unwrap_or_default())
+    }
 }
 
 impl Notification {
     pub fn new(level: NotificationLevel, text: impl Into<String>) -> Self {
+        Self::new_with_id(NotificationId::next(), level, text)
+    }
+
+    /// Like [`Self::new`] and [`Self::with_id`], without taking a fresh id first.
+    fn new_with_id(id: NotificationId, level: NotificationLevel, text: impl Into<String>) -> Self {
         Self {
+            id,
             level,
             text: text.into(),
             details: None,
             link: None,
+            buttons: Vec::new(),
+            progress: None,
             permanent_dismiss_id: None,
             created_at: web_time::SystemTime::now(),
             toast_ttl: base_ttl(),
+            toast_duration: base_ttl(),
             is_unread: true,
+            count: 1,
         }
     }
 
@@ -144,12 +333,93 @@ impl Notification {
         self
     }
 
+    /// Add a button. Clicking it reports `action` through [`NotificationCenter::take_actions`]
+    /// and hides the toast.
+    pub fn with_action(mut self, action: impl Into<String>, label: impl Into<String>) -> Self {
+        self.buttons.push(NotificationButton {
+            action: action.into(),
+            label: label.into(),
+        });
+        self
+    }
+
+    /// Show a progress bar (0.0 ~ 1.0). The toast stays until the notification is replaced
+    /// without progress, see [`NotificationCenter::progress`].
+    pub fn with_progress(mut self, fraction: f32) -> Self {
+        self.progress = Some(fraction.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Replace the notification with the same id when added, keeping its place in the history.
+    pub fn with_id(mut self, id: NotificationId) -> Self {
+        self.id = id;
+        self
+    }
+
     // Show no toast - only show when clicking the notification panel!
     pub fn no_toast(mut self) -> Self {
         self.toast_ttl = Duration::ZERO;
+        self.toast_duration = Duration::ZERO;
         self
     }
 
+    /// How long the toast is shown (while not hovered).
+    pub fn toast_duration(mut self, duration: Duration) -> Self {
+        self.toast_ttl = duration;
+        self.toast_duration = duration;
+        self
+    }
+
+    pub fn id(&self) -> NotificationId {
+        self.id
+    }
+
+    pub fn progress(&self) -> Option<f32> {
+        self.progress
+    }
+
+    /// A button was clicked: report it and hide the toast. The buttons are removed so the
+    /// action can't be triggered twice.
+    fn trigger(&mut self, action: String) {
+        NotificationCenter::push_action(NotificationAction {
+            notification: self.id,
+            action,
+        });
+        self.buttons.clear();
+        self.toast_ttl = Duration::ZERO;
+    }
+
+    pub fn level(&self) -> NotificationLevel {
+        self.level
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn details(&self) -> Option<&str> {
+        self.details.as_deref()
+    }
+
+    pub fn link(&self) -> Option<&Link> {
+        self.link.as_ref()
+    }
+
+    pub fn created_at(&self) -> web_time::SystemTime {
+        self.created_at
+    }
+
+    /// How long ago the notification was added.
+    fn age(&self) -> Duration {
+        web_time::SystemTime::now()
+            .duration_since(self.created_at)
+            .unwrap_or_default()
+    }
+
+    pub fn is_unread(&self) -> bool {
+        self.is_unread
+    }
+
     /// If set, the notification will NEVER be shown again
     /// if the user has dismissed it.
     pub fn permanent_dismiss_id(mut self, id: egui::Id) -> Self {
@@ -157,6 +427,20 @@ impl Notification {
         self
     }
 
+    /// Same level and text, so it should be shown as a repeat.
+    ///
+    /// Notifications with buttons are never repeats: their actions are reported with their own
+    /// [`NotificationId`].
+    fn is_repeat_of(&self, other: &Self) -> bool {
+        self.level == other.level
+            && self.text == other.text
+            && self.details == other.details
+            && self.progress.is_none()
+            && other.progress.is_none()
+            && self.buttons.is_empty()
+            && other.buttons.is_empty()
+    }
+
     /// Called only when this notification was dismissed on its own.
     fn remember_dismiss(&self, ctx: &egui::Context) {
         if let Some(permanent_dismiss_id) = self.permanent_dismiss_id {
@@ -173,12 +457,168 @@ impl Notification {
     }
 }
 
+#[derive(Default)]
+struct NotificationQueue {
+    pending: Vec<Notification>,
+
+    /// Clicked buttons, see [`NotificationCenter::take_actions`].
+    actions: Vec<NotificationAction>,
+
+    /// Repainted when a notification is queued.
+    ctx: Option<egui::Context>,
+}
+
+fn notification_queue() -> &'static Mutex<NotificationQueue> {
+    static QUEUE: OnceLock<Mutex<NotificationQueue>> = OnceLock::new();
+    QUEUE.get_or_init(Default::default)
+}
+
+/// Global entry point for notifications, usable from any thread without an [`egui::Context`].
+///
+/// ```
+/// # use egui_kit::components::notifications::{Notification, NotificationCenter, NotificationLevel};
+/// NotificationCenter::success("Saved");
+/// NotificationCenter::add(
+///     Notification::new(NotificationLevel::Error, "Upload failed").with_details("timeout"),
+/// );
+/// ```
+///
+/// Queued notifications are taken by the next [`NotificationUi`] that is drawn.
+pub struct NotificationCenter;
+
+impl NotificationCenter {
+    /// Set the context to repaint when a notification is queued.
+    ///
+    /// Called by [`NotificationUi::new`].
+    pub fn init(ctx: &egui::Context) {
+        notification_queue()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .ctx = Some(ctx.clone());
+    }
+
+    /// Queue a notification. If one with the same [`NotificationId`] exists, it is updated in place.
+    pub fn add(notification: Notification) -> NotificationId {
+        let id = notification.id;
+        let ctx = {
+            let mut queue = notification_queue()
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            queue.pending.push(notification);
+            queue.ctx.clone()
+        };
+        if let Some(ctx) = ctx {
+            ctx.request_repaint();
+        }
+        id
+    }
+
+    /// Show a progress toast ("Uploading 42%") that is updated through the returned handle.
+    pub fn progress(text: impl Into<String>) -> ProgressToast {
+        let id = Self::add(Notification::new(NotificationLevel::Info, text).with_progress(0.0));
+        ProgressToast { id }
+    }
+
+    /// The buttons clicked since the last call, oldest first.
+    pub fn take_actions() -> Vec<NotificationAction> {
+        std::mem::take(
+            &mut notification_queue()
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .actions,
+        )
+    }
+
+    fn push_action(action: NotificationAction) {
+        notification_queue()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .actions
+            .push(action);
+    }
+
+    pub fn tip(text: impl Into<String>) {
+        Self::add(Notification::new(NotificationLevel::Tip, text));
+    }
+
+    pub fn info(text: impl Into<String>) {
+        Self::add(Notification::new(NotificationLevel::Info, text));
+    }
+
+    pub fn success(text: impl Into<String>) {
+        Self::add(Notification::new(NotificationLevel::Success, text));
+    }
+
+    pub fn warning(text: impl Into<String>) {
+        Self::add(Notification::new(NotificationLevel::Warning, text));
+    }
+
+    pub fn error(text: impl Into<String>) {
+        Self::add(Notification::new(NotificationLevel::Error, text));
+    }
+
+    fn take_pending() -> Vec<Notification> {
+        std::mem::take(
+            &mut notification_queue()
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .pending,
+        )
+    }
+}
+
+/// Updates a progress notification in place, see [`NotificationCenter::progress`].
+///
+/// Can be sent to any thread.
+#[derive(Clone, Copy, Debug)]
+pub struct ProgressToast {
+    id: NotificationId,
+}
+
+impl ProgressToast {
+    pub fn id(&self) -> NotificationId {
+        self.id
+    }
+
+    /// Set the progress (0.0 ~ 1.0) and text, e.g. "Uploading 42%".
+    pub fn update(&self, fraction: f32, text: impl Into<String>) {
+        NotificationCenter::add(
+            Notification::new_with_id(self.id, NotificationLevel::Info, text)
+                .with_progress(fraction),
+        );
+    }
+
+    pub fn success(self, text: impl Into<String>) {
+        NotificationCenter::add(Notification::new_with_id(
+            self.id,
+            NotificationLevel::Success,
+            text,
+        ));
+    }
+
+    pub fn error(self, text: impl Into<String>) {
+        NotificationCenter::add(Notification::new_with_id(
+            self.id,
+            NotificationLevel::Error,
+            text,
+        ));
+    }
+
+    /// Replace the progress notification with `notification`, whose toast then times out as usual.
+    pub fn finish(self, notification: Notification) {
+        NotificationCenter::add(notification.with_id(self.id));
+    }
+}
+
 #[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
 struct PermaDismissiedMarker;
 
 enum NotificationReaction {
     Dismissed,
     NeverShowAgain,
+
+    /// A button was clicked.
+    Action(String),
 }
 
 pub struct NotificationUi {
@@ -194,16 +634,224 @@ pub struct NotificationUi {
 
     /// Toasts that show up for a short time.
     toasts: Toasts,
+
+    /// Which log records [`Self::add_log`] accepts.
+    log_filter: LogFilter,
+
+    /// See [`Self::receive_kit_logs`].
+    #[cfg(feature = "logger")]
+    kit_log_receiver: Option<crossbeam_channel::Receiver<crate::utils::logger::LogEntry>>,
+
+    /// See [`Self::with_persistence`].
+    #[cfg(feature = "serde")]
+    persistence: Option<NotificationPersistence>,
+
+    /// The history changed since it was last saved.
+    history_changed: bool,
+
+    /// Only show notifications of this level in the panel.
+    level_filter: Option<NotificationLevel>,
+}
+
+/// Limits of the persisted notification history, see [`NotificationUi::with_persistence`].
+#[cfg(feature = "serde")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct NotificationPersistence {
+    /// Keep at most this many notifications, the oldest are dropped first.
+    pub max_count: usize,
+
+    /// Drop notifications older than this.
+    pub max_age: Option<Duration>,
+}
+
+#[cfg(feature = "serde")]
+impl Default for NotificationPersistence {
+    fn default() -> Self {
+        Self {
+            max_count: 100,
+            max_age: Some(Duration::from_secs(7 * 24 * 60 * 60)),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl NotificationPersistence {
+    #[inline]
+    pub fn max_count(mut self, max_count: usize) -> Self {
+        self.max_count = max_count;
+        self
+    }
+
+    #[inline]
+    pub fn max_age(mut self, max_age: Option<Duration>) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Drop the notifications that are too old or too many.
+    fn prune(&self, notifications: &mut Vec<Notification>) {
+        if let Some(max_age) = self.max_age {
+            notifications.retain(|n| n.age() <= max_age);
+        }
+        let excess = notifications.len().saturating_sub(self.max_count);
+        notifications.drain(..excess);
+    }
+}
+
+#[cfg(feature = "serde")]
+fn notification_history_id() -> egui::Id {
+    egui::Id::new("notification_history")
+}
+
+/// Number of [`NotificationUi`]s alive, see [`NotificationUi::live_count`].
+static LIVE_NOTIFICATION_UIS: AtomicUsize = AtomicUsize::new(0);
+
+impl Drop for NotificationUi {
+    fn drop(&mut self) {
+        LIVE_NOTIFICATION_UIS.fetch_sub(1, Ordering::Relaxed);
+    }
 }
 
 impl NotificationUi {
     pub fn new(ctx: egui::Context) -> Self {
+        NotificationCenter::init(&ctx);
+        LIVE_NOTIFICATION_UIS.fetch_add(1, Ordering::Relaxed);
         Self {
             ctx,
             notifications: Vec::new(),
             unread_notification_level: None,
             was_open_last_frame: false,
             toasts: Toasts::new(),
+            log_filter: LogFilter::default(),
+            #[cfg(feature = "logger")]
+            kit_log_receiver: None,
+            #[cfg(feature = "serde")]
+            persistence: None,
+            history_changed: false,
+            level_filter: None,
+        }
+    }
+
+    /// Keep the notification history in the persisted egui memory, and restore it now.
+    ///
+    /// Only the level, text, details, link, time and read state are kept. Restored
+    /// notifications are not shown as toasts, and progress notifications are not saved.
+    #[cfg(feature = "serde")]
+    pub fn with_persistence(mut self, persistence: NotificationPersistence) -> Self {
+        let mut history: Vec<Notification> = self
+            .ctx
+            .data_mut(|d| d.get_persisted(notification_history_id()))
+            .unwrap_or_default();
+        for notification in &mut history {
+            notification.toast_ttl = Duration::ZERO;
+        }
+        // 恢复的通知排在本次运行的通知之前
+        history.append(&mut self.notifications);
+        persistence.prune(&mut history);
+        self.notifications = history;
+        self.unread_notification_level = self
+            .notifications
+            .iter()
+            .filter(|n| n.is_unread)
+            .map(|n| n.level)
+            .max();
+
+        self.persistence = Some(persistence);
+        self.history_changed = true;
+        self
+    }
+
+    /// Save the history if it changed, see [`Self::with_persistence`].
+    fn save_history(&mut self) {
+        if !std::mem::take(&mut self.history_changed) {
+            return;
+        }
+
+        #[cfg(feature = "serde")]
+        if let Some(persistence) = &self.persistence {
+            persistence.prune(&mut self.notifications);
+            let history: Vec<Notification> = self
+                .notifications
+                .iter()
+                .filter(|n| n.progress.is_none())
+                .cloned()
+                .collect();
+            self.ctx
+                .data_mut(|d| d.insert_persisted(notification_history_id(), history));
+        }
+    }
+
+    /// Show at most this many toasts at once (default 5); the rest are collapsed into "+N more".
+    #[inline]
+    pub fn with_max_toasts(mut self, max_toasts: usize) -> Self {
+        self.toasts.options.max_visible = max_toasts.max(1);
+        self
+    }
+
+    /// Where and how toasts are shown.
+    #[inline]
+    pub fn with_toast_options(mut self, options: ToastOptions) -> Self {
+        self.toasts.options = options;
+        self
+    }
+
+    pub fn set_toast_options(&mut self, options: ToastOptions) {
+        self.toasts.options = options;
+    }
+
+    pub fn toast_options(&self) -> &ToastOptions {
+        &self.toasts.options
+    }
+
+    /// Which log records [`Self::add_log`] turns into notifications.
+    #[inline]
+    pub fn with_log_filter(mut self, log_filter: LogFilter) -> Self {
+        self.log_filter = log_filter;
+        self
+    }
+
+    pub fn set_log_filter(&mut self, log_filter: LogFilter) {
+        self.log_filter = log_filter;
+    }
+
+    pub fn log_filter(&self) -> &LogFilter {
+        &self.log_filter
+    }
+
+    /// Forward everything logged through [`crate::utils::logger::KitLogger`] to [`Self::add_log`].
+    ///
+    /// The records are picked up whenever the notification UI is drawn, independently of
+    /// [`crate::utils::logger::LogPanel`].
+    #[cfg(feature = "logger")]
+    pub fn receive_kit_logs(&mut self) {
+        if self.kit_log_receiver.is_none() {
+            self.kit_log_receiver = Some(crate::utils::logger::subscribe_logs());
+        }
+    }
+
+    /// How many notification UIs exist. They all take from the same [`NotificationCenter`] queue.
+    pub(crate) fn live_count() -> usize {
+        LIVE_NOTIFICATION_UIS.load(Ordering::Relaxed)
+    }
+
+    /// Take everything queued through [`NotificationCenter`] and the logger.
+    fn receive_pending(&mut self) {
+        for notification in NotificationCenter::take_pending() {
+            self.add(notification);
+        }
+        self.drain_kit_logs();
+    }
+
+    fn drain_kit_logs(&mut self) {
+        #[cfg(feature = "logger")]
+        {
+            let Some(receiver) = &self.kit_log_receiver else {
+                return;
+            };
+            let entries: Vec<_> = receiver.try_iter().collect();
+            for entry in entries {
+                self.add_log(entry.into());
+            }
         }
     }
 
@@ -211,6 +859,30 @@ impl NotificationUi {
         self.unread_notification_level
     }
 
+    /// All notifications, oldest first.
+    pub fn notifications(&self) -> &[Notification] {
+        &self.notifications
+    }
+
+    pub fn unread_count(&self) -> usize {
+        self.notifications.iter().filter(|n| n.is_unread).count()
+    }
+
+    pub fn mark_all_read(&mut self) {
+        self.unread_notification_level = None;
+        for notification in &mut self.notifications {
+            notification.is_unread = false;
+        }
+        self.history_changed = true;
+    }
+
+    /// Remove all notifications.
+    pub fn clear(&mut self) {
+        self.notifications.clear();
+        self.unread_notification_level = None;
+        self.history_changed = true;
+    }
+
     /// Given that the log is relevant this creates a notification
     /// based on that log.
     ///
@@ -220,7 +892,7 @@ impl NotificationUi {
     pub fn add_log(&mut self, message: LogMsg) {
         let LogMsg { level, target, msg } = message;
 
-        if is_relevant(&target, level) {
+        if self.log_filter.is_relevant(&target, level) {
             let (split_msg, msg_details) = msg.split_once("\nDetails:").unzip();
 
             let msg = split_msg.unwrap_or(&msg);
@@ -239,19 +911,66 @@ impl NotificationUi {
         self.add(Notification::new(NotificationLevel::Success, text.into()));
     }
 
+    /// Add a notification.
+    ///
+    /// A notification with the same [`NotificationId`] as an existing one replaces it in place.
+    /// If an equal notification (same level and text, no buttons) already exists, it is moved to the
+    /// top with an increased counter instead, and its toast is shown again.
     pub fn add(&mut self, notification: Notification) {
         if notification.is_perma_dismissed(&self.ctx) {
             return;
         }
 
+        self.history_changed = true;
+
+        // 原位更新 (如进度通知)
+        if let Some(existing) = self
+            .notifications
+            .iter_mut()
+            .find(|existing| existing.id == notification.id)
+        {
+            let created_at = existing.created_at;
+            let is_unread = existing.is_unread;
+            // 进度更新不重新显示已关闭的 toast, 也不算新的未读通知
+            let is_progress_update = notification.progress.is_some();
+            let toast_closed = existing.toast_ttl.is_zero() && is_progress_update;
+            *existing = notification;
+            existing.created_at = created_at;
+            if toast_closed {
+                existing.toast_ttl = Duration::ZERO;
+            }
+            if is_progress_update {
+                existing.is_unread = is_unread;
+            } else if Some(existing.level) > self.unread_notification_level {
+                self.unread_notification_level = Some(existing.level);
+            }
+            return;
+        }
+
         if Some(notification.level) > self.unread_notification_level {
             self.unread_notification_level = Some(notification.level);
         }
-        self.notifications.push(notification);
+
+        if let Some(index) = self
+            .notifications
+            .iter()
+            .position(|existing| existing.is_repeat_of(&notification))
+        {
+            let mut existing = self.notifications.remove(index);
+            existing.count += 1;
+            existing.created_at = notification.created_at;
+            existing.toast_ttl = notification.toast_ttl;
+            existing.is_unread = true;
+            self.notifications.push(existing);
+        } else {
+            self.notifications.push(notification);
+        }
     }
 
     /// A little bell-like button, that shows recent notifications when clicked.
     pub fn notification_toggle_button(&mut self, ui: &mut egui::Ui) {
+        self.receive_pending();
+
         let popup_id = notification_panel_popup_id();
 
         let is_panel_visible = egui::Popup::is_id_open(ui.ctx(), popup_id);
@@ -297,47 +1016,28 @@ impl NotificationUi {
 
         if !is_panel_visible && self.was_open_last_frame {
             // Mark all as read after closing panel
-            for notification in &mut self.notifications {
-                notification.is_unread = false;
-            }
+            self.mark_all_read();
         }
 
         self.was_open_last_frame = is_panel_visible;
+        self.save_history();
     }
 
     fn popup_contents(&mut self, ui: &mut egui::Ui) {
-        let notifications = &mut self.notifications;
-
         let panel_width = 356.0;
         let panel_max_height = (ui.ctx().content_rect().height() - 100.0)
             .at_least(0.0)
             .at_most(640.0);
 
-        let mut to_dismiss = None;
-
-        let notification_list = |ui: &mut egui::Ui| {
-            if notifications.is_empty() {
-                ui.label(egui::RichText::new("No notifications yet.").weak());
-
-                return;
-            }
-
-            for (i, notification) in notifications.iter().enumerate().rev() {
-                let reaction = show_notification(ui, notification, DisplayMode::Panel).0;
-                if reaction.is_some() {
-                    to_dismiss = Some(i);
-                }
-            }
-        };
-
-        let mut dismiss_all = false;
-
         ui.set_width(panel_width);
         ui.set_max_height(panel_max_height);
 
+        let mut clear_all = false;
+        let mut mark_all_read = false;
+
         ui.horizontal_top(|ui| {
-            if !notifications.is_empty() {
-                ui.strong(format!("Notifications ({})", notifications.len()));
+            if !self.notifications.is_empty() {
+                ui.strong(format!("Notifications ({})", self.notifications.len()));
             } else {
                 ui.strong("Notifications");
             }
@@ -347,30 +1047,105 @@ impl NotificationUi {
                 }
             });
         });
+
+        if !self.notifications.is_empty() {
+            ui.horizontal(|ui| {
+                self.level_filter_ui(ui);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    clear_all = ui.small_button("Clear all").clicked();
+                    mark_all_read = ui
+                        .add_enabled(
+                            self.unread_count() > 0,
+                            egui::Button::new("Mark all read").small(),
+                        )
+                        .clicked();
+                });
+            });
+        }
+
+        let level_filter = self.level_filter;
+        let notifications = &mut self.notifications;
+        let mut to_dismiss = None;
+
+        let notification_list = |ui: &mut egui::Ui| {
+            if notifications.is_empty() {
+                ui.label(egui::RichText::new("No notifications yet.").weak());
+
+                return;
+            }
+
+            let mut any_shown = false;
+            for (i, notification) in notifications.iter_mut().enumerate().rev() {
+                if level_filter.is_some_and(|level| !level.matches(notification.level)) {
+                    continue;
+                }
+                any_shown = true;
+                match show_notification(ui, notification, DisplayMode::Panel, 340.0).0 {
+                    Some(NotificationReaction::Action(action)) => {
+                        notification.trigger(action);
+                    }
+                    Some(_) => to_dismiss = Some(i),
+                    None => {}
+                }
+            }
+            if !any_shown {
+                ui.label(egui::RichText::new("No matching notifications.").weak());
+            }
+        };
         egui::ScrollArea::vertical()
             .min_scrolled_height(panel_max_height / 2.0)
             .max_height(panel_max_height)
             .show(ui, notification_list);
 
-        if !notifications.is_empty() {
-            ui.horizontal_top(|ui| {
-                if ui.button("Dismiss all").clicked() {
-                    dismiss_all = true;
-                }
-            });
-        }
-
-        if dismiss_all {
-            notifications.clear();
+        if clear_all {
+            self.clear();
         } else if let Some(to_dismiss) = to_dismiss {
-            let removed = notifications.remove(to_dismiss);
+            let removed = self.notifications.remove(to_dismiss);
             removed.remember_dismiss(ui.ctx());
+            self.history_changed = true;
+        }
+        if mark_all_read {
+            self.mark_all_read();
+        }
+    }
+
+    /// "All | Info 3 | Warning 1 | …" (only levels that have notifications).
+    fn level_filter_ui(&mut self, ui: &mut egui::Ui) {
+        ui.spacing_mut().item_spacing.x = 2.0;
+        ui.selectable_value(&mut self.level_filter, None, "All");
+        for level in [
+            NotificationLevel::Info,
+            NotificationLevel::Success,
+            NotificationLevel::Warning,
+            NotificationLevel::Error,
+        ] {
+            let count = self
+                .notifications
+                .iter()
+                .filter(|n| level.matches(n.level))
+                .count();
+            if count == 0 && self.level_filter != Some(level) {
+                continue;
+            }
+            let text =
+                egui::RichText::new(format!("{} {count}", level.icon_str())).color(level.color(ui));
+            ui.selectable_value(&mut self.level_filter, Some(level), text)
+                .on_hover_text(format!("Only show {level:?}"));
         }
     }
 
     /// Show floating toast notifications of recent log messages.
     pub fn show_toasts(&mut self, egui_ctx: &egui::Context) {
+        self.receive_pending();
         self.toasts.show(egui_ctx, &mut self.notifications[..]);
+        self.save_history();
+    }
+
+    /// Drop the notifications whose toast is gone.
+    ///
+    /// For a UI that only shows toasts and no panel, see [`crate::GlobalToast::render`].
+    pub(crate) fn remove_expired_toasts(&mut self) {
+        self.notifications.retain(|n| !n.toast_ttl.is_zero());
     }
 }
 
@@ -378,8 +1153,167 @@ fn base_ttl() -> Duration {
     Duration::from_secs(4)
 }
 
+/// How toasts move in and out, see [`ToastOptions::animation`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ToastAnimation {
+    /// Toasts appear and disappear at once.
+    None,
+
+    /// Fade in and out.
+    Fade,
+
+    /// Slide in from the anchored edge while fading in, and glide into place when the stack changes.
+    #[default]
+    Slide,
+}
+
+/// In which direction toasts stack, see [`ToastOptions::direction`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ToastDirection {
+    /// Away from the anchored edge: down for top and center anchors, up for bottom anchors.
+    #[default]
+    Auto,
+    Down,
+    Up,
+}
+
+/// Where and how toasts are shown, see [`NotificationUi::with_toast_options`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ToastOptions {
+    /// Corner or edge of the screen the toasts are anchored to.
+    pub anchor: egui::Align2,
+
+    /// Distance from the anchored edges.
+    pub margin: egui::Vec2,
+
+    pub direction: ToastDirection,
+    pub animation: ToastAnimation,
+
+    /// Toasts are this wide, longer text wraps.
+    pub max_width: f32,
+
+    /// Gap between toasts.
+    pub spacing: f32,
+
+    /// At most this many toasts are shown, the older ones are collapsed into "+N more".
+    pub max_visible: usize,
+}
+
+impl Default for ToastOptions {
+    fn default() -> Self {
+        Self {
+            anchor: egui::Align2::RIGHT_TOP,
+            margin: egui::vec2(8.0, 32.0),
+            direction: ToastDirection::Auto,
+            animation: ToastAnimation::default(),
+            max_width: 340.0,
+            spacing: 8.0,
+            max_visible: 5,
+        }
+    }
+}
+
+impl ToastOptions {
+    #[inline]
+    pub fn anchor(mut self, anchor: egui::Align2) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    #[inline]
+    pub fn margin(mut self, margin: egui::Vec2) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    #[inline]
+    pub fn direction(mut self, direction: ToastDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    #[inline]
+    pub fn animation(mut self, animation: ToastAnimation) -> Self {
+        self.animation = animation;
+        self
+    }
+
+    #[inline]
+    pub fn max_width(mut self, max_width: f32) -> Self {
+        self.max_width = max_width;
+        self
+    }
+
+    #[inline]
+    pub fn spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    #[inline]
+    pub fn max_visible(mut self, max_visible: usize) -> Self {
+        self.max_visible = max_visible.max(1);
+        self
+    }
+
+    fn stacks_down(&self) -> bool {
+        match self.direction {
+            ToastDirection::Auto => self.anchor.y() != egui::Align::Max,
+            ToastDirection::Down => true,
+            ToastDirection::Up => false,
+        }
+    }
+
+    /// Where the first toast goes.
+    fn start_pos(&self, content_rect: egui::Rect) -> egui::Pos2 {
+        let rect = content_rect.shrink2(self.margin);
+        let x = match self.anchor.x() {
+            egui::Align::Min => rect.left(),
+            egui::Align::Center => rect.center().x,
+            egui::Align::Max => rect.right(),
+        };
+        let y = match self.anchor.y() {
+            egui::Align::Min => rect.top(),
+            egui::Align::Center => rect.center().y,
+            egui::Align::Max => rect.bottom(),
+        };
+        egui::pos2(x, y)
+    }
+
+    /// The toast pivot: the anchored side horizontally, the side facing the previous toast vertically.
+    fn pivot(&self) -> egui::Align2 {
+        let y = if self.stacks_down() {
+            egui::Align::Min
+        } else {
+            egui::Align::Max
+        };
+        egui::Align2([self.anchor.x(), y])
+    }
+
+    /// Offset of a toast that has only `1.0 - t` of its slide-in left.
+    fn slide_offset(&self, t: f32) -> egui::Vec2 {
+        let distance = 40.0 * (1.0 - t);
+        match self.anchor.x() {
+            egui::Align::Min => egui::vec2(-distance, 0.0),
+            egui::Align::Max => egui::vec2(distance, 0.0),
+            egui::Align::Center if self.stacks_down() => egui::vec2(0.0, -distance),
+            egui::Align::Center => egui::vec2(0.0, distance),
+        }
+    }
+}
+
+/// Duration of the in and out animations.
+const TOAST_ANIMATION_TIME: f32 = 0.25;
+
 struct Toasts {
     id: egui::Id,
+    options: ToastOptions,
+
+    /// "+N more" was clicked: show all toasts.
+    expanded: bool,
+
+    /// When each shown toast first appeared, for the in-animation.
+    appeared: HashMap<NotificationId, f64>,
 }
 
 impl Default for Toasts {
@@ -392,44 +1326,103 @@ impl Toasts {
     fn new() -> Self {
         Self {
             id: egui::Id::new("__toasts"),
+            options: ToastOptions::default(),
+            expanded: false,
+            appeared: HashMap::new(),
         }
     }
 
     /// Shows and updates all toasts
-    fn show(&self, egui_ctx: &egui::Context, notifications: &mut [Notification]) {
-        let dt = Duration::try_from_secs_f32(egui_ctx.input(|i| i.unstable_dt))
-            .unwrap_or(std::time::Duration::from_millis(100));
+    fn show(&mut self, egui_ctx: &egui::Context, notifications: &mut [Notification]) {
+        let (dt, now) = egui_ctx.input(|i| (i.unstable_dt, i.time));
+        let dt = Duration::try_from_secs_f32(dt).unwrap_or(std::time::Duration::from_millis(100));
 
-        let mut offset = egui::vec2(-8.0, 32.0);
+        let options = self.options.clone();
+        let animate = options.animation != ToastAnimation::None;
+        let down = options.stacks_down();
+        let mut pos = options.start_pos(egui_ctx.content_rect());
 
         let mut first_nonzero_ttl = None;
+        let mut is_animating = false;
+
+        let toast_count = notifications
+            .iter()
+            .filter(|n| n.toast_ttl > Duration::ZERO)
+            .count();
+        let hidden = if self.expanded {
+            0
+        } else {
+            toast_count.saturating_sub(options.max_visible)
+        };
+        if toast_count <= options.max_visible {
+            self.expanded = false;
+        }
 
-        for (i, notification) in notifications
+        let mut appeared = HashMap::new();
+        for notification in notifications
             .iter_mut()
-            .enumerate()
-            .filter(|(_, n)| n.toast_ttl > Duration::ZERO)
+            .filter(|n| n.toast_ttl > Duration::ZERO)
+            .skip(hidden)
         {
-            first_nonzero_ttl.get_or_insert(notification.toast_ttl);
+            let area_id = self.id.with(notification.id);
+
+            // 0 → 1 when appearing, 1 → 0 in the last moments of the ttl
+            let shown_at = *self.appeared.get(&notification.id).unwrap_or(&now);
+            appeared.insert(notification.id, shown_at);
+            let t_in = ((now - shown_at) as f32 / TOAST_ANIMATION_TIME).clamp(0.0, 1.0);
+            let t_out = if notification.progress.is_some() {
+                1.0
+            } else {
+                (notification.toast_ttl.as_secs_f32() / TOAST_ANIMATION_TIME).clamp(0.0, 1.0)
+            };
+            let t = if animate { t_in.min(t_out) } else { 1.0 };
+            is_animating |= animate && t_in < 1.0;
+
+            let mut toast_pos = pos;
+            if animate {
+                // 其它 toast 消失时平滑移动到新位置
+                toast_pos.y = egui_ctx.animate_value_with_time(
+                    area_id.with("y"),
+                    pos.y,
+                    TOAST_ANIMATION_TIME,
+                );
+                is_animating |= toast_pos.y != pos.y;
+            }
+            if options.animation == ToastAnimation::Slide {
+                toast_pos += options.slide_offset(t_in);
+            }
 
-            let response = egui::Area::new(self.id.with(i))
-                .anchor(egui::Align2::RIGHT_TOP, offset)
+            let mut reaction = None;
+            let response = egui::Area::new(area_id)
+                .pivot(options.pivot())
+                .fixed_pos(toast_pos)
                 .order(egui::Order::Foreground)
                 .interactable(true)
-                .movable(false)
+                .fade_in(false)
                 .show(egui_ctx, |ui| {
-                    show_notification(ui, notification, DisplayMode::Toast);
+                    ui.multiply_opacity(t);
+                    let (toast_reaction, frame) =
+                        show_notification(ui, notification, DisplayMode::Toast, options.max_width);
+                    reaction = toast_reaction;
+                    if notification.progress.is_none() {
+                        toast_timer_bar(ui, frame.rect, notification);
+                    }
                 })
                 .response;
 
-            if !response.hovered()
-                && !egui_ctx.rect_contains_pointer(response.layer_id, response.interact_rect)
-            {
+            // 鼠标悬停时暂停计时, 进度通知不会超时
+            let paused = response.hovered()
+                || egui_ctx.rect_contains_pointer(response.layer_id, response.interact_rect);
+            if notification.progress.is_none() && !paused {
                 notification.toast_ttl = notification.toast_ttl.saturating_sub(dt);
+                first_nonzero_ttl.get_or_insert(notification.toast_ttl);
             }
 
             let response = response.on_hover_text("Click to close and copy contents");
 
-            if response.clicked() {
+            if let Some(NotificationReaction::Action(action)) = reaction {
+                notification.trigger(action);
+            } else if response.clicked() {
                 if let Some(link) = &notification.link {
                     egui_ctx.open_url(egui::OpenUrl::new_tab(link.url.clone()));
                 } else {
@@ -438,25 +1431,78 @@ impl Toasts {
                 notification.toast_ttl = Duration::ZERO;
             }
 
-            offset.y += response.rect.height() + 8.0;
+            let step = response.rect.height() + options.spacing;
+            pos.y += if down { step } else { -step };
+        }
+        self.appeared = appeared;
+
+        if toast_count > options.max_visible {
+            let text = if self.expanded {
+                "Show less".to_owned()
+            } else {
+                format!("+{hidden} more")
+            };
+            let response = egui::Area::new(self.id.with("more"))
+                .pivot(options.pivot())
+                .fixed_pos(pos)
+                .order(egui::Order::Foreground)
+                .show(egui_ctx, |ui| {
+                    egui::Frame::window(ui.style())
+                        .corner_radius(4)
+                        .inner_margin(egui::Margin::symmetric(10, 4))
+                        .fill(ui.tokens().notification_background_color)
+                        .shadow(egui::Shadow::NONE)
+                        .show(ui, |ui| ui.label(text))
+                        .response
+                        .interact(egui::Sense::click())
+                })
+                .inner;
+            if response
+                .on_hover_cursor(egui::CursorIcon::PointingHand)
+                .clicked()
+            {
+                self.expanded = !self.expanded;
+            }
         }
 
-        if let Some(first_nonzero_ttl) = first_nonzero_ttl {
-            egui_ctx.request_repaint_after(first_nonzero_ttl);
+        if is_animating {
+            egui_ctx.request_repaint();
+        } else if let Some(first_nonzero_ttl) = first_nonzero_ttl {
+            // 重绘以更新计时条
+            egui_ctx.request_repaint_after(first_nonzero_ttl.min(Duration::from_millis(100)));
         }
     }
 }
 
+/// A thin bar along the bottom of a toast, showing the time until it closes.
+fn toast_timer_bar(ui: &egui::Ui, rect: egui::Rect, notification: &Notification) {
+    if notification.toast_duration.is_zero() {
+        return;
+    }
+    let fraction = (notification.toast_ttl.as_secs_f32()
+        / notification.toast_duration.as_secs_f32())
+    .clamp(0.0, 1.0);
+    let rect = rect.shrink2(egui::vec2(4.0, 1.0));
+    let bar = egui::Rect::from_min_max(
+        egui::pos2(rect.left(), rect.bottom() - 2.0),
+        egui::pos2(rect.left() + rect.width() * fraction, rect.bottom()),
+    );
+    let color = notification.level.color(ui).gamma_multiply(0.6);
+    ui.painter().rect_filled(bar, 1.0, color);
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum DisplayMode {
     Panel,
     Toast,
 }
 
+/// `width` is the width of the whole notification, longer text wraps.
 fn show_notification(
     ui: &mut egui::Ui,
     notification: &Notification,
     mode: DisplayMode,
+    width: f32,
 ) -> (Option<NotificationReaction>, egui::Response) {
     let Notification {
         level,
@@ -466,7 +1512,12 @@ fn show_notification(
         permanent_dismiss_id,
         created_at,
         toast_ttl: _,
+        toast_duration: _,
         is_unread,
+        count,
+        id: _,
+        buttons,
+        progress,
     } = notification;
 
     let background_color = if mode == DisplayMode::Toast || *is_unread {
@@ -477,7 +1528,12 @@ fn show_notification(
 
     let mut reaction = None;
 
-    let response = egui::Frame::window(ui.style())
+    let mut frame = egui::Frame::window(ui.style());
+    if mode == DisplayMode::Toast {
+        frame = frame.stroke(egui::Stroke::new(1.0, level.alert_visuals(ui).stroke));
+    }
+
+    let response = frame
         .corner_radius(4)
         .inner_margin(10.0)
         .fill(background_color)
@@ -489,22 +1545,34 @@ fn show_notification(
 
                     ui.vertical(|ui| {
                         ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Wrap);
-                        ui.set_width(270.0);
+                        // icon, margins and badge
+                        ui.set_width((width - 70.0).at_least(100.0));
                         ui.label(text);
 
+                        if let Some(progress) = progress {
+                            ui.add(
+                                egui::ProgressBar::new(*progress)
+                                    .desired_height(6.0)
+                                    .corner_radius(3),
+                            );
+                        }
+
                         if let Some(details) = details {
                             ui.collapsing_header("Details", false, |ui| ui.label(details));
                         }
                     });
 
                     ui.add_space(4.0);
+                    if *count > 1 {
+                        count_badge(ui, *count, level.color(ui));
+                    }
                     if mode == DisplayMode::Panel {
                         notification_age_label(ui, *created_at);
                     }
                 });
 
                 let show_dismiss = mode == DisplayMode::Panel;
-                let show_bottom_bar = show_dismiss || link.is_some();
+                let show_bottom_bar = show_dismiss || link.is_some() || !buttons.is_empty();
 
                 if show_bottom_bar {
                     egui::Sides::new().show(
@@ -515,6 +1583,13 @@ fn show_notification(
                             }
                         },
                         |ui| {
+                            // right-to-left: 按钮倒序添加
+                            for button in buttons.iter().rev() {
+                                if ui.button(&button.label).clicked() {
+                                    reaction =
+                                        Some(NotificationReaction::Action(button.action.clone()));
+                                }
+                            }
                             if show_dismiss {
                                 if permanent_dismiss_id.is_some() {
                                     if ui.button("Don't show again").clicked() {
@@ -537,6 +1612,23 @@ fn show_notification(
     (reaction, response)
 }
 
+/// "×3" for a notification that was added three times.
+fn count_badge(ui: &mut egui::Ui, count: usize, color: egui::Color32) {
+    egui::Frame::new()
+        .fill(color.gamma_multiply(0.2))
+        .corner_radius(8)
+        .inner_margin(egui::Margin::symmetric(6, 1))
+        .show(ui, |ui| {
+            ui.label(
+                egui::RichText::new(format!("×{count}"))
+                    .small()
+                    .color(color),
+            );
+        })
+        .response
+        .on_hover_text(format!("Repeated {count} times"));
+}
+
 fn notification_age_label(ui: &mut egui::Ui, created_at: web_time::SystemTime) {
     // TODO(emilk): use short_duration_ui
 
@@ -570,3 +1662,223 @@ fn notification_age_label(ui: &mut egui::Ui, created_at: web_time::SystemTime) {
         });
     });
 }
+
+#[cfg(feature = "logger")]
+impl From<crate::utils::logger::LogEntry> for LogMsg {
+    fn from(entry: crate::utils::logger::LogEntry) -> Self {
+        Self {
+            level: entry.level,
+            target: entry.target,
+            msg: entry.message,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Mutex, MutexGuard};
+
+    use super::{
+        LogMsg, Notification, NotificationAction, NotificationCenter, NotificationLevel,
+        NotificationUi, ToastDirection, ToastOptions,
+    };
+
+    /// Tests that use the global [`NotificationCenter`] queue hold this, so they don't take
+    /// each other's notifications.
+    fn lock_queue() -> MutexGuard<'static, ()> {
+        static QUEUE: Mutex<()> = Mutex::new(());
+        QUEUE.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    #[test]
+    fn test_add_log() {
+        let mut notifications = NotificationUi::new(egui::Context::default())
+            .with_log_filter(super::LogFilter::default().target("my_app", log::LevelFilter::Info));
+
+        let log = |level, target: &str, msg: &str| LogMsg {
+            level,
+            target: target.to_owned(),
+            msg: msg.to_owned(),
+        };
+        notifications.add_log(log(log::Level::Info, "wgpu_core", "adapter"));
+        notifications.add_log(log(log::Level::Info, "my_app::io", "Saved"));
+        notifications.add_log(log(log::Level::Warn, "my_app::io", "Disk almost full"));
+        notifications.add_log(log(log::Level::Info, "my_app::io", "Saved"));
+
+        let texts: Vec<_> = notifications
+            .notifications
+            .iter()
+            .map(|n| (n.text.as_str(), n.count))
+            .collect();
+        assert_eq!(texts, [("Disk almost full", 1), ("Saved", 2)]);
+    }
+
+    #[test]
+    fn test_notification_center() {
+        let _queue = lock_queue();
+        let mut notifications = NotificationUi::new(egui::Context::default());
+
+        std::thread::spawn(|| NotificationCenter::warning("From a background thread"))
+            .join()
+            .unwrap();
+        notifications.receive_pending();
+
+        assert!(
+            notifications
+                .notifications()
+                .iter()
+                .any(|n| n.text() == "From a background thread" && n.is_unread())
+        );
+        notifications.mark_all_read();
+        assert_eq!(notifications.unread_count(), 0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_notification_history() {
+        use super::{Link, NotificationPersistence};
+        use std::time::Duration;
+
+        let notification = Notification::new(NotificationLevel::Warning, "Disk almost full")
+            .with_details("3% left")
+            .with_link(Link {
+                text: "Settings".to_owned(),
+                url: "app://settings".to_owned(),
+            })
+            .with_action("clean", "Clean up");
+        let ron = ron::to_string(&notification).unwrap();
+        let restored: Notification = ron::from_str(&ron).unwrap();
+        assert_eq!(restored.level(), NotificationLevel::Warning);
+        assert_eq!(restored.details(), Some("3% left"));
+        assert_eq!(
+            restored.link().map(|l| l.url.as_str()),
+            Some("app://settings")
+        );
+        assert!(restored.buttons.is_empty());
+        assert_ne!(restored.id(), notification.id());
+        let age = |n: &Notification| n.created_at().elapsed().unwrap_or_default();
+        assert!(age(&restored) < Duration::from_secs(5));
+
+        // 保存到 egui memory, 再由新的 NotificationUi 恢复
+        let ctx = egui::Context::default();
+        let persistence = NotificationPersistence::default().max_count(2);
+        let mut notifications =
+            NotificationUi::new(ctx.clone()).with_persistence(persistence.clone());
+        for text in ["a", "b", "c"] {
+            notifications.add(Notification::new(NotificationLevel::Info, text));
+        }
+        notifications.save_history();
+
+        let restored = NotificationUi::new(ctx).with_persistence(persistence);
+        let texts: Vec<_> = restored.notifications().iter().map(|n| n.text()).collect();
+        assert_eq!(texts, ["b", "c"]);
+        assert!(
+            restored
+                .notifications()
+                .iter()
+                .all(|n| n.toast_ttl.is_zero())
+        );
+        assert_eq!(restored.unread_count(), 2);
+    }
+
+    #[test]
+    fn test_toast_placement() {
+        let screen = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(800.0, 600.0));
+
+        let options = ToastOptions::default();
+        assert_eq!(options.start_pos(screen), egui::pos2(792.0, 32.0));
+        assert_eq!(options.pivot(), egui::Align2::RIGHT_TOP);
+
+        // 底部锚定时向上堆叠
+        let options = options.anchor(egui::Align2::CENTER_BOTTOM);
+        assert_eq!(options.start_pos(screen), egui::pos2(400.0, 568.0));
+        assert_eq!(options.pivot(), egui::Align2::CENTER_BOTTOM);
+        assert_eq!(options.slide_offset(0.0), egui::vec2(0.0, 40.0));
+
+        let options = options.direction(ToastDirection::Down);
+        assert_eq!(options.pivot(), egui::Align2::CENTER_TOP);
+    }
+
+    #[test]
+    fn test_remove_expired_toasts() {
+        let mut notifications = NotificationUi::new(egui::Context::default());
+        notifications.add(Notification::new(NotificationLevel::Info, "Saved"));
+        notifications.add(Notification::new(NotificationLevel::Info, "Quiet").no_toast());
+
+        notifications.remove_expired_toasts();
+        let texts: Vec<_> = notifications
+            .notifications()
+            .iter()
+            .map(|n| n.text())
+            .collect();
+        assert_eq!(texts, ["Saved"]);
+
+        notifications.notifications[0].toast_ttl = std::time::Duration::ZERO;
+        notifications.remove_expired_toasts();
+        assert!(notifications.notifications().is_empty());
+    }
+
+    #[test]
+    fn test_progress_and_actions() {
+        let _queue = lock_queue();
+        let mut notifications = NotificationUi::new(egui::Context::default());
+
+        let upload = NotificationCenter::progress("Uploading");
+        upload.update(0.42, "Uploading 42%");
+        notifications.receive_pending();
+        let find = |notifications: &NotificationUi| {
+            notifications
+                .notifications()
+                .iter()
+                .filter(|n| n.id() == upload.id())
+                .map(|n| (n.text().to_owned(), n.progress()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            find(&notifications),
+            [("Uploading 42%".to_owned(), Some(0.42))]
+        );
+
+        // 进度更新不会重新标记为未读
+        notifications.mark_all_read();
+        upload.update(0.5, "Uploading 50%");
+        notifications.receive_pending();
+        assert_eq!(notifications.unread_count(), 0);
+        assert_eq!(notifications.unread_notification_level(), None);
+
+        upload.success("Uploaded");
+        notifications.receive_pending();
+        assert_eq!(find(&notifications), [("Uploaded".to_owned(), None)]);
+
+        let id = NotificationCenter::add(
+            Notification::new(NotificationLevel::Info, "Deleted").with_action("undo", "Undo"),
+        );
+        notifications.receive_pending();
+        let deleted = notifications
+            .notifications
+            .iter_mut()
+            .find(|n| n.id() == id)
+            .unwrap();
+        deleted.trigger("undo".to_owned());
+        assert!(deleted.buttons.is_empty());
+        assert!(
+            NotificationCenter::take_actions().contains(&NotificationAction {
+                notification: id,
+                action: "undo".to_owned(),
+            })
+        );
+
+        // 同样的通知再次出现时不与已触发的合并, 保留自己的按钮和 id
+        let again = NotificationCenter::add(
+            Notification::new(NotificationLevel::Info, "Deleted").with_action("undo", "Undo"),
+        );
+        notifications.receive_pending();
+        let deleted: Vec<_> = notifications
+            .notifications()
+            .iter()
+            .filter(|n| n.text() == "Deleted")
+            .map(|n| (n.id(), n.count, n.buttons.len()))
+            .collect();
+        assert_eq!(deleted, [(id, 1, 0), (again, 1, 1)]);
+    }
+}
@@ -0,0 +1,198 @@
+//! 命令面板使用记录 (Frecency = frequency + recency).
+//!
+//! 每次从 [`crate::CommandPalette`] 选择一项都会记录使用次数和时间.
+//! 空查询时最近使用的条目显示在最前面; 有查询时 frecency 作为加分项参与排序.
+//!
+//! 记录保存在 egui 的持久化 memory 中 (需要开启 egui `persistence` 才能跨进程保留),
+//! 也可以用 [`PaletteHistory::to_ron`] / [`PaletteHistory::from_ron`] 自行保存.
+//! 解析后的记录缓存在 temp memory 中, 只在记录变化时重新写入.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// How many entries to remember at most. The least recently used are forgotten first.
+const MAX_ENTRIES: usize = 256;
+
+/// Usage statistics of a single palette entry.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PaletteUsage {
+    pub count: u32,
+
+    /// Seconds since the unix epoch.
+    pub last_used: f64,
+}
+
+/// Usage history of the command palette, keyed by a stable entry key.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PaletteHistory {
+    entries: BTreeMap<String, PaletteUsage>,
+}
+
+impl PaletteHistory {
+    fn memory_id() -> egui::Id {
+        egui::Id::new("command_palette_history")
+    }
+
+    /// 解析后的记录在 temp memory 中的 id.
+    fn cache_id() -> egui::Id {
+        Self::memory_id().with("parsed")
+    }
+
+    /// Current time in seconds since the unix epoch.
+    pub fn now() -> f64 {
+        web_time::SystemTime::now()
+            .duration_since(web_time::UNIX_EPOCH)
+            .map_or(0.0, |duration| duration.as_secs_f64())
+    }
+
+    pub fn record(&mut self, key: &str, now: f64) {
+        let usage = self.entries.entry(key.to_owned()).or_insert(PaletteUsage {
+            count: 0,
+            last_used: now,
+        });
+        usage.count = usage.count.saturating_add(1);
+        usage.last_used = now;
+
+        if self.entries.len() > MAX_ENTRIES {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by(|(_, a), (_, b)| a.last_used.total_cmp(&b.last_used))
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<PaletteUsage> {
+        self.entries.get(key).copied()
+    }
+
+    /// Use count weighted by how recently the entry was used.
+    ///
+    /// Recent uses count fully, uses older than a few months count for a tenth.
+    pub fn frecency(&self, key: &str, now: f64) -> f64 {
+        const DAY: f64 = 24.0 * 60.0 * 60.0;
+
+        let Some(usage) = self.get(key) else {
+            return 0.0;
+        };
+        let age_days = (now - usage.last_used).max(0.0) / DAY;
+        let weight = if age_days < 4.0 {
+            1.0
+        } else if age_days < 14.0 {
+            0.7
+        } else if age_days < 31.0 {
+            0.5
+        } else if age_days < 90.0 {
+            0.3
+        } else {
+            0.1
+        };
+        usage.count as f64 * weight
+    }
+
+    /// Bonus added to a fuzzy match score, so frequently used entries win ties.
+    pub(crate) fn score_bonus(&self, key: &str, now: f64) -> isize {
+        (self.frecency(key, now).ln_1p() * 8.0).round() as isize
+    }
+
+    /// Keys of the most recently used entries, most recent first.
+    pub fn recent(&self) -> Vec<&str> {
+        let mut entries: Vec<(&String, &PaletteUsage)> = self.entries.iter().collect();
+        entries.sort_by(|(_, a), (_, b)| b.last_used.total_cmp(&a.last_used));
+        entries.into_iter().map(|(key, _)| key.as_str()).collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // ---------------------------------------------------------------------
+    // 持久化
+
+    /// Serialize as RON: `{ "command:SaveRecording": (3, 1700000000.0), … }`.
+    pub fn to_ron(&self) -> anyhow::Result<String> {
+        let map: BTreeMap<&str, (u32, f64)> = self
+            .entries
+            .iter()
+            .map(|(key, usage)| (key.as_str(), (usage.count, usage.last_used)))
+            .collect();
+        Ok(ron::to_string(&map)?)
+    }
+
+    pub fn from_ron(text: &str) -> anyhow::Result<Self> {
+        let map: BTreeMap<String, (u32, f64)> = ron::from_str(text)?;
+        let entries = map
+            .into_iter()
+            .map(|(key, (count, last_used))| (key, PaletteUsage { count, last_used }))
+            .collect();
+        Ok(Self { entries })
+    }
+
+    pub fn load_from_memory(ctx: &egui::Context) -> Self {
+        (*Self::shared(ctx)).clone()
+    }
+
+    /// The history in egui memory, only parsed the first time.
+    pub(crate) fn shared(ctx: &egui::Context) -> Arc<Self> {
+        if let Some(history) = ctx.data(|d| d.get_temp::<Arc<Self>>(Self::cache_id())) {
+            return history;
+        }
+
+        let text = ctx.data_mut(|d| d.get_persisted::<String>(Self::memory_id()));
+        let history = match text.as_deref().map(Self::from_ron) {
+            Some(Ok(history)) => history,
+            Some(Err(err)) => {
+                log::warn!("Failed to load command palette history: {err}");
+                Self::default()
+            }
+            None => Self::default(),
+        };
+        let history = Arc::new(history);
+        ctx.data_mut(|d| d.insert_temp(Self::cache_id(), history.clone()));
+        history
+    }
+
+    pub fn save_to_memory(&self, ctx: &egui::Context) {
+        ctx.data_mut(|d| d.insert_temp(Self::cache_id(), Arc::new(self.clone())));
+        match self.to_ron() {
+            Ok(text) => ctx.data_mut(|d| d.insert_persisted(Self::memory_id(), text)),
+            Err(err) => log::warn!("Failed to save command palette history: {err}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PaletteHistory;
+
+    #[test]
+    fn test_frecency() {
+        const DAY: f64 = 24.0 * 60.0 * 60.0;
+        let now = 1_000.0 * DAY;
+
+        let mut history = PaletteHistory::default();
+        for _ in 0..3 {
+            history.record("old", now - 200.0 * DAY);
+        }
+        history.record("new", now - DAY);
+
+        // One recent use beats three very old ones.
+        assert!(history.frecency("new", now) > history.frecency("old", now));
+        assert_eq!(history.frecency("unknown", now), 0.0);
+        assert_eq!(history.recent(), vec!["new", "old"]);
+
+        let restored = PaletteHistory::from_ron(&history.to_ron().unwrap()).unwrap();
+        assert_eq!(restored, history);
+
+        let ctx = egui::Context::default();
+        history.save_to_memory(&ctx);
+        assert_eq!(PaletteHistory::load_from_memory(&ctx), history);
+    }
+}
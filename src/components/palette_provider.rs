@@ -0,0 +1,370 @@
+//! 命令面板数据源 (PaletteProvider).
+//!
+//! 应用可以向 [`crate::CommandPalette`] 提供任意可搜索的条目 (打开的文件, 符号, 设置, 最近项目 …).
+//! 每个数据源在面板中显示为一个带标题的分组.
+//!
+//! - 没有前缀的数据源与命令一起显示在默认模式中.
+//! - 有前缀的数据源 (如 `@` 符号, `:` 跳转到行) 只在查询以该前缀开头时显示, 查询中的前缀会被去掉.
+//! - 查询以 `>` 开头时只显示命令.
+//!
+//! ```ignore
+//! struct GoToLine;
+//!
+//! impl PaletteProvider<AppAction> for GoToLine {
+//!     fn title(&self) -> &str { "Go to line" }
+//!     fn prefix(&self) -> Option<&str> { Some(":") }
+//!     fn filters_itself(&self) -> bool { true }
+//!
+//!     fn query(&mut self, _ctx: &egui::Context, query: &str) -> PaletteResults<AppAction> {
+//!         let items = query.trim().parse().ok().map(|line: usize| {
+//!             PaletteItem::new(AppAction::GoToLine(line), format!("Go to line {line}"))
+//!         });
+//!         PaletteResults::Ready(items.into_iter().collect())
+//!     }
+//! }
+//! ```
+
+use crate::components::command_palette::COMMAND_PALETTE_COMMAND_PREFIX;
+use crate::components::command_registry::CommandIcon;
+use crate::utils::promise::Promise;
+
+/// A single entry contributed by a [`PaletteProvider`].
+#[derive(Clone)]
+pub struct PaletteItem<A> {
+    /// Returned as [`crate::CommandPaletteAction::Item`] when the entry is chosen.
+    pub action: A,
+    pub text: String,
+    pub tooltip: String,
+
+    /// Already formatted shortcut text shown on the right.
+    pub shortcut_text: Option<String>,
+    pub icon: Option<CommandIcon>,
+    pub category: Option<String>,
+
+    /// Stable key used to remember how often this item is chosen. Defaults to [`Self::text`].
+    pub key: Option<String>,
+}
+
+impl<A> PaletteItem<A> {
+    pub fn new(action: A, text: impl Into<String>) -> Self {
+        Self {
+            action,
+            text: text.into(),
+            tooltip: String::new(),
+            shortcut_text: None,
+            icon: None,
+            category: None,
+            key: None,
+        }
+    }
+
+    #[inline]
+    pub fn tooltip(mut self, tooltip: impl Into<String>) -> Self {
+        self.tooltip = tooltip.into();
+        self
+    }
+
+    #[inline]
+    pub fn shortcut_text(mut self, shortcut_text: impl Into<String>) -> Self {
+        self.shortcut_text = Some(shortcut_text.into());
+        self
+    }
+
+    #[inline]
+    pub fn icon(mut self, icon: &'static crate::Icon) -> Self {
+        self.icon = Some(CommandIcon::Icon(icon));
+        self
+    }
+
+    #[inline]
+    pub fn phosphor_icon(mut self, icon: &'static str) -> Self {
+        self.icon = Some(CommandIcon::Phosphor(icon));
+        self
+    }
+
+    #[inline]
+    pub fn category(mut self, category: impl Into<String>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    #[inline]
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Key in the [`crate::PaletteHistory`], scoped by the provider title.
+    ///
+    /// Title and key are separated by the ASCII unit separator, which can't be typed into a
+    /// title, so these keys never collide with the `command:` keys of registered commands.
+    pub(crate) fn history_key(&self, provider_title: &str) -> String {
+        format!(
+            "{provider_title}\u{1f}{}",
+            self.key.as_deref().unwrap_or(&self.text)
+        )
+    }
+}
+
+/// Result of [`PaletteProvider::query`].
+pub enum PaletteResults<A> {
+    /// The items are available right away.
+    Ready(Vec<PaletteItem<A>>),
+
+    /// The items are computed in the background; a loading row is shown meanwhile.
+    Pending(Promise<Vec<PaletteItem<A>>>),
+}
+
+impl<A> Default for PaletteResults<A> {
+    fn default() -> Self {
+        Self::Ready(Vec::new())
+    }
+}
+
+/// A source of searchable items for the [`crate::CommandPalette`].
+pub trait PaletteProvider<A> {
+    /// Header of the section showing this provider's items.
+    fn title(&self) -> &str;
+
+    /// Only query this provider when the palette query starts with this prefix (e.g. `"@"`).
+    ///
+    /// `None` = show the items together with the commands.
+    fn prefix(&self) -> Option<&str> {
+        None
+    }
+
+    /// Produce the items for `query` (with the prefix removed).
+    ///
+    /// Called when the palette opens and whenever the query changes, not every frame.
+    /// A [`PaletteResults::Pending`] promise is dropped (and thus cancelled) when the query changes.
+    fn query(&mut self, egui_ctx: &egui::Context, query: &str) -> PaletteResults<A>;
+
+    /// If `true`, the items are shown as returned.
+    /// If `false` (the default), the palette fuzzy-matches the query against [`PaletteItem::text`].
+    fn filters_itself(&self) -> bool {
+        false
+    }
+}
+
+/// 查询模式: 由查询前缀决定显示哪些分组.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PaletteMode<'q> {
+    /// 命令 + 无前缀的数据源 + URL.
+    Default(&'q str),
+
+    /// 只显示命令 (`>`).
+    Commands(&'q str),
+
+    /// 只显示某个有前缀的数据源.
+    Provider(usize, &'q str),
+}
+
+impl<'q> PaletteMode<'q> {
+    pub fn new<A>(query: &'q str, providers: &[&mut dyn PaletteProvider<A>]) -> Self {
+        if let Some(rest) = query.strip_prefix(COMMAND_PALETTE_COMMAND_PREFIX) {
+            return Self::Commands(rest.trim_start());
+        }
+
+        // 最长的前缀优先.
+        providers
+            .iter()
+            .enumerate()
+            .filter_map(|(index, provider)| {
+                let prefix = provider.prefix().filter(|prefix| !prefix.is_empty())?;
+                query
+                    .strip_prefix(prefix)
+                    .map(|rest| (prefix.len(), index, rest))
+            })
+            .max_by_key(|(len, _, _)| *len)
+            .map_or(Self::Default(query), |(_, index, rest)| {
+                Self::Provider(index, rest)
+            })
+    }
+}
+
+/// 每个数据源的缓存: 上一次查询及其结果.
+#[derive(Clone)]
+pub(crate) struct ProviderCache<A> {
+    pub query: String,
+    pub items: Vec<PaletteItem<A>>,
+    pub pending: Option<Promise<Vec<PaletteItem<A>>>>,
+}
+
+impl<A: Send + 'static> ProviderCache<A> {
+    pub fn new(query: &str, results: PaletteResults<A>) -> Self {
+        let (items, pending) = match results {
+            PaletteResults::Ready(items) => (items, None),
+            PaletteResults::Pending(promise) => (Vec::new(), Some(promise)),
+        };
+        Self {
+            query: query.to_owned(),
+            items,
+            pending,
+        }
+    }
+
+    /// 查询变化时重新查询数据源, 丢弃 (并取消) 旧的结果.
+    pub fn refresh<'c>(
+        cache: &'c mut Option<Self>,
+        egui_ctx: &egui::Context,
+        provider: &mut dyn PaletteProvider<A>,
+        query: &str,
+    ) -> &'c mut Self {
+        if cache.as_ref().is_some_and(|cache| cache.query != query) {
+            *cache = None;
+        }
+        cache.get_or_insert_with(|| Self::new(query, provider.query(egui_ctx, query)))
+    }
+
+    /// 轮询进行中的查询. 返回是否仍在加载.
+    pub fn poll(&mut self) -> bool {
+        if let Some(promise) = &mut self.pending {
+            if let Some(items) = promise.poll() {
+                self.items = items;
+                self.pending = None;
+            } else if promise.is_ready() || promise.is_cancelled() {
+                self.pending = None;
+            }
+        }
+        self.pending.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{PaletteItem, PaletteMode, PaletteProvider, PaletteResults, ProviderCache};
+    use crate::utils::promise::Promise;
+
+    /// 记录每次查询; 结果在后台等待 `receiver` 交付.
+    struct Provider {
+        prefix: Option<&'static str>,
+        runtime: tokio::runtime::Handle,
+        queries: Vec<String>,
+        receiver: Option<tokio::sync::oneshot::Receiver<Vec<&'static str>>>,
+
+        /// 由进行中的任务持有, 任务结束 (或被取消) 后释放.
+        alive: Arc<()>,
+    }
+
+    impl Provider {
+        fn new(prefix: Option<&'static str>, runtime: &tokio::runtime::Runtime) -> Self {
+            Self {
+                prefix,
+                runtime: runtime.handle().clone(),
+                queries: Vec::new(),
+                receiver: None,
+                alive: Arc::new(()),
+            }
+        }
+    }
+
+    impl PaletteProvider<u32> for Provider {
+        fn title(&self) -> &str {
+            "Test"
+        }
+
+        fn prefix(&self) -> Option<&str> {
+            self.prefix
+        }
+
+        fn query(&mut self, egui_ctx: &egui::Context, query: &str) -> PaletteResults<u32> {
+            self.queries.push(query.to_owned());
+            let receiver = self.receiver.take();
+            let alive = self.alive.clone();
+            PaletteResults::Pending(Promise::spawn(&self.runtime, egui_ctx, async move {
+                let _alive = alive;
+                let Some(receiver) = receiver else {
+                    return std::future::pending().await;
+                };
+                let texts = receiver.await.unwrap_or_default();
+                texts
+                    .into_iter()
+                    .map(|text| PaletteItem::new(0, text))
+                    .collect()
+            }))
+        }
+    }
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+    }
+
+    /// 运行已排队的任务 (单线程 runtime 按顺序执行).
+    fn drive(runtime: &tokio::runtime::Runtime) {
+        runtime.block_on(async { tokio::spawn(async {}).await.unwrap() });
+    }
+
+    #[test]
+    fn test_mode_routing() {
+        let runtime = runtime();
+        let mut plain = Provider::new(None, &runtime);
+        let mut symbols = Provider::new(Some("@"), &runtime);
+        let mut references = Provider::new(Some("@@"), &runtime);
+        let mut empty = Provider::new(Some(""), &runtime);
+        let providers: [&mut dyn PaletteProvider<u32>; 4] =
+            [&mut plain, &mut symbols, &mut references, &mut empty];
+
+        let mode = |query| PaletteMode::new(query, &providers);
+        assert_eq!(mode("> open"), PaletteMode::Commands("open"));
+        assert_eq!(mode(">@x"), PaletteMode::Commands("@x"));
+        assert_eq!(mode("@x"), PaletteMode::Provider(1, "x"));
+        assert_eq!(mode("@@x"), PaletteMode::Provider(2, "x"));
+        assert_eq!(mode("open"), PaletteMode::Default("open"));
+        assert_eq!(mode(""), PaletteMode::Default(""));
+    }
+
+    #[test]
+    fn test_cache_polls_pending_results() {
+        let ctx = egui::Context::default();
+        let runtime = runtime();
+        let mut provider = Provider::new(None, &runtime);
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        provider.receiver = Some(receiver);
+
+        let mut cache = None;
+        let cache = ProviderCache::refresh(&mut cache, &ctx, &mut provider, "a");
+        assert!(cache.poll());
+        drive(&runtime);
+        assert!(cache.poll());
+        assert!(cache.items.is_empty());
+
+        sender.send(vec!["apple", "avocado"]).unwrap();
+        drive(&runtime);
+        assert!(!cache.poll());
+        let texts: Vec<_> = cache.items.iter().map(|item| item.text.as_str()).collect();
+        assert_eq!(texts, ["apple", "avocado"]);
+        assert!(cache.pending.is_none());
+    }
+
+    #[test]
+    fn test_cache_replaced_when_query_changes() {
+        let ctx = egui::Context::default();
+        let runtime = runtime();
+        let mut provider = Provider::new(None, &runtime);
+
+        let mut cache = None;
+        ProviderCache::refresh(&mut cache, &ctx, &mut provider, "a");
+        drive(&runtime);
+        assert_eq!(Arc::strong_count(&provider.alive), 2);
+
+        // 查询不变: 不重新查询
+        assert!(ProviderCache::refresh(&mut cache, &ctx, &mut provider, "a").poll());
+        assert_eq!(provider.queries, ["a"]);
+
+        // 查询变化: 重新查询, 旧的任务被取消
+        let replaced = ProviderCache::refresh(&mut cache, &ctx, &mut provider, "ab");
+        assert_eq!(replaced.query, "ab");
+        assert!(replaced.poll());
+        assert_eq!(provider.queries, ["a", "ab"]);
+        drive(&runtime);
+        assert_eq!(Arc::strong_count(&provider.alive), 2);
+
+        drop(cache);
+        drive(&runtime);
+        assert_eq!(Arc::strong_count(&provider.alive), 1);
+    }
+}
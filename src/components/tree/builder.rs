@@ -2,6 +2,7 @@ use crate::list_item::{CustomContent, LabelContent, ListItem};
 use crate::UiExt;
 use egui::{Align2, FontId, Response, Sense, Ui, WidgetText};
 
+use super::lazy::{lazy_children_id, LazyChildren};
 use super::{TreeAction, TreeConfig, TreeNodeId, TreeState};
 
 /// 单个节点的可选配置.
@@ -83,6 +84,24 @@ impl<'ui, 'a, NodeId: TreeNodeId> TreeBuilder<'ui, 'a, NodeId> {
         let _ = self.render_dir_response(id, label.into(), opts, children);
     }
 
+    /// Render a container node whose children are loaded asynchronously.
+    ///
+    /// 首次展开时发出 [`TreeAction::LoadChildren`], 调用方应通过
+    /// [`super::TreeView::load_children`] 交付结果. 加载中显示 spinner 行, 失败时显示错误行与重试按钮,
+    /// 成功后缓存 `T` 并在每帧传给 `children`, 直到 [`super::TreeView::invalidate_children`].
+    pub fn lazy_dir<T: Clone + Send + Sync + 'static>(
+        &mut self,
+        id: NodeId,
+        label: impl Into<WidgetText>,
+        opts: NodeOptions,
+        children: impl FnOnce(&mut TreeBuilder<'_, 'a, NodeId>, &T),
+    ) {
+        let node_id = id.clone();
+        let _ = self.render_dir_response(id, label.into(), opts, move |builder| {
+            builder.lazy_children_ui(node_id, children);
+        });
+    }
+
     /// Render a container node and return the collapsing response.
     ///
     /// Useful when the caller needs access to `item_response` + `body_response` for e.g. drop zones.
@@ -214,6 +233,71 @@ impl<'ui, 'a, NodeId: TreeNodeId> TreeBuilder<'ui, 'a, NodeId> {
         collapsing
     }
 
+    /// 渲染懒加载目录的 body (仅在目录展开时调用).
+    fn lazy_children_ui<T: Clone + Send + Sync + 'static>(
+        &mut self,
+        id: NodeId,
+        children: impl FnOnce(&mut TreeBuilder<'_, 'a, NodeId>, &T),
+    ) {
+        let key = lazy_children_id(self.tree_id, &id);
+        let lazy = self.ui.ctx().data_mut(|d| {
+            let lazy = d.get_temp_mut_or_default::<LazyChildren<T>>(key);
+            lazy.poll();
+            if matches!(lazy, LazyChildren::Unknown) {
+                *lazy = LazyChildren::Requested;
+                self.actions.push(TreeAction::LoadChildren(id.clone()));
+            }
+            lazy.clone()
+        });
+
+        match lazy {
+            LazyChildren::Unknown | LazyChildren::Requested | LazyChildren::Loading(_) => {
+                self.show_loading_row();
+            }
+            LazyChildren::Loaded(loaded) => children(self, &loaded),
+            LazyChildren::Failed(err) => {
+                if self.show_error_row(&err)
+                    && self
+                        .ui
+                        .ctx()
+                        .data_mut(|d| d.get_temp_mut_or_default::<LazyChildren<T>>(key).retry())
+                {
+                    self.actions.push(TreeAction::LoadChildren(id));
+                }
+            }
+        }
+    }
+
+    fn show_loading_row(&mut self) {
+        let content = CustomContent::new(|ui, _ctx| {
+            let tokens = ui.tokens();
+            ui.add(egui::Spinner::new().size(tokens.small_icon_size.y));
+            ui.add_space(tokens.text_to_icon_padding());
+            ui.weak("Loading…");
+        });
+        self.base_item(false).interactive(false).show_hierarchical(self.ui, content);
+    }
+
+    /// 返回是否点击了重试.
+    fn show_error_row(&mut self, err: &str) -> bool {
+        let retry = std::rc::Rc::new(std::cell::Cell::new(false));
+        let retry_clicked = retry.clone();
+        let content = CustomContent::new(|ui, _ctx| {
+            let color = ui.visuals().error_fg_color;
+            ui.label(egui::RichText::new(err).color(color))
+                .on_hover_text(err);
+        })
+        .button(move |ui: &mut Ui| {
+            let response = ui.small_button("Retry");
+            if response.clicked() {
+                retry_clicked.set(true);
+            }
+            response
+        });
+        self.base_item(false).interactive(false).show_hierarchical(self.ui, content);
+        retry.get()
+    }
+
     fn make_checkbox_content(
         &self,
         checked_now: bool,
@@ -282,47 +366,8 @@ impl<'ui, 'a, NodeId: TreeNodeId> TreeBuilder<'ui, 'a, NodeId> {
     }
 
     fn handle_click(&mut self, id: NodeId, modifiers: egui::Modifiers) {
-        let prev_selected = self.state.selected().to_vec();
-
-        if self.config.allow_multi_select {
-            if modifiers.matches_exact(self.config.range_select_modifier) {
-                if let Some(pivot) = prev_selected.first() {
-                    let range = self.collect_range(pivot, &id);
-                    self.state.set_selected(range);
-                } else {
-                    self.state.set_selected(vec![id]);
-                }
-            } else if modifiers.matches_exact(self.config.toggle_select_modifier) {
-                let mut selected = prev_selected.clone();
-                if let Some(pos) = selected.iter().position(|x| x == &id) {
-                    selected.remove(pos);
-                } else {
-                    selected.push(id);
-                }
-                self.state.set_selected(selected);
-            } else {
-                self.state.set_selected(vec![id]);
-            }
-        } else {
-            self.state.set_selected(vec![id]);
-        }
-
-        if self.state.selected() != prev_selected.as_slice() {
+        if self.state.apply_click(id, modifiers, self.config, self.all_nodes) {
             self.actions.push(TreeAction::SelectionChanged(self.state.selected().to_vec()));
         }
     }
-
-    fn collect_range(&self, from: &NodeId, to: &NodeId) -> Vec<NodeId> {
-        let from_idx = self.all_nodes.iter().position(|x| x == from);
-        let to_idx = self.all_nodes.iter().position(|x| x == to);
-
-        match (from_idx, to_idx) {
-            (Some(f), Some(t)) => {
-                let start = f.min(t);
-                let end = f.max(t);
-                self.all_nodes[start..=end].to_vec()
-            }
-            _ => vec![to.clone()],
-        }
-    }
 }
@@ -0,0 +1,209 @@
+//! 懒加载子节点 (远程文件系统等异步数据源).
+//!
+//! 流程:
+//! 1. `TreeBuilder::lazy_dir` 首次展开时发出 [`TreeAction::LoadChildren`](super::TreeAction::LoadChildren).
+//! 2. 调用方收到后通过 [`TreeView::load_children`](super::TreeView::load_children) 交付一个 `Promise`.
+//! 3. Promise 未完成时显示加载行; 失败时显示错误行 (带重试); 成功后缓存结果直到被
+//!    [`TreeView::invalidate_children`](super::TreeView::invalidate_children) 清除.
+
+use std::sync::Arc;
+
+use crate::utils::promise::Promise;
+
+/// 懒加载子节点的结果类型 (错误以字符串形式展示在错误行).
+pub type LazyChildrenResult<T> = Result<T, String>;
+
+/// 单个目录节点的子节点加载状态.
+pub enum LazyChildren<T> {
+    /// 尚未请求加载.
+    Unknown,
+    /// 已发出 `LoadChildren`, 等待调用方交付 Promise.
+    Requested,
+    /// 正在加载.
+    Loading(Promise<LazyChildrenResult<T>>),
+    /// 已加载 (缓存).
+    Loaded(Arc<T>),
+    /// 加载失败.
+    Failed(String),
+}
+
+// 手动实现, 避免 derive 给 `T` 加上 `Clone` / `Default` 约束.
+impl<T> Clone for LazyChildren<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Unknown => Self::Unknown,
+            Self::Requested => Self::Requested,
+            Self::Loading(promise) => Self::Loading(promise.clone()),
+            Self::Loaded(children) => Self::Loaded(children.clone()),
+            Self::Failed(err) => Self::Failed(err.clone()),
+        }
+    }
+}
+
+#[expect(clippy::derivable_impls, reason = "derive 会要求 `T: Default`")]
+impl<T> Default for LazyChildren<T> {
+    fn default() -> Self {
+        Self::Unknown
+    }
+}
+
+impl<T: Clone + Send + 'static> LazyChildren<T> {
+    /// 轮询正在进行的 Promise, 完成后切换到 `Loaded` / `Failed`.
+    pub(super) fn poll(&mut self) {
+        if let Self::Loading(promise) = self {
+            if let Some(result) = promise.poll() {
+                *self = match result {
+                    Ok(children) => Self::Loaded(Arc::new(children)),
+                    Err(err) => Self::Failed(err),
+                };
+            } else if promise.is_ready() || promise.is_cancelled() {
+                // 发送端已关闭却没有结果 (任务被取消), 视为失败以便重试.
+                *self = Self::Failed("Loading was cancelled".to_owned());
+            }
+        }
+    }
+
+    /// 失败后重试: 回到 `Requested`, 返回是否需要重新发出 `LoadChildren`.
+    pub(super) fn retry(&mut self) -> bool {
+        if matches!(self, Self::Failed(_)) {
+            *self = Self::Requested;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn is_loading(&self) -> bool {
+        matches!(self, Self::Requested | Self::Loading(_))
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        matches!(self, Self::Loaded(_))
+    }
+}
+
+/// 子节点缓存在 egui memory 中的 id.
+pub(super) fn lazy_children_id<NodeId: std::hash::Hash>(
+    tree_id: egui::Id,
+    id: &NodeId,
+) -> egui::Id {
+    tree_id.with(("tree_lazy_children", id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LazyChildren;
+    use crate::components::tree::{NodeOptions, TreeAction, TreeView};
+    use crate::utils::promise::Promise;
+
+    /// 一个懒加载目录 `1`, 子节点为 `Vec<u32>`. 返回本帧的 `LoadChildren` 请求.
+    fn show(ctx: &egui::Context, view: &TreeView<u32>) -> Vec<u32> {
+        let mut actions = Vec::new();
+        let _ = ctx.run(Default::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                // egui 可能在一帧内运行多次, 每次的请求都要处理
+                actions.extend(view.show(ui, |builder| {
+                    builder.lazy_dir(
+                        1,
+                        "dir",
+                        NodeOptions::default(),
+                        |builder, children: &Vec<u32>| {
+                            for &child in children {
+                                builder.leaf(child, child.to_string(), NodeOptions::default());
+                            }
+                        },
+                    );
+                }));
+            });
+        });
+        actions
+            .into_iter()
+            .filter_map(|action| match action {
+                TreeAction::LoadChildren(id) => Some(id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+    }
+
+    /// 运行已排队的任务 (单线程 runtime 按顺序执行).
+    fn drive(runtime: &tokio::runtime::Runtime) {
+        runtime.block_on(async { tokio::spawn(async {}).await.unwrap() });
+    }
+
+    #[test]
+    fn test_load_once_then_loaded() {
+        let ctx = egui::Context::default();
+        let view = TreeView::<u32>::new("lazy").default_expanded(true);
+        let runtime = runtime();
+
+        // 首次展开只请求一次
+        assert_eq!(show(&ctx, &view), [1]);
+        assert_eq!(show(&ctx, &view), Vec::<u32>::new());
+        assert!(view.children_state::<Vec<u32>>(&ctx, &1).is_loading());
+
+        let promise = Promise::spawn(runtime.handle(), &ctx, std::future::pending());
+        view.load_children::<Vec<u32>>(&ctx, &1, promise);
+        assert!(show(&ctx, &view).is_empty());
+        assert!(matches!(
+            view.children_state::<Vec<u32>>(&ctx, &1),
+            LazyChildren::Loading(_)
+        ));
+
+        // 直接交付结果: Loading -> Loaded
+        view.set_children(&ctx, &1, vec![2_u32, 3]);
+        assert!(show(&ctx, &view).is_empty());
+        match view.children_state::<Vec<u32>>(&ctx, &1) {
+            LazyChildren::Loaded(children) => assert_eq!(*children, [2, 3]),
+            _ => panic!("expected loaded children"),
+        }
+
+        // 清除后回到 Unknown, 再次展开时重新请求
+        view.invalidate_children::<Vec<u32>>(&ctx, &1);
+        assert!(matches!(
+            view.children_state::<Vec<u32>>(&ctx, &1),
+            LazyChildren::Unknown
+        ));
+        assert_eq!(show(&ctx, &view), [1]);
+    }
+
+    #[test]
+    fn test_failed_and_retry() {
+        let ctx = egui::Context::default();
+        let view = TreeView::<u32>::new("lazy").default_expanded(true);
+        let runtime = runtime();
+
+        assert_eq!(show(&ctx, &view), [1]);
+        let promise = Promise::spawn(runtime.handle(), &ctx, async {
+            Err::<Vec<u32>, _>("offline".to_owned())
+        });
+        drive(&runtime);
+        view.load_children(&ctx, &1, promise);
+
+        // 失败后停在错误行, 不会自动重新请求
+        assert!(show(&ctx, &view).is_empty());
+        assert!(show(&ctx, &view).is_empty());
+        let mut state = view.children_state::<Vec<u32>>(&ctx, &1);
+        assert!(matches!(&state, LazyChildren::Failed(err) if err == "offline"));
+
+        // 重试回到 Requested, 只有失败状态可以重试
+        assert!(state.retry());
+        assert!(state.is_loading());
+        assert!(!state.retry());
+
+        // 任务被取消视为失败
+        let promise = Promise::spawn(runtime.handle(), &ctx, std::future::pending());
+        view.load_children::<Vec<u32>>(&ctx, &1, promise.clone());
+        promise.cancel();
+        assert!(show(&ctx, &view).is_empty());
+        assert!(matches!(
+            view.children_state::<Vec<u32>>(&ctx, &1),
+            LazyChildren::Failed(_)
+        ));
+    }
+}
@@ -2,11 +2,15 @@
 //! 统一树组件 (点击展开, 支持多选和复选).
 
 mod builder;
+mod lazy;
 mod state;
+mod table;
 mod types;
 mod view;
 
 pub use builder::{NodeOptions, TreeBuilder};
+pub use lazy::{LazyChildren, LazyChildrenResult};
 pub use state::TreeState;
+pub use table::{TreeTable, TreeTableColumn, TreeTableModel, TreeTableSort};
 pub use types::{TreeAction, TreeConfig, TreeNodeId};
 pub use view::TreeView;
@@ -1,4 +1,4 @@
-use super::TreeNodeId;
+use super::{TreeConfig, TreeNodeId};
 use std::collections::HashSet;
 
 #[derive(Clone, Debug)]
@@ -8,6 +8,13 @@ pub struct TreeState<NodeId: TreeNodeId> {
     expanded: HashSet<NodeId>,
     checked: HashSet<NodeId>,
     interacted: HashSet<NodeId>,
+    /// 范围选择的固定端 (普通点击 / 切换点击时更新, Shift 点击时保持不变).
+    anchor: Option<NodeId>,
+    /// 范围选择的活动端 (最近一次点击的节点).
+    focus: Option<NodeId>,
+    /// 上一帧渲染出的完整可见行顺序, 用于计算跨越未渲染行的范围选择.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    visible_order: Vec<NodeId>,
 }
 
 impl<NodeId: TreeNodeId> Default for TreeState<NodeId> {
@@ -17,6 +24,9 @@ impl<NodeId: TreeNodeId> Default for TreeState<NodeId> {
             expanded: HashSet::new(),
             checked: HashSet::new(),
             interacted: HashSet::new(),
+            anchor: None,
+            focus: None,
+            visible_order: Vec::new(),
         }
     }
 }
@@ -46,10 +56,58 @@ impl<NodeId: TreeNodeId> TreeState<NodeId> {
         self.selected.contains(id)
     }
 
+    /// Replace the selection.
+    ///
+    /// If the current anchor is no longer selected, anchor and focus move to the ends of the new selection.
     pub fn set_selected(&mut self, selected: Vec<NodeId>) {
+        if !self.anchor.as_ref().is_some_and(|anchor| selected.contains(anchor)) {
+            self.anchor = selected.first().cloned();
+            self.focus = selected.last().cloned();
+        }
         self.selected = selected;
     }
 
+    /// The fixed end of a range selection.
+    pub fn anchor(&self) -> Option<&NodeId> {
+        self.anchor.as_ref()
+    }
+
+    /// The moving end of a range selection (the most recently clicked node).
+    pub fn focus(&self) -> Option<&NodeId> {
+        self.focus.as_ref()
+    }
+
+    pub fn set_anchor(&mut self, anchor: Option<NodeId>) {
+        self.anchor = anchor;
+    }
+
+    pub fn set_focus(&mut self, focus: Option<NodeId>) {
+        self.focus = focus;
+    }
+
+    /// Visible rows in display order, as rendered by the last frame.
+    pub fn visible_order(&self) -> &[NodeId] {
+        &self.visible_order
+    }
+
+    /// Override the visible row order, e.g. from a data model before the first frame.
+    ///
+    /// `TreeView::show` replaces it with the rendered order at the end of every frame.
+    pub fn set_visible_order(&mut self, order: Vec<NodeId>) {
+        self.visible_order = order;
+    }
+
+    /// All visible nodes between `from` and `to` (inclusive), in display order.
+    ///
+    /// Returns `None` if either end is not visible.
+    pub fn visible_range(&self, from: &NodeId, to: &NodeId) -> Option<Vec<NodeId>> {
+        let from_idx = self.visible_order.iter().position(|x| x == from)?;
+        let to_idx = self.visible_order.iter().position(|x| x == to)?;
+        let start = from_idx.min(to_idx);
+        let end = from_idx.max(to_idx);
+        Some(self.visible_order[start..=end].to_vec())
+    }
+
     pub fn is_expanded(&self, id: &NodeId) -> bool {
         self.expanded.contains(id)
     }
@@ -90,6 +148,74 @@ impl<NodeId: TreeNodeId> TreeState<NodeId> {
         }
     }
 
+    /// 根据修饰键更新选择 (普通点击 / 切换 / 范围), 返回选择是否改变.
+    ///
+    /// `rendered` 是本帧已渲染的行, 仅在上一帧的可见顺序中找不到范围端点时作为回退.
+    pub(super) fn apply_click(
+        &mut self,
+        id: NodeId,
+        modifiers: egui::Modifiers,
+        config: &TreeConfig,
+        rendered: &[NodeId],
+    ) -> bool {
+        let prev_selected = self.selected.clone();
+
+        if config.allow_multi_select {
+            if modifiers.matches_exact(config.range_select_modifier) {
+                // 锚点保持不变, 只移动活动端.
+                if let Some(anchor) = self.anchor.clone() {
+                    let range = self.collect_range(&anchor, &id, rendered);
+                    self.set_selected(range);
+                    self.anchor = Some(anchor);
+                    self.focus = Some(id);
+                } else {
+                    self.select_single(id);
+                }
+            } else if modifiers.matches_exact(config.toggle_select_modifier) {
+                let mut selected = prev_selected.clone();
+                if let Some(pos) = selected.iter().position(|x| x == &id) {
+                    selected.remove(pos);
+                } else {
+                    selected.push(id.clone());
+                }
+                self.set_selected(selected);
+                self.anchor = Some(id.clone());
+                self.focus = Some(id);
+            } else {
+                self.select_single(id);
+            }
+        } else {
+            self.select_single(id);
+        }
+
+        self.selected != prev_selected
+    }
+
+    fn select_single(&mut self, id: NodeId) {
+        self.selected = vec![id.clone()];
+        self.anchor = Some(id.clone());
+        self.focus = Some(id);
+    }
+
+    /// 优先使用上一帧的完整可见顺序 (包含本帧尚未渲染的行), 找不到时回退到本帧已渲染的行.
+    fn collect_range(&self, from: &NodeId, to: &NodeId, rendered: &[NodeId]) -> Vec<NodeId> {
+        if let Some(range) = self.visible_range(from, to) {
+            return range;
+        }
+
+        let from_idx = rendered.iter().position(|x| x == from);
+        let to_idx = rendered.iter().position(|x| x == to);
+
+        match (from_idx, to_idx) {
+            (Some(f), Some(t)) => {
+                let start = f.min(t);
+                let end = f.max(t);
+                rendered[start..=end].to_vec()
+            }
+            _ => vec![to.clone()],
+        }
+    }
+
     pub fn is_interacted(&self, id: &NodeId) -> bool {
         self.interacted.contains(id)
     }
@@ -106,3 +232,57 @@ impl<NodeId: TreeNodeId> TreeState<NodeId> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{TreeConfig, TreeState};
+
+    #[test]
+    fn test_visible_range() {
+        let mut state = TreeState::<u32>::default();
+        state.set_visible_order(vec![1, 2, 3, 4, 5]);
+
+        assert_eq!(state.visible_range(&2, &4), Some(vec![2, 3, 4]));
+        assert_eq!(state.visible_range(&4, &2), Some(vec![2, 3, 4]));
+        assert_eq!(state.visible_range(&3, &3), Some(vec![3]));
+        assert_eq!(state.visible_range(&3, &9), None);
+    }
+
+    #[test]
+    fn test_shift_click_before_rendered() {
+        let config = TreeConfig {
+            allow_multi_select: true,
+            ..Default::default()
+        };
+        let mut state = TreeState::<u32>::default();
+        state.set_visible_order(vec![1, 2, 3, 4, 5]);
+
+        state.apply_click(2, egui::Modifiers::NONE, &config, &[]);
+        // Row 4 is not rendered yet this frame, but known from the previous one.
+        state.apply_click(4, egui::Modifiers::SHIFT, &config, &[1, 2]);
+        assert_eq!(state.selected(), &[2, 3, 4]);
+        assert_eq!(state.anchor(), Some(&2));
+        assert_eq!(state.focus(), Some(&4));
+
+        // Extending again keeps the anchor.
+        state.apply_click(1, egui::Modifiers::SHIFT, &config, &[1]);
+        assert_eq!(state.selected(), &[1, 2]);
+        assert_eq!(state.anchor(), Some(&2));
+    }
+
+    #[test]
+    fn test_anchor_follows_selection() {
+        let mut state = TreeState::<u32>::default();
+        state.set_selected(vec![2, 3]);
+        assert_eq!(state.anchor(), Some(&2));
+        assert_eq!(state.focus(), Some(&3));
+
+        // Anchor still selected: keep it.
+        state.set_selected(vec![1, 2]);
+        assert_eq!(state.anchor(), Some(&2));
+
+        state.set_selected(Vec::new());
+        assert_eq!(state.anchor(), None);
+        assert_eq!(state.focus(), None);
+    }
+}
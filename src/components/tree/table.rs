@@ -0,0 +1,518 @@
+//! 多列树表 (TreeTable).
+//!
+//! 第一列为树 (缩进 + 折叠箭头 + 图标 + 标签), 与 [`TreeView`](super::TreeView) 一样
+//! 用 [`ListItem`] 渲染. 其余列由 [`TreeTableModel::cell_ui`] 渲染.
+//! 列头可调整宽度, 可排序的列点击后在同级节点之间排序.
+//!
+//! ```ignore
+//! let actions = TreeTable::new("files")
+//!     .column(TreeTableColumn::new("Name", Column::remainder()).sortable(true))
+//!     .column(TreeTableColumn::new("Size", Column::initial(80.0).resizable(true)).sortable(true))
+//!     .show(ui, &model);
+//! ```
+
+use std::cmp::Ordering;
+
+use egui::{Sense, Ui, WidgetText};
+use egui_extras::{Column, TableBuilder};
+
+use crate::UiExt as _;
+use crate::foundation::TableStyle;
+use crate::list_item::{LabelContent, ListItem, list_item_scope};
+
+use super::{TreeAction, TreeConfig, TreeNodeId, TreeState};
+
+/// 为 [`TreeTable`] 提供层级数据和单元格内容.
+pub trait TreeTableModel<NodeId: TreeNodeId> {
+    /// Children of `parent` in their natural order (`None` = root nodes).
+    fn children(&self, parent: Option<&NodeId>) -> Vec<NodeId>;
+
+    /// Whether the node can be expanded.
+    fn is_dir(&self, id: &NodeId) -> bool;
+
+    /// Label shown in the tree column.
+    fn label(&self, id: &NodeId) -> WidgetText;
+
+    /// Optional Phosphor icon shown before the label.
+    fn icon(&self, _id: &NodeId) -> Option<&'static str> {
+        None
+    }
+
+    /// Render the cell of an extra column (`column >= 1`; column 0 is the tree).
+    fn cell_ui(&self, ui: &mut Ui, id: &NodeId, column: usize);
+
+    /// Compare two siblings for the given sort column (ascending).
+    fn compare(&self, _a: &NodeId, _b: &NodeId, _column: usize) -> Ordering {
+        Ordering::Equal
+    }
+}
+
+/// 列定义.
+pub struct TreeTableColumn {
+    pub title: WidgetText,
+    pub column: Column,
+    pub sortable: bool,
+}
+
+impl TreeTableColumn {
+    pub fn new(title: impl Into<WidgetText>, column: Column) -> Self {
+        Self {
+            title: title.into(),
+            column,
+            sortable: false,
+        }
+    }
+
+    #[inline]
+    pub fn sortable(mut self, sortable: bool) -> Self {
+        self.sortable = sortable;
+        self
+    }
+}
+
+/// 当前排序列与方向.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TreeTableSort {
+    pub column: usize,
+    pub ascending: bool,
+}
+
+struct FlatRow<NodeId> {
+    id: NodeId,
+    depth: usize,
+    is_dir: bool,
+    is_expanded: bool,
+}
+
+pub struct TreeTable<NodeId: TreeNodeId> {
+    id: egui::Id,
+    config: TreeConfig,
+    columns: Vec<TreeTableColumn>,
+    table_style: TableStyle,
+    _phantom: std::marker::PhantomData<NodeId>,
+}
+
+impl<NodeId: TreeNodeId> TreeTable<NodeId> {
+    pub fn new(id: impl std::hash::Hash) -> Self {
+        Self {
+            id: egui::Id::new(id),
+            config: TreeConfig::default(),
+            columns: Vec::new(),
+            table_style: TableStyle::Dense,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    pub fn config(mut self, config: TreeConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Add a column. The first column holds the tree.
+    pub fn column(mut self, column: TreeTableColumn) -> Self {
+        self.columns.push(column);
+        self
+    }
+
+    pub fn table_style(mut self, table_style: TableStyle) -> Self {
+        self.table_style = table_style;
+        self
+    }
+
+    pub fn allow_multi_select(mut self, allow: bool) -> Self {
+        self.config.allow_multi_select = allow;
+        self
+    }
+
+    pub fn default_expanded(mut self, expanded: bool) -> Self {
+        self.config.default_expanded = expanded;
+        self
+    }
+
+    pub fn state(&self, ctx: &egui::Context) -> TreeState<NodeId> {
+        TreeState::load(ctx, self.id)
+    }
+
+    pub fn modify_state(&self, ctx: &egui::Context, modify: impl FnOnce(&mut TreeState<NodeId>)) {
+        let mut state = TreeState::load(ctx, self.id);
+        modify(&mut state);
+        state.save(ctx, self.id);
+    }
+
+    pub fn sort(&self, ctx: &egui::Context) -> Option<TreeTableSort> {
+        ctx.data(|d| d.get_temp(self.sort_id()))
+    }
+
+    pub fn set_sort(&self, ctx: &egui::Context, sort: Option<TreeTableSort>) {
+        ctx.data_mut(|d| match sort {
+            Some(sort) => d.insert_temp(self.sort_id(), sort),
+            None => d.remove::<TreeTableSort>(self.sort_id()),
+        });
+    }
+
+    fn sort_id(&self) -> egui::Id {
+        self.id.with("tree_table_sort")
+    }
+
+    pub fn show(
+        &self,
+        ui: &mut Ui,
+        model: &impl TreeTableModel<NodeId>,
+    ) -> Vec<TreeAction<NodeId>> {
+        let mut state = TreeState::load(ui.ctx(), self.id);
+        let mut actions = Vec::new();
+        let mut sort = self.sort(ui.ctx());
+
+        let mut rows = Vec::new();
+        self.flatten(model, &state, sort, None, 0, &mut rows);
+
+        // 行顺序在渲染前就已完整, 范围选择可以直接使用.
+        state.set_visible_order(rows.iter().map(|row| row.id.clone()).collect());
+        let order = state.visible_order().to_vec();
+
+        let tokens = ui.tokens();
+        let row_height = self
+            .config
+            .row_height
+            .unwrap_or_else(|| tokens.table_row_height(self.table_style));
+        // 与 ListItem::show_hierarchical_with_children 的缩进一致.
+        let indent = self
+            .config
+            .indent
+            .unwrap_or_else(|| tokens.small_icon_size.x + tokens.text_to_icon_padding());
+
+        list_item_scope(ui, self.id, |ui| {
+            let mut table = TableBuilder::new(ui)
+                .id_salt(self.id)
+                .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                .sense(Sense::click())
+                .auto_shrink([false, true]);
+            for column in &self.columns {
+                table = table.column(column.column);
+            }
+
+            table
+                .header(tokens.deprecated_table_header_height(), |mut header| {
+                    crate::DesignTokens::setup_table_header(&mut header);
+                    for (index, column) in self.columns.iter().enumerate() {
+                        header.col(|ui| {
+                            if let Some(new_sort) = Self::header_ui(ui, index, column, sort) {
+                                sort = new_sort;
+                            }
+                        });
+                    }
+                })
+                .body(|mut body| {
+                    tokens.setup_table_body(&mut body, self.table_style);
+
+                    body.rows(row_height, rows.len(), |mut row| {
+                        let flat = &rows[row.index()];
+                        row.set_selected(state.is_selected(&flat.id));
+
+                        let mut item_response = None;
+                        let mut toggle_clicked = false;
+                        row.col(|ui| {
+                            let (response, clicked) = self.tree_cell_ui(
+                                ui,
+                                model,
+                                flat,
+                                state.is_selected(&flat.id),
+                                indent,
+                            );
+                            item_response = Some(response);
+                            toggle_clicked = clicked;
+                        });
+                        for column in 1..self.columns.len() {
+                            row.col(|ui| model.cell_ui(ui, &flat.id, column));
+                        }
+
+                        // 树列的 ListItem 自己处理点击, 与整行的响应合并.
+                        let mut response = row.response();
+                        if let Some(item_response) = item_response {
+                            response = response.union(item_response);
+                        }
+                        let toggle = toggle_clicked || (flat.is_dir && response.double_clicked());
+                        if toggle {
+                            let expanded = !flat.is_expanded;
+                            if expanded {
+                                state.expand(flat.id.clone());
+                            } else {
+                                state.collapse(&flat.id);
+                            }
+                            actions.push(TreeAction::Toggled {
+                                id: flat.id.clone(),
+                                expanded,
+                            });
+                        } else if response.double_clicked() {
+                            actions.push(TreeAction::Activated(flat.id.clone()));
+                        } else if response.clicked() {
+                            let modifiers = response.ctx.input(|i| i.modifiers);
+                            if state.apply_click(flat.id.clone(), modifiers, &self.config, &order) {
+                                actions
+                                    .push(TreeAction::SelectionChanged(state.selected().to_vec()));
+                            }
+                        }
+
+                        if response.secondary_clicked() {
+                            let position = response
+                                .ctx
+                                .input(|i| i.pointer.interact_pos().unwrap_or_default());
+                            actions.push(TreeAction::ContextMenuRequested {
+                                id: flat.id.clone(),
+                                position,
+                            });
+                        }
+                    });
+                });
+        });
+
+        self.set_sort(ui.ctx(), sort);
+        state.save(ui.ctx(), self.id);
+        actions
+    }
+
+    fn flatten(
+        &self,
+        model: &impl TreeTableModel<NodeId>,
+        state: &TreeState<NodeId>,
+        sort: Option<TreeTableSort>,
+        parent: Option<&NodeId>,
+        depth: usize,
+        rows: &mut Vec<FlatRow<NodeId>>,
+    ) {
+        let mut children = model.children(parent);
+        if let Some(sort) = sort {
+            // 稳定排序: 比较结果相同的兄弟节点保持原有顺序.
+            children.sort_by(|a, b| {
+                let ordering = model.compare(a, b, sort.column);
+                if sort.ascending {
+                    ordering
+                } else {
+                    ordering.reverse()
+                }
+            });
+        }
+
+        for id in children {
+            let is_dir = model.is_dir(&id);
+            let is_expanded = is_dir
+                && if state.is_interacted(&id) {
+                    state.is_expanded(&id)
+                } else {
+                    self.config.default_expanded
+                };
+
+            rows.push(FlatRow {
+                id: id.clone(),
+                depth,
+                is_dir,
+                is_expanded,
+            });
+
+            if is_expanded {
+                self.flatten(model, state, sort, Some(&id), depth + 1, rows);
+            }
+        }
+    }
+
+    /// 渲染列头, 点击可排序的列时返回新的排序状态.
+    fn header_ui(
+        ui: &mut Ui,
+        index: usize,
+        column: &TreeTableColumn,
+        sort: Option<TreeTableSort>,
+    ) -> Option<Option<TreeTableSort>> {
+        let current = sort.filter(|sort| sort.column == index);
+
+        if !column.sortable {
+            ui.add(egui::Label::new(column.title.clone()).selectable(false));
+            return None;
+        }
+
+        let response = ui
+            .add(egui::Label::new(column.title.clone()).sense(Sense::click()))
+            .on_hover_cursor(egui::CursorIcon::PointingHand);
+
+        if let Some(current) = current {
+            let icon = if current.ascending {
+                egui_phosphor::regular::CARET_UP
+            } else {
+                egui_phosphor::regular::CARET_DOWN
+            };
+            ui.label(egui::RichText::new(icon).color(ui.tokens().table_sort_icon_color));
+        }
+
+        // 升序 -> 降序 -> 不排序 (恢复自然顺序).
+        let next = match current {
+            None => Some(TreeTableSort {
+                column: index,
+                ascending: true,
+            }),
+            Some(TreeTableSort {
+                ascending: true, ..
+            }) => Some(TreeTableSort {
+                column: index,
+                ascending: false,
+            }),
+            Some(TreeTableSort {
+                ascending: false, ..
+            }) => None,
+        };
+        response.clicked().then_some(next)
+    }
+
+    /// 渲染树列 (缩进后复用 [`ListItem`]), 返回行响应和是否点击了折叠箭头.
+    fn tree_cell_ui(
+        &self,
+        ui: &mut Ui,
+        model: &impl TreeTableModel<NodeId>,
+        row: &FlatRow<NodeId>,
+        is_selected: bool,
+        indent: f32,
+    ) -> (egui::Response, bool) {
+        // 背景 (选中/悬停) 只覆盖树列, 由表格负责整行.
+        ui.full_span_scope(ui.max_rect().x_range(), |ui| {
+            ui.add_space(row.depth as f32 * indent);
+
+            let mut content = LabelContent::new(model.label(&row.id)).truncate(true);
+            if let Some(icon) = model.icon(&row.id) {
+                content = content.with_phosphor_icon(icon);
+            }
+
+            let openness = row
+                .is_dir
+                .then_some(if row.is_expanded { 1.0 } else { 0.0 });
+            let (response, triangle_response) = ListItem::new()
+                .selected(is_selected)
+                .force_background(egui::Color32::TRANSPARENT)
+                .with_height(ui.available_height())
+                .show_hierarchical_row(
+                    ui,
+                    self.id.with(("tree_table_row", &row.id)),
+                    openness,
+                    content,
+                );
+
+            let toggle_clicked = triangle_response.is_some_and(|r| r.clicked());
+            (response, toggle_clicked)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use egui::{Ui, WidgetText};
+
+    use super::{TreeTable, TreeTableModel, TreeTableSort};
+    use crate::components::tree::TreeState;
+
+    /// `(id, parent, is_dir)`, 自然顺序.
+    struct Files(Vec<(&'static str, Option<&'static str>, bool)>);
+
+    impl TreeTableModel<&'static str> for Files {
+        fn children(&self, parent: Option<&&'static str>) -> Vec<&'static str> {
+            self.0
+                .iter()
+                .filter(|(_, p, _)| p.as_ref() == parent)
+                .map(|(id, _, _)| *id)
+                .collect()
+        }
+
+        fn is_dir(&self, id: &&'static str) -> bool {
+            self.0.iter().any(|(i, _, dir)| i == id && *dir)
+        }
+
+        fn label(&self, id: &&'static str) -> WidgetText {
+            (*id).into()
+        }
+
+        fn cell_ui(&self, _ui: &mut Ui, _id: &&'static str, _column: usize) {}
+
+        fn compare(&self, a: &&'static str, b: &&'static str, _column: usize) -> Ordering {
+            // 只比较第一个字符, 用来检查稳定排序.
+            a.as_bytes()[0].cmp(&b.as_bytes()[0])
+        }
+    }
+
+    #[test]
+    fn test_flatten_and_sort() {
+        let model = Files(vec![
+            ("src", None, true),
+            ("main.rs", Some("src"), false),
+            ("lib.rs", Some("src"), false),
+            ("Cargo.toml", None, false),
+            ("assets", None, true),
+            ("logo.png", Some("assets"), false),
+        ]);
+        let table = TreeTable::<&'static str>::new("files");
+
+        let flatten = |state: &TreeState<&'static str>, sort| {
+            let mut rows = Vec::new();
+            table.flatten(&model, state, sort, None, 0, &mut rows);
+            rows.iter()
+                .map(|row| (row.id, row.depth))
+                .collect::<Vec<_>>()
+        };
+
+        let mut state = TreeState::default();
+        assert_eq!(
+            flatten(&state, None),
+            [("src", 0), ("Cargo.toml", 0), ("assets", 0)]
+        );
+
+        state.expand("src");
+        assert_eq!(
+            flatten(&state, None),
+            [
+                ("src", 0),
+                ("main.rs", 1),
+                ("lib.rs", 1),
+                ("Cargo.toml", 0),
+                ("assets", 0)
+            ]
+        );
+
+        // 排序只在兄弟节点之间进行, 子节点跟随父节点.
+        let ascending = TreeTableSort {
+            column: 0,
+            ascending: true,
+        };
+        assert_eq!(
+            flatten(&state, Some(ascending)),
+            [
+                ("Cargo.toml", 0),
+                ("assets", 0),
+                ("src", 0),
+                ("lib.rs", 1),
+                ("main.rs", 1)
+            ]
+        );
+
+        let descending = TreeTableSort {
+            column: 0,
+            ascending: false,
+        };
+        assert_eq!(
+            flatten(&state, Some(descending)),
+            [
+                ("src", 0),
+                ("main.rs", 1),
+                ("lib.rs", 1),
+                ("assets", 0),
+                ("Cargo.toml", 0)
+            ]
+        );
+
+        // 比较结果相同时保持自然顺序.
+        let model = Files(vec![("b2", None, false), ("b1", None, false)]);
+        let mut rows = Vec::new();
+        table.flatten(&model, &state, Some(ascending), None, 0, &mut rows);
+        assert_eq!(
+            rows.iter().map(|row| row.id).collect::<Vec<_>>(),
+            ["b2", "b1"]
+        );
+    }
+}
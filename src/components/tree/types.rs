@@ -34,6 +34,10 @@ pub enum TreeAction<NodeId> {
     Toggled { id: NodeId, expanded: bool },
     CheckedChanged { id: NodeId, checked: bool },
     ContextMenuRequested { id: NodeId, position: egui::Pos2 },
+    /// A lazy directory was expanded (or retried) and needs its children loaded.
+    ///
+    /// Respond with [`super::TreeView::load_children`] or [`super::TreeView::set_children`].
+    LoadChildren(NodeId),
 }
 
 pub trait TreeNodeId: Clone + Eq + std::hash::Hash + Send + Sync + 'static {}
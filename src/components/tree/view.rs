@@ -1,7 +1,9 @@
 use crate::list_item::list_item_scope;
 use crate::UiExt;
 
+use super::lazy::{lazy_children_id, LazyChildren, LazyChildrenResult};
 use super::{TreeAction, TreeBuilder, TreeConfig, TreeNodeId, TreeState};
+use crate::utils::promise::Promise;
 
 pub struct TreeView<NodeId: TreeNodeId> {
     id: egui::Id,
@@ -65,6 +67,7 @@ impl<NodeId: TreeNodeId> TreeView<NodeId> {
 
         ui.style_mut().visuals.indent_has_left_vline = prev_indent_guides;
 
+        state.set_visible_order(all_nodes);
         state.save(ui.ctx(), self.id);
         actions
     }
@@ -78,4 +81,38 @@ impl<NodeId: TreeNodeId> TreeView<NodeId> {
         modify(&mut state);
         state.save(ctx, self.id);
     }
+
+    /// Deliver the async result for a [`TreeAction::LoadChildren`] request.
+    pub fn load_children<T: Clone + Send + Sync + 'static>(
+        &self,
+        ctx: &egui::Context,
+        id: &NodeId,
+        promise: Promise<LazyChildrenResult<T>>,
+    ) {
+        let key = lazy_children_id(self.id, id);
+        ctx.data_mut(|d| d.insert_temp(key, LazyChildren::Loading(promise)));
+    }
+
+    /// Set the children of a lazy directory directly (e.g. from a synchronous cache).
+    pub fn set_children<T: Clone + Send + Sync + 'static>(&self, ctx: &egui::Context, id: &NodeId, children: T) {
+        let key = lazy_children_id(self.id, id);
+        ctx.data_mut(|d| d.insert_temp(key, LazyChildren::Loaded(std::sync::Arc::new(children))));
+    }
+
+    /// Current load state of a lazy directory.
+    pub fn children_state<T: Clone + Send + Sync + 'static>(&self, ctx: &egui::Context, id: &NodeId) -> LazyChildren<T> {
+        let key = lazy_children_id(self.id, id);
+        ctx.data(|d| d.get_temp::<LazyChildren<T>>(key)).unwrap_or_default()
+    }
+
+    /// Drop cached children so the next expansion emits [`TreeAction::LoadChildren`] again.
+    ///
+    /// An in-flight load is cancelled. If the directory is currently expanded, it reloads on the next frame.
+    pub fn invalidate_children<T: Clone + Send + Sync + 'static>(&self, ctx: &egui::Context, id: &NodeId) {
+        let key = lazy_children_id(self.id, id);
+        let removed = ctx.data_mut(|d| d.remove_temp::<LazyChildren<T>>(key));
+        if let Some(LazyChildren::Loading(promise)) = removed {
+            promise.cancel();
+        }
+    }
 }
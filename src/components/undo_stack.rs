@@ -0,0 +1,555 @@
+//! 撤销 / 重做 (UndoStack).
+//!
+//! 支持两种记录方式, 可以混用:
+//! - 快照: 修改前调用 [`UndoStack::push_snapshot`] 保存修改前的状态 (`State: Clone`).
+//! - 命令: 用 [`UndoStack::execute`] 执行一个 [`UndoCommand`], 撤销时调用其 `revert`.
+//!
+//! 每个历史条目都有标签 (如 `"Move node"`), 显示在历史面板和菜单提示中.
+//! 连续的同类修改 (如拖动滑块) 可以用相同的合并键合并为一条, 多个修改也可以用事务组合为一条.
+//!
+//! ```ignore
+//! // 初始化时把 Undo / Redo 命令的启用状态交给撤销栈:
+//! UndoStack::<Doc>::wire_commands(&mut registry, UICommand::Undo, UICommand::Redo);
+//!
+//! // 每帧:
+//! let before = self.doc.clone();
+//! if ui.add(egui::Slider::new(&mut self.doc.size, 0.0..=10.0)).changed() {
+//!     self.undo.push_snapshot_coalesced("Change size", "size", before);
+//! }
+//! self.undo.publish(ui.ctx());
+//!
+//! // 处理命令:
+//! match command {
+//!     UICommand::Undo => self.undo.undo(&mut self.doc),
+//!     UICommand::Redo => self.undo.redo(&mut self.doc),
+//!     _ => {}
+//! }
+//! ```
+
+use web_time::{Duration, Instant};
+
+use crate::UiExt as _;
+use crate::components::command_registry::{CommandId, CommandRegistry};
+use crate::components::list_item::LabelContent;
+
+/// Default maximum number of undo entries.
+pub const DEFAULT_MAX_UNDO_ENTRIES: usize = 100;
+
+/// Edits with the same coalesce key closer than this are merged into one entry.
+pub const DEFAULT_COALESCE_WINDOW: Duration = Duration::from_millis(1000);
+
+/// A reversible edit of `State`.
+pub trait UndoCommand<State>: Send + Sync {
+    fn apply(&mut self, state: &mut State);
+    fn revert(&mut self, state: &mut State);
+}
+
+enum Change<State> {
+    /// The state before (when undoing) or after (when redoing) the change.
+    Snapshot(State),
+    Command(Box<dyn UndoCommand<State>>),
+}
+
+impl<State: Clone> Change<State> {
+    /// Undo or redo the change, returning the change that reverses it.
+    fn swap(self, state: &mut State, forward: bool) -> Self {
+        match self {
+            Self::Snapshot(snapshot) => Self::Snapshot(std::mem::replace(state, snapshot)),
+            Self::Command(mut command) => {
+                if forward {
+                    command.apply(state);
+                } else {
+                    command.revert(state);
+                }
+                Self::Command(command)
+            }
+        }
+    }
+}
+
+/// A labeled entry in the history. May group several changes.
+struct Entry<State> {
+    label: String,
+    coalesce_key: Option<String>,
+    time: Instant,
+
+    /// In the order they were made.
+    changes: Vec<Change<State>>,
+}
+
+impl<State: Clone> Entry<State> {
+    fn new(label: String, coalesce_key: Option<String>) -> Self {
+        Self {
+            label,
+            coalesce_key,
+            time: Instant::now(),
+            changes: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, change: Change<State>) {
+        // Of consecutive snapshots only the first one matters when undoing.
+        // A snapshot after a command is still needed to undo past that command.
+        let redundant = matches!(change, Change::Snapshot(_))
+            && matches!(self.changes.last(), Some(Change::Snapshot(_)));
+        if !redundant {
+            self.changes.push(change);
+        }
+    }
+
+    fn undo(mut self, state: &mut State) -> Self {
+        self.changes = self
+            .changes
+            .into_iter()
+            .rev()
+            .map(|change| change.swap(state, false))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect();
+        self
+    }
+
+    fn redo(mut self, state: &mut State) -> Self {
+        self.changes = self
+            .changes
+            .into_iter()
+            .map(|change| change.swap(state, true))
+            .collect();
+        self
+    }
+}
+
+/// Undo / redo availability, published to egui memory for menus and command predicates.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UndoStatus {
+    pub undo_label: Option<String>,
+    pub redo_label: Option<String>,
+}
+
+impl UndoStatus {
+    fn id() -> egui::Id {
+        egui::Id::new("undo_stack_status")
+    }
+
+    /// The status last published with [`UndoStack::publish`].
+    pub fn load(egui_ctx: &egui::Context) -> Self {
+        egui_ctx
+            .data(|d| d.get_temp(Self::id()))
+            .unwrap_or_default()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.undo_label.is_some()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.redo_label.is_some()
+    }
+}
+
+/// A history of labeled, undoable changes to a `State`.
+pub struct UndoStack<State> {
+    undo: Vec<Entry<State>>,
+    redo: Vec<Entry<State>>,
+    transaction: Option<(usize, Entry<State>)>,
+    max_entries: usize,
+    coalesce_window: Duration,
+}
+
+impl<State: Clone> Default for UndoStack<State> {
+    fn default() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            transaction: None,
+            max_entries: DEFAULT_MAX_UNDO_ENTRIES,
+            coalesce_window: DEFAULT_COALESCE_WINDOW,
+        }
+    }
+}
+
+impl<State: Clone> UndoStack<State> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries.max(1);
+        self
+    }
+
+    #[inline]
+    pub fn with_coalesce_window(mut self, coalesce_window: Duration) -> Self {
+        self.coalesce_window = coalesce_window;
+        self
+    }
+
+    // ---------------------------------------------------------------------
+    // 记录
+
+    /// Record a change, given the state from before it.
+    pub fn push_snapshot(&mut self, label: impl Into<String>, before: State) {
+        self.push(label.into(), None, Change::Snapshot(before));
+    }
+
+    /// Like [`Self::push_snapshot`], but merged into the previous entry if it has the same
+    /// `coalesce_key` and was made within the coalesce window.
+    pub fn push_snapshot_coalesced(
+        &mut self,
+        label: impl Into<String>,
+        coalesce_key: impl Into<String>,
+        before: State,
+    ) {
+        self.push(
+            label.into(),
+            Some(coalesce_key.into()),
+            Change::Snapshot(before),
+        );
+    }
+
+    /// Apply `command` to `state` and record it.
+    pub fn execute(
+        &mut self,
+        label: impl Into<String>,
+        state: &mut State,
+        mut command: impl UndoCommand<State> + 'static,
+    ) {
+        command.apply(state);
+        self.push(label.into(), None, Change::Command(Box::new(command)));
+    }
+
+    /// Like [`Self::execute`], with coalescing as in [`Self::push_snapshot_coalesced`].
+    pub fn execute_coalesced(
+        &mut self,
+        label: impl Into<String>,
+        coalesce_key: impl Into<String>,
+        state: &mut State,
+        mut command: impl UndoCommand<State> + 'static,
+    ) {
+        command.apply(state);
+        self.push(
+            label.into(),
+            Some(coalesce_key.into()),
+            Change::Command(Box::new(command)),
+        );
+    }
+
+    fn push(&mut self, label: String, coalesce_key: Option<String>, change: Change<State>) {
+        self.redo.clear();
+
+        if let Some((_, transaction)) = &mut self.transaction {
+            transaction.push(change);
+            return;
+        }
+
+        if let Some(last) = self.undo.last_mut()
+            && coalesce_key.is_some()
+            && last.coalesce_key == coalesce_key
+            && last.time.elapsed() < self.coalesce_window
+        {
+            last.push(change);
+            last.time = Instant::now();
+            return;
+        }
+
+        let mut entry = Entry::new(label, coalesce_key);
+        entry.push(change);
+        self.push_entry(entry);
+    }
+
+    fn push_entry(&mut self, entry: Entry<State>) {
+        self.undo.push(entry);
+        if self.undo.len() > self.max_entries {
+            self.undo.remove(0);
+        }
+    }
+
+    // ---------------------------------------------------------------------
+    // 事务
+
+    /// Group all following changes into one entry until [`Self::end_transaction`].
+    ///
+    /// Transactions nest; only the outermost label is used.
+    pub fn begin_transaction(&mut self, label: impl Into<String>) {
+        match &mut self.transaction {
+            Some((depth, _)) => *depth += 1,
+            None => self.transaction = Some((1, Entry::new(label.into(), None))),
+        }
+    }
+
+    pub fn end_transaction(&mut self) {
+        let Some((depth, _)) = &mut self.transaction else {
+            log::warn!("end_transaction without begin_transaction");
+            return;
+        };
+        *depth -= 1;
+        if *depth == 0
+            && let Some((_, mut entry)) = self.transaction.take()
+            && !entry.changes.is_empty()
+        {
+            entry.time = Instant::now();
+            self.push_entry(entry);
+        }
+    }
+
+    /// Revert all changes of the open transaction and close it.
+    pub fn cancel_transaction(&mut self, state: &mut State) {
+        if let Some((_, entry)) = self.transaction.take() {
+            entry.undo(state);
+        }
+    }
+
+    pub fn is_in_transaction(&self) -> bool {
+        self.transaction.is_some()
+    }
+
+    // ---------------------------------------------------------------------
+    // 撤销 / 重做
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Label of the entry [`Self::undo`] would revert.
+    pub fn undo_label(&self) -> Option<&str> {
+        self.undo.last().map(|entry| entry.label.as_str())
+    }
+
+    /// Label of the entry [`Self::redo`] would re-apply.
+    pub fn redo_label(&self) -> Option<&str> {
+        self.redo.last().map(|entry| entry.label.as_str())
+    }
+
+    /// Returns `false` if there was nothing to undo.
+    pub fn undo(&mut self, state: &mut State) -> bool {
+        if self.transaction.is_some() {
+            log::warn!("Cannot undo during a transaction");
+            return false;
+        }
+        let Some(entry) = self.undo.pop() else {
+            return false;
+        };
+        self.redo.push(entry.undo(state));
+        true
+    }
+
+    /// Returns `false` if there was nothing to redo.
+    pub fn redo(&mut self, state: &mut State) -> bool {
+        if self.transaction.is_some() {
+            log::warn!("Cannot redo during a transaction");
+            return false;
+        }
+        let Some(entry) = self.redo.pop() else {
+            return false;
+        };
+        self.undo.push(entry.redo(state));
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.transaction = None;
+    }
+
+    /// Labels of the undoable entries (oldest first), then the redoable ones (next first).
+    pub fn labels(&self) -> (Vec<&str>, Vec<&str>) {
+        (
+            self.undo.iter().map(|entry| entry.label.as_str()).collect(),
+            self.redo
+                .iter()
+                .rev()
+                .map(|entry| entry.label.as_str())
+                .collect(),
+        )
+    }
+
+    // ---------------------------------------------------------------------
+    // UI
+
+    pub fn status(&self) -> UndoStatus {
+        UndoStatus {
+            undo_label: self.undo_label().map(ToOwned::to_owned),
+            redo_label: self.redo_label().map(ToOwned::to_owned),
+        }
+    }
+
+    /// Publish [`Self::status`] for [`Self::wire_commands`]. Call once per frame on the active stack.
+    pub fn publish(&self, egui_ctx: &egui::Context) {
+        let status = self.status();
+        egui_ctx.data_mut(|d| d.insert_temp(UndoStatus::id(), status));
+    }
+
+    /// Enable the `undo` / `redo` commands (menus, palette, shortcuts) only when there is something
+    /// to undo / redo in the stack last [`Self::publish`]ed.
+    pub fn wire_commands<C: CommandId>(registry: &mut CommandRegistry<C>, undo: C, redo: C) {
+        if let Some(command) = registry.get_mut(&undo) {
+            command.set_enabled_when(|egui_ctx| UndoStatus::load(egui_ctx).can_undo());
+        }
+        if let Some(command) = registry.get_mut(&redo) {
+            command.set_enabled_when(|egui_ctx| UndoStatus::load(egui_ctx).can_redo());
+        }
+    }
+
+    /// The history as a list: the initial state, the undoable entries and the redoable ones (weak).
+    ///
+    /// Clicking an entry undoes or redoes up to it. Returns `true` if `state` changed.
+    pub fn history_ui(&mut self, ui: &mut egui::Ui, state: &mut State) -> bool {
+        let num_undo = self.undo.len();
+        let (undo_labels, redo_labels) = self.labels();
+        let labels: Vec<String> = std::iter::once("Initial state")
+            .chain(undo_labels)
+            .chain(redo_labels)
+            .map(ToOwned::to_owned)
+            .collect();
+
+        let mut clicked = None;
+        ui.list_item_scope("undo_history", |ui| {
+            for (position, label) in labels.iter().enumerate() {
+                let response = ui.list_item().selected(position == num_undo).show_flat(
+                    ui,
+                    LabelContent::new(label.as_str()).weak(position > num_undo),
+                );
+                if response.clicked() {
+                    clicked = Some(position);
+                }
+            }
+        });
+
+        let Some(target) = clicked else {
+            return false;
+        };
+        let mut changed = false;
+        while self.undo.len() > target && self.undo(state) {
+            changed = true;
+        }
+        while self.undo.len() < target && self.redo(state) {
+            changed = true;
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{UndoCommand, UndoStack};
+
+    struct Add(i32);
+
+    impl UndoCommand<i32> for Add {
+        fn apply(&mut self, state: &mut i32) {
+            *state += self.0;
+        }
+
+        fn revert(&mut self, state: &mut i32) {
+            *state -= self.0;
+        }
+    }
+
+    /// Sets the state, remembering the previous value.
+    struct Set {
+        value: i32,
+        previous: i32,
+    }
+
+    impl UndoCommand<i32> for Set {
+        fn apply(&mut self, state: &mut i32) {
+            self.previous = std::mem::replace(state, self.value);
+        }
+
+        fn revert(&mut self, state: &mut i32) {
+            *state = self.previous;
+        }
+    }
+
+    #[test]
+    fn test_undo_stack() {
+        let mut state = 0;
+        let mut stack = UndoStack::new();
+
+        // Snapshots:
+        let before = state;
+        state = 5;
+        stack.push_snapshot("Set 5", before);
+
+        // Commands:
+        stack.execute("Add 2", &mut state, Add(2));
+        assert_eq!(state, 7);
+        assert_eq!(stack.undo_label(), Some("Add 2"));
+
+        assert!(stack.undo(&mut state));
+        assert_eq!(state, 5);
+        assert!(stack.undo(&mut state));
+        assert_eq!(state, 0);
+        assert!(!stack.undo(&mut state));
+
+        assert!(stack.redo(&mut state));
+        assert_eq!(state, 5);
+        assert_eq!(stack.redo_label(), Some("Add 2"));
+
+        // A new change clears the redo stack:
+        stack.execute("Add 1", &mut state, Add(1));
+        assert!(!stack.can_redo());
+
+        // Coalescing rapid edits:
+        for _ in 0..3 {
+            stack.execute_coalesced("Drag", "drag", &mut state, Add(10));
+        }
+        assert_eq!(state, 36);
+        assert_eq!(stack.labels().0, vec!["Set 5", "Add 1", "Drag"]);
+        stack.undo(&mut state);
+        assert_eq!(state, 6);
+
+        // Transactions:
+        stack.begin_transaction("Batch");
+        stack.execute("a", &mut state, Add(1));
+        let before = state;
+        state = 100;
+        stack.push_snapshot("b", before);
+        stack.end_transaction();
+        assert_eq!(stack.undo_label(), Some("Batch"));
+        stack.undo(&mut state);
+        assert_eq!(state, 6);
+        stack.redo(&mut state);
+        assert_eq!(state, 100);
+    }
+
+    #[test]
+    fn test_mixed_transaction() {
+        let mut state = 0;
+        let mut stack = UndoStack::new();
+
+        // Snapshot, command, snapshot, snapshot:
+        stack.begin_transaction("Mixed");
+        let before = state;
+        state = 1;
+        stack.push_snapshot("a", before);
+        let set = Set {
+            value: 5,
+            previous: 0,
+        };
+        stack.execute("b", &mut state, set);
+        assert_eq!(state, 5);
+        let before = state;
+        state = 7;
+        stack.push_snapshot("c", before);
+        let before = state;
+        state = 9;
+        stack.push_snapshot("d", before);
+        stack.end_transaction();
+        assert_eq!(stack.labels().0, vec!["Mixed"]);
+
+        assert!(stack.undo(&mut state));
+        assert_eq!(state, 0);
+        assert!(stack.redo(&mut state));
+        assert_eq!(state, 9);
+        assert!(stack.undo(&mut state));
+        assert_eq!(state, 0);
+    }
+}
@@ -0,0 +1,405 @@
+//! 向导 (Wizard) - 多步骤的模态框.
+//!
+//! 每一步是一个页面闭包, 接收共享的类型化状态 `S`. 可以为每一步设置校验 (校验失败时 `Next` 不可用),
+//! 以及标记为可跳过:
+//!
+//! ```ignore
+//! #[derive(Default)]
+//! struct Setup {
+//!     name: String,
+//!     telemetry: bool,
+//! }
+//!
+//! let mut wizard = Wizard::new("Project setup")
+//!     .step(
+//!         WizardStep::new("Name", |ui, setup: &mut Setup| {
+//!             ui.text_edit_singleline(&mut setup.name);
+//!         })
+//!         .validate(|setup| {
+//!             if setup.name.is_empty() { Err("Enter a name".to_owned()) } else { Ok(()) }
+//!         }),
+//!     )
+//!     .step(
+//!         WizardStep::new("Telemetry", |ui, setup: &mut Setup| {
+//!             ui.checkbox(&mut setup.telemetry, "Send anonymous usage data");
+//!         })
+//!         .skippable(true),
+//!     );
+//!
+//! // 每帧:
+//! match wizard.ui(ctx, &mut setup) {
+//!     Some(WizardOutcome::Finished) => create_project(&setup),
+//!     Some(WizardOutcome::Cancelled) | None => {}
+//! }
+//! ```
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::UiExt as _;
+use crate::components::basic::button::ReButton;
+use crate::components::modal::ModalWrapper;
+use crate::components::modal_stack::ModalStack;
+
+/// Returns `Err` with a message for the user if the state is not valid for the step.
+pub type StepValidator<S> = Box<dyn Fn(&S) -> Result<(), String>>;
+
+/// Draws the page of a step.
+pub type StepPage<S> = Box<dyn FnMut(&mut egui::Ui, &mut S)>;
+
+/// A single page of a [`Wizard`].
+pub struct WizardStep<S> {
+    title: String,
+    description: Option<String>,
+    skippable: bool,
+    page: StepPage<S>,
+    validate: Option<StepValidator<S>>,
+}
+
+impl<S> WizardStep<S> {
+    pub fn new(
+        title: impl Into<String>,
+        page: impl FnMut(&mut egui::Ui, &mut S) + 'static,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            description: None,
+            skippable: false,
+            page: Box::new(page),
+            validate: None,
+        }
+    }
+
+    /// Shown below the step title.
+    #[inline]
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Show a `Skip` button that moves on without validating the step.
+    #[inline]
+    pub fn skippable(mut self, skippable: bool) -> Self {
+        self.skippable = skippable;
+        self
+    }
+
+    /// `Next` / `Finish` is disabled while this returns `Err`.
+    #[inline]
+    pub fn validate(mut self, validate: impl Fn(&S) -> Result<(), String> + 'static) -> Self {
+        self.validate = Some(Box::new(validate));
+        self
+    }
+
+    fn check(&self, state: &S) -> Result<(), String> {
+        self.validate
+            .as_ref()
+            .map_or(Ok(()), |validate| validate(state))
+    }
+}
+
+/// How a [`Wizard`] was closed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WizardOutcome {
+    /// `Finish` was clicked on the last step.
+    Finished,
+
+    /// `Cancel`, the close button, `Escape` or the backdrop.
+    Cancelled,
+}
+
+/// A button clicked this frame.
+#[derive(Clone, Copy)]
+enum WizardAction {
+    Back,
+    Next,
+    Skip,
+    Finish,
+    Cancel,
+}
+
+/// A multi-step [`ModalWrapper`], see the [module docs](self).
+///
+/// Like [`crate::modal::ModalHandler`], the wizard is stored in the app and its [`Self::ui`]
+/// method is called every frame. The state `S` is owned by the caller.
+pub struct Wizard<S> {
+    /// Unique per instance, so wizards with the same title don't share a [`ModalStack`] entry.
+    id: egui::Id,
+    title: String,
+    steps: Vec<WizardStep<S>>,
+    min_width: f32,
+    allow_escape: bool,
+
+    is_open: bool,
+    current: usize,
+
+    /// Per step: whether it was skipped.
+    skipped: Vec<bool>,
+}
+
+impl<S> Wizard<S> {
+    pub fn new(title: impl Into<String>) -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+        Self {
+            id: egui::Id::new(("wizard", NEXT_ID.fetch_add(1, Ordering::Relaxed))),
+            title: title.into(),
+            steps: Vec::new(),
+            min_width: 420.0,
+            allow_escape: true,
+            is_open: false,
+            current: 0,
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Add a step.
+    #[inline]
+    pub fn step(mut self, step: WizardStep<S>) -> Self {
+        self.steps.push(step);
+        self.skipped.push(false);
+        self
+    }
+
+    #[inline]
+    pub fn min_width(mut self, min_width: f32) -> Self {
+        self.min_width = min_width;
+        self
+    }
+
+    /// Allow the user to cancel the wizard by interacting with the backdrop and/or pressing escape.
+    #[inline]
+    pub fn allow_escape(mut self, allow_escape: bool) -> Self {
+        self.allow_escape = allow_escape;
+        self
+    }
+
+    /// Open the wizard at the first step.
+    pub fn open(&mut self) {
+        self.is_open = true;
+        self.current = 0;
+        self.skipped.fill(false);
+    }
+
+    /// Close the wizard without reporting an outcome.
+    pub fn close(&mut self) {
+        self.is_open = false;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Index of the current step.
+    pub fn current_step(&self) -> usize {
+        self.current
+    }
+
+    pub fn step_count(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Whether the step at `index` was skipped with the `Skip` button.
+    pub fn is_skipped(&self, index: usize) -> bool {
+        self.skipped.get(index).copied().unwrap_or(false)
+    }
+
+    fn is_last_step(&self) -> bool {
+        self.current + 1 >= self.steps.len()
+    }
+
+    /// Draw the wizard if it is open. Returns the outcome in the frame the wizard closes.
+    pub fn ui(&mut self, ctx: &egui::Context, state: &mut S) -> Option<WizardOutcome> {
+        if !self.is_open {
+            ModalStack::close(ctx, self.id);
+            return None;
+        }
+
+        ModalStack::show(ctx, self.id);
+        let response = self.modal().ui(ctx, |ui| self.content_ui(ui, state));
+
+        let action = match response.inner {
+            Some(action) => Some(action),
+            None if self.allow_escape && response.should_close() => Some(WizardAction::Cancel),
+            None => None,
+        };
+
+        let outcome = action.and_then(|action| self.apply(action, state));
+        if outcome.is_some() {
+            self.is_open = false;
+            ModalStack::close(ctx, self.id);
+        }
+        outcome
+    }
+
+    fn modal(&self) -> ModalWrapper {
+        ModalWrapper::new(&self.title)
+            .id(self.id)
+            .min_width(self.min_width)
+    }
+
+    /// Move between steps. Returns the outcome if the wizard is done.
+    fn apply(&mut self, action: WizardAction, state: &S) -> Option<WizardOutcome> {
+        let valid = self
+            .steps
+            .get(self.current)
+            .is_none_or(|step| step.check(state).is_ok());
+
+        match action {
+            WizardAction::Back => {
+                self.current = self.current.saturating_sub(1);
+            }
+            WizardAction::Next if valid && !self.is_last_step() => {
+                self.skipped[self.current] = false;
+                self.current += 1;
+            }
+            WizardAction::Skip if !self.is_last_step() => {
+                self.skipped[self.current] = true;
+                self.current += 1;
+            }
+            WizardAction::Finish if valid => return Some(WizardOutcome::Finished),
+            WizardAction::Cancel => return Some(WizardOutcome::Cancelled),
+            WizardAction::Next | WizardAction::Skip | WizardAction::Finish => {}
+        }
+        None
+    }
+
+    fn content_ui(&mut self, ui: &mut egui::Ui, state: &mut S) -> Option<WizardAction> {
+        self.steps_indicator_ui(ui);
+        ui.add_space(12.0);
+
+        let is_first = self.current == 0;
+        let is_last = self.is_last_step();
+        let Some(step) = self.steps.get_mut(self.current) else {
+            // 没有步骤
+            return ui.button("Close").clicked().then_some(WizardAction::Cancel);
+        };
+
+        ui.label(egui::RichText::new(&step.title).heading().strong());
+        if let Some(description) = &step.description {
+            ui.weak(description);
+        }
+        ui.add_space(8.0);
+
+        (step.page)(ui, state);
+
+        let validation = step.check(state);
+        if let Err(error) = &validation {
+            ui.add_space(4.0);
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+
+        ui.add_space(16.0);
+        let skippable = step.skippable;
+        let mut action = None;
+        ui.horizontal(|ui| {
+            if ui.add(ReButton::new("Cancel").ghost()).clicked() {
+                action = Some(WizardAction::Cancel);
+            }
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if is_last {
+                    let finish =
+                        ui.add_enabled(validation.is_ok(), ReButton::new("Finish").primary());
+                    if finish.clicked() {
+                        action = Some(WizardAction::Finish);
+                    }
+                } else {
+                    let next = ui.add_enabled(validation.is_ok(), ReButton::new("Next").primary());
+                    if next.clicked() {
+                        action = Some(WizardAction::Next);
+                    }
+                    if skippable && ui.add(ReButton::new("Skip").secondary()).clicked() {
+                        action = Some(WizardAction::Skip);
+                    }
+                }
+
+                let back = ui.add_enabled(!is_first, ReButton::new("Back").secondary());
+                if back.clicked() {
+                    action = Some(WizardAction::Back);
+                }
+            });
+        });
+
+        action
+    }
+
+    /// "✔ Name › 2 Telemetry › 3 Confirm", 当前步骤加粗, 之后的步骤弱化.
+    fn steps_indicator_ui(&self, ui: &mut egui::Ui) {
+        ui.horizontal_wrapped(|ui| {
+            ui.spacing_mut().item_spacing.x = 4.0;
+            for (index, step) in self.steps.iter().enumerate() {
+                if index > 0 {
+                    ui.weak("›");
+                }
+
+                let text = if index < self.current {
+                    let marker = if self.skipped[index] { "–" } else { "✔" };
+                    egui::RichText::new(format!("{marker} {}", step.title))
+                } else {
+                    egui::RichText::new(format!("{} {}", index + 1, step.title))
+                };
+
+                if index == self.current {
+                    ui.label(text.strong());
+                } else if index < self.current {
+                    ui.label(text);
+                } else {
+                    ui.weak(text.text());
+                }
+            }
+        });
+        ui.full_span_separator();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Wizard, WizardAction, WizardOutcome, WizardStep};
+
+    #[test]
+    fn test_wizard_navigation() {
+        let mut wizard = Wizard::new("Setup")
+            .step(
+                WizardStep::new("Name", |_, _: &mut String| {}).validate(|name| {
+                    if name.is_empty() {
+                        Err("Enter a name".to_owned())
+                    } else {
+                        Ok(())
+                    }
+                }),
+            )
+            .step(WizardStep::new("Options", |_, _| {}).skippable(true))
+            .step(WizardStep::new("Confirm", |_, _| {}));
+        wizard.open();
+
+        let mut name = String::new();
+        assert_eq!(wizard.apply(WizardAction::Next, &name), None);
+        assert_eq!(wizard.current_step(), 0);
+
+        name.push_str("demo");
+        wizard.apply(WizardAction::Next, &name);
+        wizard.apply(WizardAction::Skip, &name);
+        assert_eq!(wizard.current_step(), 2);
+        assert!(wizard.is_skipped(1));
+
+        // 最后一步不能跳过
+        wizard.apply(WizardAction::Skip, &name);
+        assert_eq!(wizard.current_step(), 2);
+
+        wizard.apply(WizardAction::Back, &name);
+        assert_eq!(wizard.current_step(), 1);
+        wizard.apply(WizardAction::Next, &name);
+        assert!(!wizard.is_skipped(1));
+
+        assert_eq!(
+            wizard.apply(WizardAction::Finish, &name),
+            Some(WizardOutcome::Finished)
+        );
+
+        // 同名的向导也有各自的 id, 窗口使用该 id 而不是标题
+        let other = Wizard::<String>::new("Setup");
+        assert_ne!(other.id, wizard.id);
+        assert_eq!(wizard.modal().id, wizard.id);
+        assert_ne!(other.modal().id, wizard.modal().id);
+    }
+}
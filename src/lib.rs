@@ -101,6 +101,27 @@ pub use components::{
     CommandPalette,
     CommandPaletteAction,
     CommandPaletteUrl,
+    PaletteHistory,
+    PaletteItem,
+    PaletteProvider,
+    PaletteResults,
+    CommandContext,
+    CommandRegistry,
+    CommandId,
+    CommandParameter,
+    ArgumentValue,
+    CommandSender,
+    KeySequence,
+    KeybindingEditor,
+    Keymap,
+    Menu,
+    MenuBar,
+    MenuItem,
+    UndoCommand,
+    UndoStack,
+    Wizard,
+    WizardOutcome,
+    WizardStep,
     // Basic
     Icon,
 
@@ -109,7 +130,10 @@ pub use components::{
     UICommandSender,
 
     // Dialog
-    dialog::Dialog,
+    dialog::{Dialog, DialogResult, ProgressHandle},
+
+    // Banner
+    banner::{Banner, BannerAction, BannerSeverity, Banners},
 
     // Tree
     TreeView,
@@ -118,6 +142,11 @@ pub use components::{
     TreeAction,
     TreeConfig,
     NodeOptions,
+    LazyChildren,
+    TreeTable,
+    TreeTableColumn,
+    TreeTableModel,
+    TreeTableSort,
 
     // Others
     list_item,
@@ -1,329 +1,311 @@
-//! Toast 通知系统
-//!
-//! 提供全局 Toast 通知支持，可以在任意位置发送，自动请求重绘。
-//!
-//! ## 使用方式
-//!
-//! ```rust
-//! use egui_kit::toast::{GlobalToast};
-//!
-//! // 在 App 初始化时调用
-//! GlobalToast::init(ctx);
-//!
-//! // 在任意位置发送 toast (不需要 ctx)
-//! GlobalToast::success("操作成功");
-//! GlobalToast::error("发生错误");
-//!
-//! // 在 App::update 中渲染
-//! GlobalToast::render(ctx);
-//! ```
-//!
-//! ## 在 Promise 回调中使用
-//!
-//! ```rust
-//! use egui_kit::toast::GlobalToast;
-//!
-//! let promise = Promise::spawn(&app_ctx.runtime, ui.ctx(), async move {
-//!     // 异步操作完成后发送 toast
-//!     GlobalToast::success("保存成功");
-//! });
-//! ```
-
-use std::sync::{Arc, Mutex, Weak};
-
-use egui::{Align2, Context, Direction, RichText, Ui};
-use egui_toast::{Toast as EToast, ToastKind as EToastKind, ToastOptions, Toasts};
-
-/// Toast 通知级别
-#[derive(Clone, Debug, PartialEq)]
-pub enum ToastLevel {
-    /// 信息提示
-    Info,
-    /// 成功提示
-    Success,
-    /// 警告提示
-    Warning,
-    /// 错误提示
-    Error,
-}
-
-/// 待显示的 Toast 消息
-#[derive(Clone, Debug)]
-pub struct ToastMessage {
-    pub level: ToastLevel,
-    pub message: String,
-    pub duration_secs: f32,
-}
-
-impl ToastMessage {
-    pub fn info(message: impl Into<String>) -> Self {
-        Self {
-            level: ToastLevel::Info,
-            message: message.into(),
-            duration_secs: 3.0,
-        }
-    }
-
-    pub fn success(message: impl Into<String>) -> Self {
-        Self {
-            level: ToastLevel::Success,
-            message: message.into(),
-            duration_secs: 3.0,
-        }
-    }
-
-    pub fn error(message: impl Into<String>) -> Self {
-        Self {
-            level: ToastLevel::Error,
-            message: message.into(),
-            duration_secs: 4.0,
-        }
-    }
-
-    pub fn warning(message: impl Into<String>) -> Self {
-        Self {
-            level: ToastLevel::Warning,
-            message: message.into(),
-            duration_secs: 3.0,
-        }
-    }
-}
-
-/// 全局 Toast 状态
-struct GlobalToastState {
-    /// egui Context 的弱引用
-    ctx: Option<Weak<Context>>,
-    /// 待显示的消息队列
-    pending_toasts: Vec<ToastMessage>,
-    /// Toasts 实例 (需要在帧之间保持)
-    toasts: Toasts,
-}
-
-impl Default for GlobalToastState {
-    fn default() -> Self {
-        Self {
-            ctx: None,
-            pending_toasts: Vec::new(),
-            toasts: Toasts::new()
-                .anchor(Align2::RIGHT_TOP, (-10.0, 10.0))
-                .direction(Direction::TopDown),
-        }
-    }
-}
-
-/// 全局 Toast 管理器
-///
-/// 使用 OnceLock 实现全局单例，支持在任何位置发送 toast。
-#[derive(Clone)]
-pub struct GlobalToast {
-    state: Arc<Mutex<GlobalToastState>>,
-}
-
-impl Default for GlobalToast {
-    fn default() -> Self {
-        Self {
-            state: Arc::new(Mutex::new(GlobalToastState::default())),
-        }
-    }
-}
-
-impl GlobalToast {
-    /// 初始化全局 Toast 系统
-    ///
-    /// 需要在 App 创建时调用，传入 egui Context。
-    /// 由于 Context 不能直接存储在全局变量中，使用 Weak 引用。
-    pub fn init(ctx: &Context) {
-        let state = GlobalToast::get_or_init();
-        let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
-        state.ctx = Some(Arc::downgrade(&Arc::new(ctx.clone())));
-    }
-
-    /// 获取或初始化全局状态
-    fn get_or_init() -> &'static Arc<Mutex<GlobalToastState>> {
-        use std::sync::OnceLock;
-        static GLOBAL_STATE: OnceLock<Arc<Mutex<GlobalToastState>>> = OnceLock::new();
-        GLOBAL_STATE.get_or_init(|| Arc::new(Mutex::new(GlobalToastState::default())))
-    }
-
-    /// 添加 Toast 消息到队列
-    fn push_toast(message: ToastMessage) {
-        let state = Self::get_or_init();
-        let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
-
-        // 添加到队列
-        state.pending_toasts.push(message);
-
-        // 请求重绘
-        if let Some(ctx) = &state.ctx {
-            if let Some(ctx) = ctx.upgrade() {
-                ctx.request_repaint();
-            }
-        }
-    }
-
-    /// 渲染所有待显示的 Toast
-    pub fn render(ctx: &Context) {
-        let state = Self::get_or_init();
-        let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
-
-        // 先取出待显示的消息
-        let messages: Vec<ToastMessage> = state.pending_toasts.drain(..).collect();
-
-        // 在独立作用域中添加消息到 Toasts
-        {
-            let toasts = &mut state.toasts;
-            for message in messages {
-                let toast = EToast {
-                    text: RichText::new(message.message).into(),
-                    kind: match message.level {
-                        ToastLevel::Info => EToastKind::Info,
-                        ToastLevel::Success => EToastKind::Success,
-                        ToastLevel::Warning => EToastKind::Warning,
-                        ToastLevel::Error => EToastKind::Error,
-                    },
-                    options: ToastOptions::default()
-                        .duration_in_seconds(message.duration_secs as f64),
-                    ..Default::default()
-                };
-                toasts.add(toast);
-            }
-        }
-
-        // 渲染 Toasts
-        state.toasts.show(ctx);
-    }
-
-    // ===== 便捷 API =====
-
-    /// 添加信息提示
-    pub fn info(message: impl Into<String>) {
-        Self::push_toast(ToastMessage::info(message));
-    }
-
-    /// 添加成功提示
-    pub fn success(message: impl Into<String>) {
-        Self::push_toast(ToastMessage::success(message));
-    }
-
-    /// 添加错误提示
-    pub fn error(message: impl Into<String>) {
-        Self::push_toast(ToastMessage::error(message));
-    }
-
-    /// 添加警告提示
-    pub fn warning(message: impl Into<String>) {
-        Self::push_toast(ToastMessage::warning(message));
-    }
-}
-
-/// Toast 通知管理器 (用于需要细粒度控制的场景)
-#[derive(Default)]
-pub struct Toast {
-    toasts: Toasts,
-}
-
-impl Toast {
-    /// 创建新的 Toast 管理器
-    pub fn new() -> Self {
-        Self {
-            toasts: Toasts::new()
-                .anchor(Align2::RIGHT_TOP, (-10.0, 10.0))
-                .direction(Direction::TopDown),
-        }
-    }
-
-    /// 获取 Toasts 的可变引用
-    pub fn toasts_mut(&mut self) -> &mut Toasts {
-        &mut self.toasts
-    }
-
-    /// 渲染 Toast 通知
-    pub fn show(&mut self, ctx: &Context) {
-        self.toasts.show(ctx);
-    }
-
-    /// 添加信息提示 (使用 Ui)
-    pub fn info(ui: &mut Ui, message: impl Into<String>) {
-        let mut toasts = Toasts::new()
-            .anchor(Align2::RIGHT_TOP, (-10.0, 10.0))
-            .direction(Direction::TopDown);
-        toasts.add(EToast {
-            text: RichText::new(message).into(),
-            kind: EToastKind::Info,
-            options: ToastOptions::default()
-                .duration_in_seconds(3.0),
-            ..Default::default()
-        });
-        toasts.show(ui.ctx());
-    }
-
-    /// 添加成功提示
-    pub fn success(ui: &mut Ui, message: impl Into<String>) {
-        let mut toasts = Toasts::new()
-            .anchor(Align2::RIGHT_TOP, (-10.0, 10.0))
-            .direction(Direction::TopDown);
-        toasts.add(EToast {
-            text: RichText::new(message).into(),
-            kind: EToastKind::Success,
-            options: ToastOptions::default()
-                .duration_in_seconds(3.0),
-            ..Default::default()
-        });
-        toasts.show(ui.ctx());
-    }
-
-    /// 添加错误提示
-    pub fn error(ui: &mut Ui, message: impl Into<String>) {
-        let mut toasts = Toasts::new()
-            .anchor(Align2::RIGHT_TOP, (-10.0, 10.0))
-            .direction(Direction::TopDown);
-        toasts.add(EToast {
-            text: RichText::new(message).into(),
-            kind: EToastKind::Error,
-            options: ToastOptions::default()
-                .duration_in_seconds(4.0),
-            ..Default::default()
-        });
-        toasts.show(ui.ctx());
-    }
-
-    /// 添加警告提示
-    pub fn warning(ui: &mut Ui, message: impl Into<String>) {
-        let mut toasts = Toasts::new()
-            .anchor(Align2::RIGHT_TOP, (-10.0, 10.0))
-            .direction(Direction::TopDown);
-        toasts.add(EToast {
-            text: RichText::new(message).into(),
-            kind: EToastKind::Warning,
-            options: ToastOptions::default()
-                .duration_in_seconds(3.0),
-            ..Default::default()
-        });
-        toasts.show(ui.ctx());
-    }
-}
-
-/// 添加 Toast 到应用（使用 Context）
-pub fn add_toast(ctx: &Context, toast: &mut Toast) {
-    toast.show(ctx);
-}
-
-/// 添加信息提示的便捷函数
-pub fn info(ui: &mut Ui, message: impl Into<String>) {
-    Toast::info(ui, message);
-}
-
-/// 添加成功提示的便捷函数
-pub fn success(ui: &mut Ui, message: impl Into<String>) {
-    Toast::success(ui, message);
-}
-
-/// 添加错误提示的便捷函数
-pub fn error(ui: &mut Ui, message: impl Into<String>) {
-    Toast::error(ui, message);
-}
-
-/// 添加警告提示的便捷函数
-pub fn warning(ui: &mut Ui, message: impl Into<String>) {
-    Toast::warning(ui, message);
-}
+//! Toast 通知系统
+//!
+//! 提供全局 Toast 通知支持，可以在任意位置发送，自动请求重绘。
+//!
+//! Toast 和 [`NotificationUi`] 是同一个通知中心 ([`NotificationCenter`]):
+//! 通过 `GlobalToast` 发送的通知也会进入通知历史 (已读/未读, 详情, 链接, 永久忽略).
+//! 如果应用有 [`NotificationUi`], 由它显示 toast, 不需要再调用 [`GlobalToast::render`].
+//!
+//! ## 使用方式
+//!
+//! ```rust
+//! use egui_kit::toast::{GlobalToast};
+//!
+//! // 在 App 初始化时调用
+//! GlobalToast::init(ctx);
+//!
+//! // 在任意位置发送 toast (不需要 ctx)
+//! GlobalToast::success("操作成功");
+//! GlobalToast::error("发生错误");
+//!
+//! // 在 App::update 中渲染 (没有 NotificationUi 时)
+//! GlobalToast::render(ctx);
+//! ```
+//!
+//! ## 操作按钮和进度
+//!
+//! ```rust
+//! use egui_kit::components::notifications::{Notification, NotificationLevel};
+//! use egui_kit::toast::GlobalToast;
+//!
+//! // 带 "Undo" 按钮的 toast, 点击事件通过 take_actions 获取
+//! let id = GlobalToast::notify(
+//!     Notification::new(NotificationLevel::Info, "文件已删除").with_action("undo", "Undo"),
+//! );
+//! for action in GlobalToast::take_actions() {
+//!     if action.notification == id && action.action == "undo" { /* ... */ }
+//! }
+//!
+//! // 原位更新的进度 toast
+//! let upload = GlobalToast::progress("Uploading…");
+//! upload.update(0.42, "Uploading 42%");
+//! upload.success("Upload complete");
+//! ```
+//!
+//! ## 位置和动画
+//!
+//! ```rust
+//! use egui_kit::toast::{GlobalToast, ToastAnimation, ToastOptions};
+//!
+//! GlobalToast::set_options(
+//!     ToastOptions::default()
+//!         .anchor(egui::Align2::CENTER_BOTTOM)
+//!         .animation(ToastAnimation::Fade)
+//!         .max_width(420.0),
+//! );
+//! ```
+//!
+//! 有 [`NotificationUi`] 时使用 [`NotificationUi::with_toast_options`].
+//!
+//! ## 在 Promise 回调中使用
+//!
+//! ```rust
+//! use egui_kit::toast::GlobalToast;
+//!
+//! let promise = Promise::spawn(&app_ctx.runtime, ui.ctx(), async move {
+//!     // 异步操作完成后发送 toast
+//!     GlobalToast::success("保存成功");
+//! });
+//! ```
+
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use egui::{Context, Ui};
+
+use crate::components::notifications::{
+    Notification, NotificationAction, NotificationCenter, NotificationId, NotificationLevel,
+    NotificationUi, ProgressToast,
+};
+pub use crate::components::notifications::{ToastAnimation, ToastDirection, ToastOptions};
+
+/// [`GlobalToast::render`] 使用的 toast 选项
+static FALLBACK_OPTIONS: Mutex<Option<ToastOptions>> = Mutex::new(None);
+
+/// Toast 通知级别
+#[derive(Clone, Debug, PartialEq)]
+pub enum ToastLevel {
+    /// 信息提示
+    Info,
+    /// 成功提示
+    Success,
+    /// 警告提示
+    Warning,
+    /// 错误提示
+    Error,
+}
+
+impl From<ToastLevel> for NotificationLevel {
+    fn from(level: ToastLevel) -> Self {
+        match level {
+            ToastLevel::Info => Self::Info,
+            ToastLevel::Success => Self::Success,
+            ToastLevel::Warning => Self::Warning,
+            ToastLevel::Error => Self::Error,
+        }
+    }
+}
+
+/// 待显示的 Toast 消息
+#[derive(Clone, Debug)]
+pub struct ToastMessage {
+    pub level: ToastLevel,
+    pub message: String,
+    pub duration_secs: f32,
+}
+
+impl ToastMessage {
+    pub fn info(message: impl Into<String>) -> Self {
+        Self {
+            level: ToastLevel::Info,
+            message: message.into(),
+            duration_secs: 3.0,
+        }
+    }
+
+    pub fn success(message: impl Into<String>) -> Self {
+        Self {
+            level: ToastLevel::Success,
+            message: message.into(),
+            duration_secs: 3.0,
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            level: ToastLevel::Error,
+            message: message.into(),
+            duration_secs: 4.0,
+        }
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self {
+            level: ToastLevel::Warning,
+            message: message.into(),
+            duration_secs: 3.0,
+        }
+    }
+}
+
+impl From<ToastMessage> for Notification {
+    fn from(message: ToastMessage) -> Self {
+        // 无穷大等无法表示的时长视为一直显示
+        let duration =
+            Duration::try_from_secs_f32(message.duration_secs.max(0.0)).unwrap_or(Duration::MAX);
+        Self::new(message.level.into(), message.message).toast_duration(duration)
+    }
+}
+
+/// 全局 Toast 管理器
+///
+/// 发送到 [`NotificationCenter`], 支持在任何位置 (任意线程) 发送 toast。
+pub struct GlobalToast;
+
+impl GlobalToast {
+    /// 初始化全局 Toast 系统
+    ///
+    /// 需要在 App 创建时调用，传入 egui Context, 用于发送 toast 后请求重绘。
+    pub fn init(ctx: &Context) {
+        NotificationCenter::init(ctx);
+    }
+
+    /// 发送 Toast 消息
+    pub fn push(message: ToastMessage) {
+        NotificationCenter::add(message.into());
+    }
+
+    /// 发送通知 (可带操作按钮, 见 [`Notification::with_action`]), 返回其 id
+    pub fn notify(notification: Notification) -> NotificationId {
+        NotificationCenter::add(notification)
+    }
+
+    /// 发送进度 toast, 通过返回的 [`ProgressToast`] 原位更新, 最后转为成功/失败
+    pub fn progress(message: impl Into<String>) -> ProgressToast {
+        NotificationCenter::progress(message)
+    }
+
+    /// 取出上次调用后被点击的操作按钮
+    pub fn take_actions() -> Vec<NotificationAction> {
+        NotificationCenter::take_actions()
+    }
+
+    /// 渲染所有待显示的 Toast
+    ///
+    /// 只在应用没有 [`NotificationUi`] 时需要 (否则由 [`NotificationUi::show_toasts`] 显示)。
+    /// 应用创建了自己的 [`NotificationUi`] 后什么也不做, 以免两者争抢同一个队列。
+    pub fn render(ctx: &Context) {
+        // 应用自己没有 NotificationUi 时使用的通知 UI
+        static FALLBACK_UI: OnceLock<Mutex<Option<NotificationUi>>> = OnceLock::new();
+
+        let mut ui = FALLBACK_UI
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if NotificationUi::live_count() > usize::from(ui.is_some()) {
+            *ui = None;
+            return;
+        }
+        let ui = ui.get_or_insert_with(|| NotificationUi::new(ctx.clone()));
+        if let Some(options) = FALLBACK_OPTIONS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+        {
+            ui.set_toast_options(options);
+        }
+        ui.show_toasts(ctx);
+        // 没有通知面板, 过期的 toast 不再需要保留
+        ui.remove_expired_toasts();
+    }
+
+    /// 设置 toast 的位置, 堆叠方向, 动画和最大宽度 (用于 [`Self::render`])
+    pub fn set_options(options: ToastOptions) {
+        *FALLBACK_OPTIONS.lock().unwrap_or_else(|e| e.into_inner()) = Some(options);
+    }
+
+    // ===== 便捷 API =====
+
+    /// 添加信息提示
+    pub fn info(message: impl Into<String>) {
+        Self::push(ToastMessage::info(message));
+    }
+
+    /// 添加成功提示
+    pub fn success(message: impl Into<String>) {
+        Self::push(ToastMessage::success(message));
+    }
+
+    /// 添加错误提示
+    pub fn error(message: impl Into<String>) {
+        Self::push(ToastMessage::error(message));
+    }
+
+    /// 添加警告提示
+    pub fn warning(message: impl Into<String>) {
+        Self::push(ToastMessage::warning(message));
+    }
+}
+
+/// Toast 通知管理器
+///
+/// 与 [`GlobalToast`] 相同, 发送到 [`NotificationCenter`]。
+#[derive(Default)]
+pub struct Toast;
+
+impl Toast {
+    /// 创建新的 Toast 管理器
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// 渲染 Toast 通知, 见 [`GlobalToast::render`]
+    pub fn show(&mut self, ctx: &Context) {
+        GlobalToast::render(ctx);
+    }
+
+    /// 添加信息提示
+    pub fn info(_ui: &mut Ui, message: impl Into<String>) {
+        GlobalToast::info(message);
+    }
+
+    /// 添加成功提示
+    pub fn success(_ui: &mut Ui, message: impl Into<String>) {
+        GlobalToast::success(message);
+    }
+
+    /// 添加错误提示
+    pub fn error(_ui: &mut Ui, message: impl Into<String>) {
+        GlobalToast::error(message);
+    }
+
+    /// 添加警告提示
+    pub fn warning(_ui: &mut Ui, message: impl Into<String>) {
+        GlobalToast::warning(message);
+    }
+}
+
+/// 添加 Toast 到应用（使用 Context）
+pub fn add_toast(ctx: &Context, toast: &mut Toast) {
+    toast.show(ctx);
+}
+
+/// 添加信息提示的便捷函数
+pub fn info(ui: &mut Ui, message: impl Into<String>) {
+    Toast::info(ui, message);
+}
+
+/// 添加成功提示的便捷函数
+pub fn success(ui: &mut Ui, message: impl Into<String>) {
+    Toast::success(ui, message);
+}
+
+/// 添加错误提示的便捷函数
+pub fn error(ui: &mut Ui, message: impl Into<String>) {
+    Toast::error(ui, message);
+}
+
+/// 添加警告提示的便捷函数
+pub fn warning(ui: &mut Ui, message: impl Into<String>) {
+    Toast::warning(ui, message);
+}
@@ -3,8 +3,12 @@ egui_kit 日志组件
 
 功能:
 - 实现 log crate 的 Log trait
-- 使用 egui memory 缓存日志 (每个 Context 独立)
-- 提供 LogPanel UI 组件显示日志
+- 使用 egui memory 缓存日志 (每个 Context 独立), 环形缓冲区默认保留 100_000 条,
+  可选把淘汰的日志写入文件 (`LogPanel::spill_to_file`)
+- 提供 LogPanel UI 组件显示日志 (按级别/目标过滤, 文本或正则搜索并高亮匹配, 每个级别的计数)
+- 按行虚拟化渲染 (只布局可见的行), 支持选中多行并复制 (Ctrl+C)
+- 自动滚动到底部; 向上滚动时暂停, 回到底部后恢复
+- 可以额外订阅日志 (如转发到 NotificationUi, 见 `NotificationUi::receive_kit_logs`)
 
 使用方式:
 ```rust
@@ -22,14 +26,19 @@ LogPanel::new()
 ```
 */
 
-use std::collections::VecDeque;
-use std::sync::LazyLock;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::io::Write as _;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, Mutex};
 
 use chrono::Local;
 use crossbeam_channel::{Receiver, Sender, unbounded};
 use egui::{Color32, Id, RichText, ScrollArea, Ui};
 use log::{Level, Metadata, Record};
 
+use crate::components::filter::{FilterMatcher, FilterState};
+
 /// 日志条目
 #[derive(Debug, Clone)]
 pub struct LogEntry {
@@ -37,8 +46,18 @@ pub struct LogEntry {
     pub timestamp: String,
     /// 日志级别
     pub level: Level,
+    /// 日志目标 (通常是模块路径, 如 `my_app::net`)
+    pub target: String,
     /// 日志消息
     pub message: String,
+    /// 产生日志的模块路径
+    pub module_path: Option<String>,
+    /// 源文件
+    pub file: Option<String>,
+    /// 源文件行号
+    pub line: Option<u32>,
+    /// 线程名 (没有名字时为线程 id)
+    pub thread: Option<String>,
 }
 
 impl LogEntry {
@@ -48,10 +67,54 @@ impl LogEntry {
         Self {
             timestamp,
             level,
+            target: String::new(),
             message,
+            module_path: None,
+            file: None,
+            line: None,
+            thread: None,
         }
     }
 
+    /// 从 log 记录创建, 记录当前线程
+    pub fn from_record(record: &Record<'_>) -> Self {
+        let mut entry = Self::new(record.level(), record.args().to_string())
+            .with_target(record.target())
+            .with_thread(current_thread_name());
+        entry.module_path = record.module_path().map(str::to_owned);
+        entry.file = record.file().map(str::to_owned);
+        entry.line = record.line();
+        entry
+    }
+
+    /// 设置日志目标
+    pub fn with_target(mut self, target: impl Into<String>) -> Self {
+        self.target = target.into();
+        self
+    }
+
+    /// 设置源位置
+    pub fn with_location(mut self, file: impl Into<String>, line: u32) -> Self {
+        self.file = Some(file.into());
+        self.line = Some(line);
+        self
+    }
+
+    /// 设置线程名
+    pub fn with_thread(mut self, thread: impl Into<String>) -> Self {
+        self.thread = Some(thread.into());
+        self
+    }
+
+    /// `file:line` (没有文件时为 `None`)
+    pub fn location(&self) -> Option<String> {
+        let file = self.file.as_ref()?;
+        Some(match self.line {
+            Some(line) => format!("{file}:{line}"),
+            None => file.clone(),
+        })
+    }
+
     /// 获取完整日志行 (带时间戳)
     pub fn full_line(&self) -> String {
         format!("{} {}", self.timestamp, self.message)
@@ -59,13 +122,26 @@ impl LogEntry {
 
     /// 获取级别颜色
     pub fn level_color(&self) -> Color32 {
-        match self.level {
-            Level::Error => Color32::from_rgb(255, 100, 100), // 红色
-            Level::Warn => Color32::from_rgb(255, 200, 100),  // 橙色
-            Level::Info => Color32::from_rgb(150, 200, 255),  // 蓝色
-            Level::Debug => Color32::from_rgb(180, 180, 180), // 灰色
-            Level::Trace => Color32::from_rgb(150, 150, 150), // 浅灰
-        }
+        level_color(self.level)
+    }
+}
+
+/// 日志级别的颜色
+pub fn level_color(level: Level) -> Color32 {
+    match level {
+        Level::Error => Color32::from_rgb(255, 100, 100), // 红色
+        Level::Warn => Color32::from_rgb(255, 200, 100),  // 橙色
+        Level::Info => Color32::from_rgb(150, 200, 255),  // 蓝色
+        Level::Debug => Color32::from_rgb(180, 180, 180), // 灰色
+        Level::Trace => Color32::from_rgb(150, 150, 150), // 浅灰
+    }
+}
+
+fn current_thread_name() -> String {
+    let thread = std::thread::current();
+    match thread.name() {
+        Some(name) => name.to_owned(),
+        None => format!("{:?}", thread.id()),
     }
 }
 
@@ -86,6 +162,22 @@ fn log_receiver() -> &'static Receiver<LogEntry> {
     &LOG_CHANNEL.1
 }
 
+/// 额外的订阅者 (每个订阅者都收到所有日志)
+static LOG_SUBSCRIBERS: LazyLock<Mutex<Vec<Sender<LogEntry>>>> =
+    LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// 订阅日志: 返回的接收端收到之后的每一条日志, 与 LogPanel 互不影响.
+///
+/// 接收端被 drop 后自动取消订阅.
+pub fn subscribe_logs() -> Receiver<LogEntry> {
+    let (sender, receiver) = unbounded();
+    LOG_SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(sender);
+    receiver
+}
+
 // ============================================================================
 // 日志器实现
 // ============================================================================
@@ -100,7 +192,14 @@ impl log::Log for KitLogger {
 
     fn log(&self, record: &Record) {
         if self.enabled(record.metadata()) {
-            let entry = LogEntry::new(record.level(), format!("{}", record.args()));
+            let entry = LogEntry::from_record(record);
+
+            // 发送给订阅者, 移除已断开的
+            LOG_SUBSCRIBERS
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .retain(|subscriber| subscriber.try_send(entry.clone()).is_ok());
+
             // 发送到通道 (非阻塞)
             let _ = log_sender().try_send(entry);
         }
@@ -133,42 +232,154 @@ pub fn set_log_level(level: log::LevelFilter) {
 // egui Memory 中的日志缓存
 // ============================================================================
 
+/// 默认最多保留的日志条数
+const DEFAULT_MAX_ENTRIES: usize = 100_000;
+
 /// 日志缓存状态 (存储在 egui memory 中)
-#[derive(Clone)]
+///
+/// 每条日志有一个序号 (加入的顺序), 淘汰旧日志后序号不变, 用于过滤结果和选中状态.
 struct LogCache {
     entries: VecDeque<LogEntry>,
     max_entries: usize,
+    /// `entries[0]` 的序号
+    first_seq: u64,
+    /// 每个级别的日志数 (顺序同 [`ALL_LEVELS`])
+    level_counts: [usize; 5],
+    /// 每个目标的日志数
+    targets: TargetNode,
+
+    /// 通过过滤的日志序号 (递增)
+    filtered: Vec<u64>,
+    /// `filtered` 对应的过滤条件
+    filter_key: Option<LogFilterKey>,
+    /// `filter_key` 中的搜索条件编译后的结果, 只在搜索条件变化时重新编译
+    search: LogSearch,
+    /// 已经过滤到的序号 (之后的日志还没检查)
+    filtered_up_to: u64,
+
+    /// 淘汰的日志写入这个文件
+    spill_path: Option<PathBuf>,
+    spill_writer: Option<std::io::BufWriter<std::fs::File>>,
 }
 
+/// 共享的日志缓存, 避免每帧复制整个缓存
+type SharedLogCache = Arc<Mutex<LogCache>>;
+
 impl Default for LogCache {
     fn default() -> Self {
-        Self {
-            entries: VecDeque::with_capacity(1000),
-            max_entries: 1000,
-        }
+        Self::with_capacity(DEFAULT_MAX_ENTRIES)
     }
 }
 
 impl LogCache {
     fn with_capacity(max_entries: usize) -> Self {
         Self {
-            entries: VecDeque::with_capacity(max_entries),
+            entries: VecDeque::new(),
             max_entries,
+            first_seq: 0,
+            level_counts: [0; 5],
+            targets: TargetNode::default(),
+            filtered: Vec::new(),
+            filter_key: None,
+            search: LogSearch::All,
+            filtered_up_to: 0,
+            spill_path: None,
+            spill_writer: None,
         }
     }
 
+    /// 下一条日志的序号
+    fn next_seq(&self) -> u64 {
+        self.first_seq + self.entries.len() as u64
+    }
+
+    fn get(&self, seq: u64) -> Option<&LogEntry> {
+        let index = usize::try_from(seq.checked_sub(self.first_seq)?).ok()?;
+        self.entries.get(index)
+    }
+
     /// 从通道接收新日志
     fn drain_channel(&mut self) {
+        let mut spilled = false;
         while let Ok(entry) = log_receiver().try_recv() {
-            if self.entries.len() >= self.max_entries {
-                self.entries.pop_front();
+            self.push(entry);
+            spilled |= self.spill_writer.is_some();
+        }
+        if spilled {
+            self.flush_spill();
+        }
+    }
+
+    fn push(&mut self, entry: LogEntry) {
+        while self.entries.len() >= self.max_entries.max(1) {
+            let Some(evicted) = self.entries.pop_front() else {
+                break;
+            };
+            self.first_seq += 1;
+            self.level_counts[LogViewState::level_index(evicted.level)] -= 1;
+            self.targets.remove(&evicted.target);
+            self.spill(&evicted);
+        }
+        self.level_counts[LogViewState::level_index(entry.level)] += 1;
+        self.targets.add(&entry.target);
+        self.entries.push_back(entry);
+    }
+
+    /// 把淘汰的日志追加到文件. 出错时停止写入 (不能在这里记录日志).
+    fn spill(&mut self, entry: &LogEntry) {
+        let Some(path) = &self.spill_path else {
+            return;
+        };
+        if self.spill_writer.is_none() {
+            match std::fs::File::options()
+                .create(true)
+                .append(true)
+                .open(path)
+            {
+                Ok(file) => self.spill_writer = Some(std::io::BufWriter::new(file)),
+                Err(_) => {
+                    self.spill_path = None;
+                    return;
+                }
             }
-            self.entries.push_back(entry);
+        }
+        if let Some(writer) = &mut self.spill_writer
+            && writeln!(
+                writer,
+                "{} {:<5} {} {}",
+                entry.timestamp, entry.level, entry.target, entry.message
+            )
+            .is_err()
+        {
+            self.spill_writer = None;
+            self.spill_path = None;
+        }
+    }
+
+    fn flush_spill(&mut self) {
+        if let Some(writer) = &mut self.spill_writer
+            && writer.flush().is_err()
+        {
+            self.spill_writer = None;
+            self.spill_path = None;
+        }
+    }
+
+    fn set_spill_path(&mut self, path: Option<PathBuf>) {
+        if self.spill_path != path {
+            self.flush_spill();
+            self.spill_writer = None;
+            self.spill_path = path;
         }
     }
 
     fn clear(&mut self) {
+        self.first_seq = self.next_seq();
         self.entries.clear();
+        self.level_counts = [0; 5];
+        self.targets = TargetNode::default();
+        self.filtered.clear();
+        self.filtered_up_to = self.first_seq;
     }
 
     fn len(&self) -> usize {
@@ -179,18 +390,323 @@ impl LogCache {
         self.entries.is_empty()
     }
 
-    fn iter(&self) -> impl Iterator<Item = &LogEntry> {
-        self.entries.iter()
-    }
-
-    /// 获取所有日志的文本 (用于复制)
-    fn to_text(&self) -> String {
-        self.entries
-            .iter()
-            .map(|e| e.full_line())
+    /// 通过过滤的日志 (按 `seqs` 的顺序), 每条一行
+    fn lines_text(&self, seqs: impl Iterator<Item = u64>) -> String {
+        seqs.filter(|seq| self.filtered.binary_search(seq).is_ok())
+            .filter_map(|seq| self.get(seq))
+            .map(LogEntry::full_line)
             .collect::<Vec<_>>()
             .join("\n")
     }
+
+    /// 更新过滤结果: 过滤条件变化时重新过滤, 否则只检查新日志
+    fn update_filter(&mut self, view: &LogViewState) {
+        let key = view.filter_key();
+        if self.filter_key.as_ref() != Some(&key) {
+            let search_changed = self
+                .filter_key
+                .as_ref()
+                .is_none_or(|old| old.query != key.query || old.regex != key.regex);
+            if search_changed {
+                self.search = view.search();
+            }
+            self.filter_key = Some(key);
+            self.filtered.clear();
+            self.filtered_up_to = self.first_seq;
+        }
+
+        // 移除已淘汰的日志
+        let first_seq = self.first_seq;
+        let evicted = self.filtered.partition_point(|&seq| seq < first_seq);
+        self.filtered.drain(..evicted);
+
+        let start = self.filtered_up_to.max(first_seq);
+        let skip = usize::try_from(start - first_seq).unwrap_or(usize::MAX);
+        for (seq, entry) in (start..).zip(self.entries.iter().skip(skip)) {
+            if view.is_visible(entry) && self.search.find(&entry.message).is_some() {
+                self.filtered.push(seq);
+            }
+        }
+        self.filtered_up_to = self.next_seq();
+    }
+}
+
+// ============================================================================
+// 日志过滤
+// ============================================================================
+
+/// 所有级别, 从严重到详细
+const ALL_LEVELS: [Level; 5] = [
+    Level::Error,
+    Level::Warn,
+    Level::Info,
+    Level::Debug,
+    Level::Trace,
+];
+
+/// 日志面板的过滤和选中状态 (存储在 egui memory 中)
+struct LogViewState {
+    /// 每个级别是否显示 (顺序同 [`ALL_LEVELS`])
+    levels: [bool; 5],
+    /// 隐藏的目标 (包括其子目标, 如 `wgpu` 隐藏 `wgpu::backend`)
+    hidden_targets: BTreeSet<String>,
+    /// 搜索框
+    search: FilterState,
+    /// 搜索框内容作为正则表达式
+    regex: bool,
+    /// 选中的日志序号
+    selected: BTreeSet<u64>,
+    /// Shift+点击 从这一行开始选中
+    selection_anchor: Option<u64>,
+    /// 下一帧滚动到的位置 (`f32::MAX` 为底部)
+    pending_scroll: Option<f32>,
+}
+
+/// 共享的面板状态, 避免每帧复制选中的日志
+type SharedLogView = Arc<Mutex<LogViewState>>;
+
+impl Default for LogViewState {
+    fn default() -> Self {
+        Self {
+            levels: [true; 5],
+            hidden_targets: BTreeSet::new(),
+            search: FilterState::default(),
+            regex: false,
+            selected: BTreeSet::new(),
+            selection_anchor: None,
+            pending_scroll: None,
+        }
+    }
+}
+
+impl LogViewState {
+    fn level_index(level: Level) -> usize {
+        level as usize - 1
+    }
+
+    fn is_target_hidden(&self, target: &str) -> bool {
+        self.hidden_targets.iter().any(|hidden| {
+            target == hidden
+                || target
+                    .strip_prefix(hidden.as_str())
+                    .is_some_and(|rest| rest.starts_with("::"))
+        })
+    }
+
+    /// 级别和目标过滤 (不包括搜索)
+    fn is_visible(&self, entry: &LogEntry) -> bool {
+        self.levels[Self::level_index(entry.level)] && !self.is_target_hidden(&entry.target)
+    }
+
+    /// 点击一行: 单选, Shift 选中范围 (在 `filtered` 中), Ctrl/Cmd 切换
+    fn select(&mut self, seq: u64, filtered: &[u64], modifiers: egui::Modifiers) {
+        if modifiers.shift
+            && let Some(anchor) = self.selection_anchor
+            && let (Ok(a), Ok(b)) = (
+                filtered.binary_search(&anchor),
+                filtered.binary_search(&seq),
+            )
+        {
+            if !modifiers.command {
+                self.selected.clear();
+            }
+            self.selected.extend(&filtered[a.min(b)..=a.max(b)]);
+        } else if modifiers.command {
+            if !self.selected.remove(&seq) {
+                self.selected.insert(seq);
+            }
+            self.selection_anchor = Some(seq);
+        } else {
+            self.selected.clear();
+            self.selected.insert(seq);
+            self.selection_anchor = Some(seq);
+        }
+    }
+
+    /// 移除已淘汰的日志的选中状态
+    fn prune_selection(&mut self, first_seq: u64) {
+        self.selected = self.selected.split_off(&first_seq);
+        if self.selection_anchor.is_some_and(|seq| seq < first_seq) {
+            self.selection_anchor = None;
+        }
+    }
+
+    fn filter_key(&self) -> LogFilterKey {
+        LogFilterKey {
+            levels: self.levels,
+            hidden_targets: self.hidden_targets.clone(),
+            query: self.search.query().map(str::to_owned),
+            regex: self.regex,
+        }
+    }
+
+    fn search(&self) -> LogSearch {
+        match self.search.query() {
+            None | Some("") => LogSearch::All,
+            Some(query) if self.regex => {
+                match regex::RegexBuilder::new(query)
+                    .case_insensitive(true)
+                    .build()
+                {
+                    Ok(regex) => LogSearch::Regex(regex),
+                    Err(err) => LogSearch::InvalidRegex(err.to_string()),
+                }
+            }
+            Some(_) => LogSearch::Keywords(self.search.filter()),
+        }
+    }
+}
+
+/// 影响过滤结果的 [`LogViewState`], 变化时需要重新过滤
+#[derive(Clone, PartialEq)]
+struct LogFilterKey {
+    levels: [bool; 5],
+    hidden_targets: BTreeSet<String>,
+    query: Option<String>,
+    regex: bool,
+}
+
+/// 日志消息的搜索
+enum LogSearch {
+    All,
+    /// 关键字, 见 [`FilterMatcher`]
+    Keywords(FilterMatcher),
+    Regex(regex::Regex),
+    /// 正则表达式无效, 显示所有日志
+    InvalidRegex(String),
+}
+
+impl LogSearch {
+    /// 不匹配时返回 `None`, 否则返回要高亮的区间 (已排序且不重叠)
+    fn find(&self, text: &str) -> Option<Vec<Range<usize>>> {
+        match self {
+            Self::All | Self::InvalidRegex(_) => Some(Vec::new()),
+            Self::Keywords(matcher) => {
+                let mut ranges = matcher.match_path([text])?;
+                // 区间对应小写文本, 长度变化时 (少数非 ASCII 字符) 不高亮
+                if text.to_lowercase().len() != text.len() {
+                    return Some(Vec::new());
+                }
+                Some(ranges.remove(0).map(Iterator::collect).unwrap_or_default())
+            }
+            Self::Regex(regex) => {
+                let ranges: Vec<_> = regex
+                    .find_iter(text)
+                    .map(|m| m.range())
+                    .filter(|range| !range.is_empty())
+                    .collect();
+                (!ranges.is_empty()).then_some(ranges)
+            }
+        }
+    }
+}
+
+/// 目标树的节点 (目标按 `::` 拆分)
+#[derive(Default)]
+struct TargetNode {
+    children: BTreeMap<String, TargetNode>,
+    /// 该目标及其子目标的日志数
+    count: usize,
+}
+
+impl TargetNode {
+    /// 加入一条该目标的日志
+    fn add(&mut self, target: &str) {
+        self.count += 1;
+        let mut node = self;
+        for part in target.split("::") {
+            node = node.children.entry(part.to_owned()).or_default();
+            node.count += 1;
+        }
+    }
+
+    /// 移除一条该目标的日志, 没有日志的节点一起移除
+    fn remove(&mut self, target: &str) {
+        self.count -= 1;
+        let (part, rest) = match target.split_once("::") {
+            Some((part, rest)) => (part, Some(rest)),
+            None => (target, None),
+        };
+        if let Some(child) = self.children.get_mut(part) {
+            match rest {
+                Some(rest) => child.remove(rest),
+                None => child.count -= 1,
+            }
+            if child.count == 0 {
+                self.children.remove(part);
+            }
+        }
+    }
+
+    /// 每个子目标一个复选框, 取消勾选时隐藏该目标
+    fn ui(&self, ui: &mut Ui, prefix: &str, parent_visible: bool, state: &mut LogViewState) {
+        for (name, child) in &self.children {
+            let path = if prefix.is_empty() {
+                name.clone()
+            } else {
+                format!("{prefix}::{name}")
+            };
+            let label = if name.is_empty() {
+                "(无)"
+            } else {
+                name.as_str()
+            };
+
+            let mut visible = !state.hidden_targets.contains(&path);
+            let checkbox = |ui: &mut Ui, visible: &mut bool| {
+                ui.add_enabled(
+                    parent_visible,
+                    egui::Checkbox::new(visible, format!("{label} ({})", child.count)),
+                )
+            };
+
+            let changed = if child.children.is_empty() {
+                checkbox(ui, &mut visible).changed()
+            } else {
+                let id = ui.id().with(&path);
+                let mut changed = false;
+                egui::collapsing_header::CollapsingState::load_with_default_open(
+                    ui.ctx(),
+                    id,
+                    false,
+                )
+                .show_header(ui, |ui| changed = checkbox(ui, &mut visible).changed())
+                .body(|ui| child.ui(ui, &path, parent_visible && visible, state));
+                changed
+            };
+
+            if changed {
+                if visible {
+                    state.hidden_targets.remove(&path);
+                } else {
+                    state.hidden_targets.insert(path);
+                }
+            }
+        }
+    }
+}
+
+/// 按区间高亮文本
+fn append_highlighted(
+    job: &mut egui::text::LayoutJob,
+    text: &str,
+    ranges: &[Range<usize>],
+    format: &egui::text::TextFormat,
+    highlight: Color32,
+) {
+    let mut current = 0;
+    for range in ranges {
+        if current < range.start {
+            job.append(&text[current..range.start], 0.0, format.clone());
+        }
+        let mut highlighted = format.clone();
+        highlighted.background = highlight;
+        job.append(&text[range.clone()], 0.0, highlighted);
+        current = range.end;
+    }
+    if current < text.len() {
+        job.append(&text[current..], 0.0, format.clone());
+    }
 }
 
 // ============================================================================
@@ -211,17 +727,29 @@ pub struct LogPanel {
     show_toolbar: bool,
     /// 是否自动滚动到底部
     auto_scroll: bool,
+    /// 是否显示日志目标
+    show_target: bool,
+    /// 是否显示源位置 (file:line)
+    show_location: bool,
+    /// 是否显示线程名
+    show_thread: bool,
+    /// 淘汰的日志写入这个文件
+    spill_path: Option<PathBuf>,
 }
 
 impl Default for LogPanel {
     fn default() -> Self {
         Self {
             id: Id::new("egui_kit_log_panel"),
-            max_entries: 1000,
+            max_entries: DEFAULT_MAX_ENTRIES,
             font_size: 13.0,
             line_height: 19.0,
             show_toolbar: true,
             auto_scroll: true,
+            show_target: true,
+            show_location: false,
+            show_thread: false,
+            spill_path: None,
         }
     }
 }
@@ -238,12 +766,18 @@ impl LogPanel {
         self
     }
 
-    /// 设置最大日志条目数
+    /// 设置最大日志条目数 (默认 100_000), 超出时淘汰最旧的日志
     pub fn max_entries(mut self, max: usize) -> Self {
         self.max_entries = max;
         self
     }
 
+    /// 把淘汰的日志追加到文件 (只适用于 native), 避免丢失旧日志
+    pub fn spill_to_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.spill_path = Some(path.into());
+        self
+    }
+
     /// 设置字体大小
     pub fn font_size(mut self, size: f32) -> Self {
         self.font_size = size;
@@ -268,30 +802,58 @@ impl LogPanel {
         self
     }
 
+    /// 是否显示日志目标 (默认显示)
+    pub fn show_target(mut self, show: bool) -> Self {
+        self.show_target = show;
+        self
+    }
+
+    /// 是否显示源位置 file:line (默认不显示)
+    pub fn show_location(mut self, show: bool) -> Self {
+        self.show_location = show;
+        self
+    }
+
+    /// 是否显示线程名 (默认不显示)
+    pub fn show_thread(mut self, show: bool) -> Self {
+        self.show_thread = show;
+        self
+    }
+
     /// 显示日志面板
     pub fn show(self, ui: &mut Ui) -> LogPanelResponse {
         let cache_id = self.id.with("cache");
+        let view_id = self.id.with("view");
 
-        // 获取或创建日志缓存
-        let mut cache = ui
-            .ctx()
-            .memory_mut(|mem| {
-                mem.data
-                    .get_temp_mut_or_insert_with(cache_id, || LogCache::with_capacity(self.max_entries))
-                    .clone()
-            });
+        // 获取或创建日志缓存 (共享, 不复制)
+        let cache: SharedLogCache = ui.ctx().memory_mut(|mem| {
+            mem.data
+                .get_temp_mut_or_insert_with(cache_id, SharedLogCache::default)
+                .clone()
+        });
+        let mut cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+        // 过滤和选中状态 (同样共享)
+        let view: SharedLogView = ui.ctx().memory_mut(|mem| {
+            mem.data
+                .get_temp_mut_or_insert_with(view_id, SharedLogView::default)
+                .clone()
+        });
+        let mut view = view.lock().unwrap_or_else(|e| e.into_inner());
 
         // 从通道接收新日志
+        cache.max_entries = self.max_entries;
+        cache.set_spill_path(self.spill_path.clone());
         cache.drain_channel();
 
         let mut response = LogPanelResponse {
             cleared: false,
             copied: false,
             log_count: cache.len(),
+            visible_count: 0,
+            selected_count: 0,
         };
 
-        let mut scroll_top = false;
-        let mut scroll_bottom = false;
+        let mut copy = false;
 
         // 工具栏
         if self.show_toolbar {
@@ -299,88 +861,255 @@ impl LogPanel {
                 // 清空按钮
                 if ui.button("🗑 清空").clicked() {
                     cache.clear();
+                    view.selected.clear();
                     response.cleared = true;
                 }
 
-                ui.add_space(10.0);
-
-                // 复制按钮
+                // 复制按钮 (复制选中的日志, 没有选中时复制显示的日志)
                 if ui
                     .add_enabled(!cache.is_empty(), egui::Button::new("📋 复制"))
+                    .on_hover_text("复制选中的日志 (没有选中时复制全部显示的日志)")
                     .clicked()
                 {
-                    ui.ctx().copy_text(cache.to_text());
-                    response.copied = true;
+                    copy = true;
                 }
 
-                ui.add_space(10.0);
-
                 // 滚动按钮
                 if ui.button("⬆ 顶部").clicked() {
-                    scroll_top = true;
+                    view.pending_scroll = Some(0.0);
                 }
                 if ui.button("⬇ 底部").clicked() {
-                    scroll_bottom = true;
+                    view.pending_scroll = Some(f32::MAX);
                 }
 
-                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    ui.label(RichText::new(format!("共 {} 条", cache.len())).color(Color32::GRAY));
+                ui.separator();
+                self.level_toggles_ui(ui, &cache, &mut view);
+
+                ui.separator();
+                ui.menu_button("目标 ⏷", |ui| {
+                    ui.set_min_width(220.0);
+                    if ui.button("全部显示").clicked() {
+                        view.hidden_targets.clear();
+                    }
+                    ui.separator();
+                    ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                        cache.targets.ui(ui, "", true, &mut view);
+                    });
                 });
             });
 
+            ui.horizontal(|ui| {
+                ui.toggle_value(&mut view.regex, ".*")
+                    .on_hover_text("正则表达式");
+                view.search.search_field_ui_with_hint(ui, "搜索日志…");
+            });
+        }
+
+        // 过滤 (只检查新日志, 除非过滤条件变化)
+        cache.update_filter(&view);
+        if let LogSearch::InvalidRegex(err) = &cache.search {
+            ui.colored_label(
+                ui.visuals().error_fg_color,
+                format!("无效的正则表达式: {err}"),
+            );
+        }
+        view.prune_selection(cache.first_seq);
+        response.visible_count = cache.filtered.len();
+
+        // Ctrl+C 复制选中的日志 (没有控件获得焦点时)
+        if !view.selected.is_empty()
+            && ui.memory(|mem| mem.focused().is_none())
+            && ui.input(|i| i.events.iter().any(|e| matches!(e, egui::Event::Copy)))
+        {
+            copy = true;
+        }
+
+        if copy {
+            let text = if view.selected.is_empty() {
+                cache.lines_text(cache.filtered.iter().copied())
+            } else {
+                cache.lines_text(view.selected.iter().copied())
+            };
+            ui.ctx().copy_text(text);
+            response.copied = true;
+        }
+
+        if self.show_toolbar {
+            ui.horizontal(|ui| {
+                let visible = cache.filtered.len();
+                let mut count = if visible == cache.len() {
+                    format!("共 {} 条", cache.len())
+                } else {
+                    format!("显示 {} / 共 {} 条", visible, cache.len())
+                };
+                if !view.selected.is_empty() {
+                    count += &format!(", 选中 {} 条", view.selected.len());
+                }
+                ui.label(RichText::new(count).color(Color32::GRAY));
+            });
             ui.separator();
-            ui.add_space(5.0);
         }
 
-        // 日志内容区域
+        if cache.filtered.is_empty() {
+            ui.vertical_centered(|ui| {
+                ui.add_space(50.0);
+                let text = if cache.is_empty() {
+                    "暂无日志"
+                } else {
+                    "没有匹配的日志"
+                };
+                ui.label(RichText::new(text).color(Color32::GRAY).size(14.0));
+            });
+        } else {
+            self.rows_ui(ui, &cache, &mut view);
+        }
+
+        response.selected_count = view.selected.len();
+
+        response
+    }
+
+    /// 日志列表: 只布局可见的行
+    fn rows_ui(&self, ui: &mut Ui, cache: &LogCache, view: &mut LogViewState) {
         let mut font_id = egui::FontSelection::Default.resolve(ui.style());
         font_id.size = self.font_size;
 
         let base_text_format = egui::text::TextFormat {
             font_id,
             color: Color32::from_rgb(230, 230, 230),
-            line_height: Some(self.line_height),
             ..Default::default()
         };
+        let mut dim_format = base_text_format.clone();
+        dim_format.color = Color32::GRAY;
+        let highlight = ui.visuals().selection.bg_fill;
+        let selected_fill = ui.visuals().selection.bg_fill.gamma_multiply(0.5);
+        let hover_fill = ui.visuals().widgets.hovered.weak_bg_fill;
 
-        ScrollArea::vertical()
+        let mut scroll_area = ScrollArea::both()
             .auto_shrink([false, false])
-            .stick_to_bottom(self.auto_scroll)
-            .show(ui, |ui| {
-                if scroll_top {
-                    ui.scroll_to_cursor(Some(egui::Align::TOP));
-                }
-                if scroll_bottom {
-                    ui.scroll_to_cursor(Some(egui::Align::BOTTOM));
-                }
+            .stick_to_bottom(self.auto_scroll);
+        if let Some(offset) = view.pending_scroll.take() {
+            scroll_area = scroll_area.vertical_scroll_offset(offset);
+        }
 
-                if cache.is_empty() {
-                    ui.vertical_centered(|ui| {
-                        ui.add_space(50.0);
-                        ui.label(RichText::new("暂无日志").color(Color32::GRAY).size(14.0));
-                    });
-                } else {
-                    // 使用 LayoutJob 批量渲染日志 (性能优化)
-                    let mut job = egui::text::LayoutJob::default();
-                    job.wrap.break_anywhere = true;
+        let mut last_row = 0;
+        let output = ui
+            .scope(|ui| {
+                ui.spacing_mut().item_spacing.y = 0.0;
+                scroll_area.show_rows(ui, self.line_height, cache.filtered.len(), |ui, rows| {
+                    last_row = rows.end;
+                    let mut clicked = None;
+
+                    for &seq in &cache.filtered[rows] {
+                        let Some(entry) = cache.get(seq) else {
+                            continue;
+                        };
+
+                        let mut job = egui::text::LayoutJob::default();
+                        // 每条日志一行, 多行消息只显示第一行
+                        job.wrap.max_rows = 1;
+
+                        job.append(&entry.timestamp, 0.0, dim_format.clone());
+                        if self.show_thread
+                            && let Some(thread) = &entry.thread
+                        {
+                            job.append(&format!(" [{thread}]"), 0.0, dim_format.clone());
+                        }
+                        if self.show_target && !entry.target.is_empty() {
+                            job.append(&format!(" {}", entry.target), 0.0, dim_format.clone());
+                        }
+                        if self.show_location
+                            && let Some(location) = entry.location()
+                        {
+                            job.append(&format!(" ({location})"), 0.0, dim_format.clone());
+                        }
+                        job.append(" ", 0.0, base_text_format.clone());
 
-                    for entry in cache.iter() {
-                        let text = format!("{} {}\n", entry.timestamp, entry.message);
                         let mut text_format = base_text_format.clone();
                         text_format.color = entry.level_color();
-                        job.append(&text, 0.0, text_format);
+                        let ranges = cache.search.find(&entry.message).unwrap_or_default();
+                        append_highlighted(
+                            &mut job,
+                            &entry.message,
+                            &ranges,
+                            &text_format,
+                            highlight,
+                        );
+
+                        let galley = ui.fonts_mut(|fonts| fonts.layout_job(job));
+                        let width = galley.size().x.max(ui.available_width());
+                        let (rect, row_response) = ui.allocate_exact_size(
+                            egui::vec2(width, self.line_height),
+                            egui::Sense::click(),
+                        );
+
+                        if view.selected.contains(&seq) {
+                            ui.painter().rect_filled(rect, 0.0, selected_fill);
+                        } else if row_response.hovered() {
+                            ui.painter().rect_filled(rect, 0.0, hover_fill);
+                        }
+                        let text_pos = rect.left_top()
+                            + egui::vec2(0.0, (rect.height() - galley.size().y) / 2.0);
+                        ui.painter().galley(text_pos, galley, Color32::GRAY);
+
+                        if row_response.clicked() {
+                            clicked = Some(seq);
+                        }
+                        if entry.message.contains('\n') || entry.location().is_some() {
+                            row_response.on_hover_ui_at_pointer(|ui| {
+                                if let Some(location) = entry.location() {
+                                    ui.weak(location);
+                                }
+                                if let Some(thread) = &entry.thread {
+                                    ui.weak(format!("线程: {thread}"));
+                                }
+                                ui.label(&entry.message);
+                            });
+                        }
                     }
 
-                    ui.label(job);
-                }
-            });
+                    if let Some(seq) = clicked {
+                        let modifiers = ui.input(|i| i.modifiers);
+                        view.select(seq, &cache.filtered, modifiers);
+                    }
+                })
+            })
+            .inner;
+
+        if ui.rect_contains_pointer(output.inner_rect)
+            && ui.input(|i| i.key_pressed(egui::Key::Escape))
+        {
+            view.selected.clear();
+            view.selection_anchor = None;
+        }
 
-        // 保存缓存回 memory
-        ui.ctx().memory_mut(|mem| {
-            mem.data.insert_temp(cache_id, cache);
-        });
+        // 向上滚动后暂停自动滚动, 提示下方还有多少条日志
+        let max_offset = output.content_size.y - output.inner_rect.height();
+        let at_bottom = output.state.offset.y >= max_offset - 1.0;
+        let below = cache.filtered.len().saturating_sub(last_row);
+        if self.auto_scroll && !at_bottom && below > 0 {
+            let rect = egui::Rect::from_center_size(
+                output.inner_rect.center_bottom() - egui::vec2(0.0, 20.0),
+                egui::vec2(180.0, 24.0),
+            );
+            let button = egui::Button::new(format!("⬇ 回到底部 ({below} 条)"));
+            if ui.put(rect, button).on_hover_text("恢复自动滚动").clicked() {
+                view.pending_scroll = Some(f32::MAX);
+                ui.ctx().request_repaint();
+            }
+        }
+    }
 
-        response
+    /// 每个级别一个开关, 显示该级别的日志数
+    fn level_toggles_ui(&self, ui: &mut Ui, cache: &LogCache, view: &mut LogViewState) {
+        ui.spacing_mut().item_spacing.x = 2.0;
+        for level in ALL_LEVELS {
+            let index = LogViewState::level_index(level);
+            let color = level_color(level);
+            let text = RichText::new(format!("{level} {}", cache.level_counts[index])).color(color);
+            ui.toggle_value(&mut view.levels[index], text)
+                .on_hover_text(format!("显示 {level} 日志"));
+        }
     }
 }
 
@@ -392,6 +1121,10 @@ pub struct LogPanelResponse {
     pub copied: bool,
     /// 当前日志数量
     pub log_count: usize,
+    /// 过滤后显示的日志数量
+    pub visible_count: usize,
+    /// 选中的日志数量
+    pub selected_count: usize,
 }
 
 // ============================================================================
@@ -494,6 +1227,10 @@ impl Clone for LogPanel {
             line_height: self.line_height,
             show_toolbar: self.show_toolbar,
             auto_scroll: self.auto_scroll,
+            show_target: self.show_target,
+            show_location: self.show_location,
+            show_thread: self.show_thread,
+            spill_path: self.spill_path.clone(),
         }
     }
 }
@@ -511,3 +1248,124 @@ pub fn pending_log_count() -> usize {
 pub fn clear_pending_logs() {
     while log_receiver().try_recv().is_ok() {}
 }
+
+#[cfg(test)]
+mod tests {
+    #![expect(clippy::single_range_in_vec_init)]
+
+    use super::{LogCache, LogEntry, LogViewState, TargetNode};
+    use log::Level;
+
+    #[test]
+    fn test_log_filter() {
+        let entry = |level, target: &str, message: &str| {
+            LogEntry::new(level, message.to_owned()).with_target(target)
+        };
+        let entries = [
+            entry(Level::Info, "my_app::net", "Connected to 10.0.0.1"),
+            entry(Level::Warn, "my_app::net::retry", "Retrying request"),
+            entry(Level::Debug, "wgpu_core", "Adapter selected"),
+            entry(Level::Error, "my_app", "Request failed: timeout"),
+        ];
+
+        let mut view = LogViewState::default();
+        view.levels[LogViewState::level_index(Level::Debug)] = false;
+        view.hidden_targets.insert("my_app::net".to_owned());
+        let visible: Vec<_> = entries.iter().filter(|e| view.is_visible(e)).collect();
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].target, "my_app");
+
+        let view = LogViewState::default();
+        assert!(!view.is_target_hidden("my_app::network"));
+
+        let mut view = LogViewState::default();
+        view.search.activate("request");
+        let search = view.search();
+        assert_eq!(search.find("Retrying REQUEST"), Some(vec![9..16]));
+        assert_eq!(search.find("Connected"), None);
+
+        view.regex = true;
+        view.search.activate(r"\d+\.\d+");
+        let search = view.search();
+        assert_eq!(
+            search.find("Connected to 10.0.0.1"),
+            Some(vec![13..17, 18..21])
+        );
+
+        let mut tree = TargetNode::default();
+        for entry in &entries {
+            tree.add(&entry.target);
+        }
+        assert_eq!(tree.children["my_app"].count, 3);
+        assert_eq!(tree.children["my_app"].children["net"].count, 2);
+
+        tree.remove("my_app::net::retry");
+        assert_eq!(tree.count, 3);
+        assert_eq!(tree.children["my_app"].count, 2);
+        assert!(tree.children["my_app"].children["net"].children.is_empty());
+        tree.remove("wgpu_core");
+        assert!(!tree.children.contains_key("wgpu_core"));
+    }
+
+    #[test]
+    fn test_log_cache() {
+        let mut cache = LogCache::with_capacity(3);
+        for i in 0..5 {
+            let level = if i % 2 == 0 { Level::Info } else { Level::Warn };
+            cache.push(LogEntry::new(level, format!("message {i}")));
+        }
+
+        // 环形缓冲区: 淘汰最旧的日志, 序号不变
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.first_seq, 2);
+        assert_eq!(cache.get(1).map(|e| e.message.as_str()), None);
+        assert_eq!(cache.get(4).unwrap().message, "message 4");
+        assert_eq!(
+            cache.level_counts[LogViewState::level_index(Level::Info)],
+            2
+        );
+        assert_eq!(
+            cache.level_counts[LogViewState::level_index(Level::Warn)],
+            1
+        );
+        assert_eq!(cache.targets.count, 3);
+        assert_eq!(cache.targets.children[""].count, 3);
+
+        let mut view = LogViewState::default();
+        view.levels[LogViewState::level_index(Level::Warn)] = false;
+        cache.update_filter(&view);
+        assert_eq!(cache.filtered, [2, 4]);
+
+        // 只过滤新日志, 移除淘汰的序号
+        cache.push(LogEntry::new(Level::Info, "message 5".to_owned()));
+        cache.push(LogEntry::new(Level::Info, "message 6".to_owned()));
+        cache.update_filter(&view);
+        assert_eq!(cache.filtered, [4, 5, 6]);
+
+        // 过滤条件变化时重新过滤
+        view.search.activate("6");
+        cache.update_filter(&view);
+        assert_eq!(cache.filtered, [6]);
+
+        view.search = Default::default();
+        cache.update_filter(&view);
+        let modifiers = egui::Modifiers::default();
+        view.select(4, &cache.filtered, modifiers);
+        view.select(6, &cache.filtered, egui::Modifiers::SHIFT);
+        assert_eq!(view.selected.iter().copied().collect::<Vec<_>>(), [4, 5, 6]);
+        view.select(5, &cache.filtered, egui::Modifiers::COMMAND);
+        assert_eq!(
+            cache.lines_text(view.selected.iter().copied()),
+            format!(
+                "{} message 4\n{} message 6",
+                cache.get(4).unwrap().timestamp,
+                cache.get(6).unwrap().timestamp
+            )
+        );
+
+        cache.clear();
+        view.prune_selection(cache.first_seq);
+        assert!(view.selected.is_empty());
+        assert_eq!(cache.first_seq, 7);
+    }
+}
@@ -46,7 +46,6 @@ use tokio_util::sync::CancellationToken;
 pub type MaybePromise<T> = Option<Promise<T>>;
 
 /// 异步操作封装 (实现 Clone 以支持 egui::Memory 存储)
-#[derive(Clone)]
 pub struct Promise<T> {
     #[cfg(not(target_arch = "wasm32"))]
     inner: Arc<Mutex<PromiseInner<T>>>,
@@ -57,6 +56,17 @@ pub struct Promise<T> {
     inner: Arc<Mutex<PromiseState<T>>>,
 }
 
+// 手动实现: 共享同一个结果, 不要求 `T: Clone`
+impl<T> Clone for Promise<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            #[cfg(not(target_arch = "wasm32"))]
+            cancel_token: self.cancel_token.clone(),
+        }
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 struct PromiseInner<T> {
     receiver: Option<oneshot::Receiver<T>>,
@@ -144,6 +154,27 @@ impl<T: Send + 'static> Promise<T> {
         state.result = None;  // 清空可能已有的结果
     }
 
+    /// 获取取消句柄, 可以在其它地方 (如进度对话框的取消按钮) 取消任务.
+    ///
+    /// 句柄不持有结果, 也不影响 Drop 时的自动取消.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn canceller(&self) -> PromiseCanceller {
+        let cancel_token = self.cancel_token.clone();
+        PromiseCanceller(Arc::new(move || cancel_token.cancel()))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn canceller(&self) -> PromiseCanceller {
+        let inner = Arc::downgrade(&self.inner);
+        PromiseCanceller(Arc::new(move || {
+            if let Some(inner) = inner.upgrade() {
+                let mut state = inner.lock().unwrap();
+                state.cancelled = true;
+                state.result = None;
+            }
+        }))
+    }
+
     /// 非阻塞轮询结果
     pub fn poll(&mut self) -> Option<T> {
         #[cfg(not(target_arch = "wasm32"))]
@@ -205,6 +236,16 @@ impl<T: Send + 'static> Promise<T> {
     }
 }
 
+/// 取消 [`Promise`] 的句柄, 见 [`Promise::canceller`]
+#[derive(Clone)]
+pub struct PromiseCanceller(Arc<dyn Fn() + Send + Sync>);
+
+impl PromiseCanceller {
+    pub fn cancel(&self) {
+        (self.0)();
+    }
+}
+
 // 实现 Drop trait，自动取消异步任务
 #[cfg(not(target_arch = "wasm32"))]
 impl<T> Drop for Promise<T> {